@@ -0,0 +1,48 @@
+/*  Copyright (C) 2025 Saúl Valdelvira
+ *
+ *  This program is free software: you can redistribute it and/or modify
+ *  it under the terms of the GNU General Public License as published by
+ *  the Free Software Foundation, version 3.
+ *
+ *  This program is distributed in the hope that it will be useful,
+ *  but WITHOUT ANY WARRANTY; without even the implied warranty of
+ *  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ *  GNU General Public License for more details.
+ *
+ *  You should have received a copy of the GNU General Public License
+ *  along with this program.  If not, see <https://www.gnu.org/licenses/>. */
+
+#ifndef INTERNS_H
+#define INTERNS_H
+
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+typedef struct Interner Interner;
+
+typedef struct InternerSymbol {
+        uint32_t offset;
+        uint32_t len;
+} InternerSymbol;
+
+Interner* interner_new(void);
+
+bool interner_intern(Interner *interner, const char *ptr, size_t len, InternerSymbol *out);
+
+/* The returned string is NOT NUL-terminated. Returns NULL for unknown symbols */
+const char* interner_resolve(const Interner *interner, InternerSymbol sym, size_t *out_len);
+
+size_t interner_len(const Interner *interner);
+
+void interner_free(Interner *interner);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* INTERNS_H */
@@ -0,0 +1,85 @@
+use core::borrow::Borrow;
+use std::sync::Arc;
+
+use crate::backend::{get_index, index, repr, try_repr, Internable};
+use crate::{InternError, ResolveError};
+
+use super::Backend;
+
+/// Backend that stores every element inside an [Arc]
+///
+/// This allows resolved values to be shared with code that
+/// outlives the [Interner](crate::Interner), like detached tasks.
+///
+/// # Example
+/// ```
+/// use interns::{Interner, backend::ArcBackend};
+///
+/// let mut interner = Interner::<str, ArcBackend<str>>::new();
+/// let sym = interner.get_or_intern("hello");
+/// let shared = interner.resolve_arc(sym).unwrap();
+/// drop(interner);
+/// assert_eq!(&*shared, "hello");
+/// ```
+pub struct ArcBackend<T: ?Sized> {
+    buf: Vec<Arc<T>>,
+}
+
+impl<T: ?Sized> ArcBackend<T> {
+    /// Creates an empty backend
+    ///
+    /// This is a const version of [Default::default]
+    pub const fn new() -> Self {
+        Self { buf: Vec::new() }
+    }
+}
+
+impl<T: ?Sized> Default for ArcBackend<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: ?Sized> ArcBackend<T> {
+    /// Resolves the symbol into the [Arc] that holds its value
+    pub fn get_arc(&self, sym: Symbol) -> Option<&Arc<T>> {
+        self.buf.get(index(sym.0))
+    }
+}
+
+super::index_symbol! {
+    impl[T: ?Sized] ArcBackend<T>, buf;
+}
+
+impl<T: ?Sized> Backend<T> for ArcBackend<T> {
+    type Symbol = Symbol;
+
+    fn get(&self, sym: Self::Symbol) -> Option<&T> {
+        let val = self.buf.get(index(sym.0))?;
+        Some(val)
+    }
+
+    fn try_get(&self, sym: Self::Symbol) -> Result<&T, ResolveError> {
+        get_index(&self.buf, sym.0).map(|val| &**val)
+    }
+}
+
+impl<T, Ref> Internable<T, ArcBackend<T>> for Ref
+where
+    T: Borrow<Ref> + ?Sized,
+    Ref: ?Sized,
+    for<'a> Arc<T>: From<&'a Ref>,
+{
+    fn intern_into(&self, b: &mut ArcBackend<T>) -> Symbol {
+        let sym = Symbol(repr(b.buf.len()));
+        b.buf.push(Arc::from(self));
+        sym
+    }
+
+    fn try_intern_into(&self, b: &mut ArcBackend<T>) -> Result<Symbol, InternError> {
+        let sym = Symbol(try_repr(b.buf.len())?);
+        b.buf.try_reserve(1)?;
+        b.buf.push(Arc::from(self));
+        Ok(sym)
+    }
+}
@@ -0,0 +1,101 @@
+use core::hash::{BuildHasher, Hash};
+
+use typed_arena::Arena;
+
+use crate::backend::{get_index, index, repr, try_repr, Internable};
+use crate::{Backend, InternError, Interner, ResolveError};
+
+/// Backend that allocates each value inside a [typed_arena] [Arena]
+///
+/// Every value gets its own allocation in the arena, which is never moved
+/// nor freed while the arena lives. This makes the references returned by
+/// [resolve_arena](Interner::resolve_arena) stable, even as more values are
+/// interned, unlike a [VecBackend](crate::backend::VecBackend), which moves
+/// its values when it grows.
+///
+/// The arena is provided by the caller, and may be shared with other
+/// backends, or used to allocate values that aren't interned.
+///
+/// # Example
+/// ```
+/// use interns::Interner;
+/// use interns::backend::ArenaBackend;
+/// use typed_arena::Arena;
+///
+/// let arena = Arena::new();
+/// let mut interner = Interner::<Vec<u32>, ArenaBackend<Vec<u32>>>::with_backend(ArenaBackend::new(&arena));
+///
+/// let sym = interner.get_or_intern(&vec![1, 2, 3]);
+/// let first: &Vec<u32> = interner.resolve_arena(sym).unwrap();
+///
+/// for i in 0..100 {
+///     interner.get_or_intern(&vec![i]);
+/// }
+/// assert_eq!(first, &[1, 2, 3]);
+/// ```
+pub struct ArenaBackend<'arena, T> {
+    arena: &'arena Arena<T>,
+    values: Vec<&'arena T>,
+}
+
+impl<'arena, T> ArenaBackend<'arena, T> {
+    /// Creates an empty backend, that allocates its values on `arena`
+    pub const fn new(arena: &'arena Arena<T>) -> Self {
+        Self { arena, values: Vec::new() }
+    }
+
+    /// Returns the arena of this backend
+    pub fn arena(&self) -> &'arena Arena<T> {
+        self.arena
+    }
+
+    /// Resolves the symbol into a reference that lives as long as the arena
+    pub fn get_ref(&self, sym: Symbol) -> Option<&'arena T> {
+        self.values.get(index(sym.0)).copied()
+    }
+}
+
+super::index_symbol! {
+    impl[T] ArenaBackend<'_, T>, values;
+}
+
+impl<T> Backend<T> for ArenaBackend<'_, T> {
+    type Symbol = Symbol;
+
+    fn get(&self, sym: Self::Symbol) -> Option<&T> {
+        self.get_ref(sym)
+    }
+
+    fn try_get(&self, sym: Self::Symbol) -> Result<&T, ResolveError> {
+        get_index(&self.values, sym.0).map(|val| &**val)
+    }
+}
+
+impl<'arena, T: Clone> Internable<T, ArenaBackend<'arena, T>> for T {
+    fn intern_into(&self, b: &mut ArenaBackend<'arena, T>) -> Symbol {
+        let sym = Symbol(repr(b.values.len()));
+        b.values.push(b.arena.alloc(self.clone()));
+        sym
+    }
+
+    fn try_intern_into(&self, b: &mut ArenaBackend<'arena, T>) -> Result<Symbol, InternError> {
+        let sym = Symbol(try_repr(b.values.len())?);
+        b.values.try_reserve(1)?;
+        b.values.push(b.arena.alloc(self.clone()));
+        Ok(sym)
+    }
+}
+
+impl<'arena, T, H> Interner<T, ArenaBackend<'arena, T>, H>
+where
+    T: Hash + Eq,
+    H: BuildHasher,
+{
+    /// Resolves the [symbol](Backend::Symbol) into a reference that lives as long as the arena
+    ///
+    /// The reference remains valid while more values are interned,
+    /// and after the interner is dropped.
+    pub fn resolve_arena(&self, sym: Symbol) -> Option<&'arena T> {
+        self.backend.get_ref(sym)
+    }
+}
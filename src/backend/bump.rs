@@ -0,0 +1,127 @@
+use core::hash::{BuildHasher, Hash};
+
+use bumpalo::Bump;
+
+use crate::backend::{get_index, index, repr, try_repr, Internable};
+use crate::{Backend, InternError, Interner, ResolveError};
+
+/// Backend that allocates the values inside a [bumpalo] arena
+///
+/// The arena is provided by the caller, so the interned values live and
+/// die with it, along with the rest of the data allocated on it (e.g. the
+/// AST that the symbols name). [resolve_bump](Interner::resolve_bump) resolves
+/// a symbol into a reference tied to the arena, instead of the interner.
+///
+/// The arena never runs the destructors of its values, so the values
+/// interned into this backend are never dropped.
+///
+/// # Example
+/// ```
+/// use bumpalo::Bump;
+/// use interns::Interner;
+/// use interns::backend::BumpBackend;
+///
+/// let bump = Bump::new();
+/// let mut interner = Interner::<str, BumpBackend<str>>::with_backend(BumpBackend::new(&bump));
+/// let sym = interner.get_or_intern("hello");
+///
+/// let hello: &str = interner.resolve_bump(sym).unwrap();
+/// drop(interner);
+/// assert_eq!(hello, "hello");
+/// ```
+pub struct BumpBackend<'bump, T: ?Sized> {
+    bump: &'bump Bump,
+    values: Vec<&'bump T>,
+}
+
+impl<'bump, T: ?Sized> BumpBackend<'bump, T> {
+    /// Creates an empty backend, that allocates its values on `bump`
+    pub const fn new(bump: &'bump Bump) -> Self {
+        Self { bump, values: Vec::new() }
+    }
+
+    /// Returns the arena of this backend
+    pub fn bump(&self) -> &'bump Bump {
+        self.bump
+    }
+
+    /// Resolves the symbol into a reference that lives as long as the arena
+    pub fn get_ref(&self, sym: Symbol) -> Option<&'bump T> {
+        self.values.get(index(sym.0)).copied()
+    }
+
+    fn push(&mut self, value: &'bump T) -> Symbol {
+        let sym = Symbol(repr(self.values.len()));
+        self.values.push(value);
+        sym
+    }
+
+    fn try_push(&mut self, alloc: impl FnOnce(&'bump Bump) -> &'bump T) -> Result<Symbol, InternError> {
+        let sym = Symbol(try_repr(self.values.len())?);
+        self.values.try_reserve(1)?;
+        self.values.push(alloc(self.bump));
+        Ok(sym)
+    }
+}
+
+super::index_symbol! {
+    impl[T: ?Sized] BumpBackend<'_, T>, values;
+}
+
+impl<T: ?Sized> Backend<T> for BumpBackend<'_, T> {
+    type Symbol = Symbol;
+
+    fn get(&self, sym: Self::Symbol) -> Option<&T> {
+        self.get_ref(sym)
+    }
+
+    fn try_get(&self, sym: Self::Symbol) -> Result<&T, ResolveError> {
+        get_index(&self.values, sym.0).map(|val| &**val)
+    }
+}
+
+impl<'bump, T: Clone> Internable<T, BumpBackend<'bump, T>> for T {
+    fn intern_into(&self, b: &mut BumpBackend<'bump, T>) -> Symbol {
+        let value = b.bump.alloc(self.clone());
+        b.push(value)
+    }
+
+    fn try_intern_into(&self, b: &mut BumpBackend<'bump, T>) -> Result<Symbol, InternError> {
+        b.try_push(|bump| bump.alloc(self.clone()))
+    }
+}
+
+impl<'bump> Internable<str, BumpBackend<'bump, str>> for str {
+    fn intern_into(&self, b: &mut BumpBackend<'bump, str>) -> Symbol {
+        let value = b.bump.alloc_str(self);
+        b.push(value)
+    }
+
+    fn try_intern_into(&self, b: &mut BumpBackend<'bump, str>) -> Result<Symbol, InternError> {
+        b.try_push(|bump| bump.alloc_str(self))
+    }
+}
+
+impl<'bump, T: Copy> Internable<[T], BumpBackend<'bump, [T]>> for [T] {
+    fn intern_into(&self, b: &mut BumpBackend<'bump, [T]>) -> Symbol {
+        let value = b.bump.alloc_slice_copy(self);
+        b.push(value)
+    }
+
+    fn try_intern_into(&self, b: &mut BumpBackend<'bump, [T]>) -> Result<Symbol, InternError> {
+        b.try_push(|bump| bump.alloc_slice_copy(self))
+    }
+}
+
+impl<'bump, T, H> Interner<T, BumpBackend<'bump, T>, H>
+where
+    T: Hash + Eq + ?Sized,
+    H: BuildHasher,
+{
+    /// Resolves the [symbol](Backend::Symbol) into a reference that lives as long as the arena
+    ///
+    /// The reference remains valid after the interner is dropped.
+    pub fn resolve_bump(&self, sym: Symbol) -> Option<&'bump T> {
+        self.backend.get_ref(sym)
+    }
+}
@@ -0,0 +1,189 @@
+use core::borrow::Borrow;
+use core::hash::BuildHasher;
+use core::mem;
+use std::io::{self, Read};
+
+use bytes::{Bytes, BytesMut};
+
+use crate::backend::{get_index, index, try_repr, Internable};
+use crate::{Backend, InternError, Interner, ResolveError};
+
+/// Default size of each chunk
+const CHUNK_SIZE: usize = 4096;
+
+/// Backend that stores byte strings inside [Bytes] chunks
+///
+/// Values are appended to a shared chunk, and each one is kept as a
+/// [Bytes] slice of it. This means that resolving a value into an owned
+/// [Bytes] (see [Interner::resolve_bytes]) is O(1), and doesn't copy.
+///
+/// # Example
+/// ```
+/// use interns::Interner;
+/// use interns::backend::BytesBackend;
+///
+/// let mut interner = Interner::<[u8], BytesBackend>::new();
+/// let sym = interner.get_or_intern(&b"content-type"[..]);
+/// let bytes = interner.resolve_bytes(sym).unwrap();
+/// assert_eq!(bytes, &b"content-type"[..]);
+/// ```
+pub struct BytesBackend {
+    chunk: BytesMut,
+    chunk_size: usize,
+    values: Vec<Bytes>,
+}
+
+impl Default for BytesBackend {
+    fn default() -> Self {
+        Self::with_chunk_size(CHUNK_SIZE)
+    }
+}
+
+impl BytesBackend {
+    /// Creates a backend that allocates chunks of `chunk_size` bytes
+    ///
+    /// Values bigger than `chunk_size` get a chunk of their own.
+    pub fn with_chunk_size(chunk_size: usize) -> Self {
+        Self {
+            chunk: BytesMut::new(),
+            chunk_size,
+            values: Vec::new(),
+        }
+    }
+
+    /// Resolves the symbol into the [Bytes] that holds its value
+    pub fn get_bytes(&self, sym: Symbol) -> Option<&Bytes> {
+        self.values.get(index(sym.0))
+    }
+
+    fn push(&mut self, src: &[u8]) -> Result<Symbol, InternError> {
+        let sym = Symbol(try_repr(self.values.len())?);
+        self.values.try_reserve(1)?;
+        if self.chunk.capacity() < src.len() {
+            self.chunk = BytesMut::with_capacity(self.chunk_size.max(src.len()));
+        }
+        self.chunk.extend_from_slice(src);
+        /* `split` leaves the remaining capacity on `self.chunk`,
+         * so the next values share the same allocation */
+        let value = self.chunk.split().freeze();
+        self.values.push(value);
+        Ok(sym)
+    }
+
+    /// Stores a value that's already on a chunk
+    fn push_bytes(&mut self, value: Bytes) -> Result<Symbol, InternError> {
+        let sym = Symbol(try_repr(self.values.len())?);
+        self.values.try_reserve(1)?;
+        self.values.push(value);
+        Ok(sym)
+    }
+}
+
+super::index_symbol! {
+    impl[] BytesBackend, values;
+}
+
+impl Backend<[u8]> for BytesBackend {
+    type Symbol = Symbol;
+
+    fn get(&self, sym: Self::Symbol) -> Option<&[u8]> {
+        self.values.get(index(sym.0)).map(|b| &b[..])
+    }
+
+    fn try_get(&self, sym: Self::Symbol) -> Result<&[u8], ResolveError> {
+        get_index(&self.values, sym.0).map(|b| &b[..])
+    }
+}
+
+impl<T> Internable<[u8], BytesBackend> for T
+where
+    [u8]: Borrow<T>,
+    T: AsRef<[u8]> + ?Sized,
+{
+    /// # Panics
+    /// If the symbols overflow their [SymbolRepr](crate::backend::SymbolRepr)
+    fn intern_into(&self, b: &mut BytesBackend) -> Symbol {
+        match b.push(self.as_ref()) {
+            Ok(sym) => sym,
+            Err(err) => panic!("Couldn't intern value: {err}"),
+        }
+    }
+
+    fn try_intern_into(&self, b: &mut BytesBackend) -> Result<Symbol, InternError> {
+        b.push(self.as_ref())
+    }
+}
+
+impl<H: BuildHasher> Interner<[u8], BytesBackend, H> {
+    /// Resolves the [symbol](Backend::Symbol) into a [Bytes]
+    ///
+    /// The returned value shares the interner's storage, so this is O(1).
+    pub fn resolve_bytes(&self, sym: Symbol) -> Option<Bytes> {
+        self.backend.get_bytes(sym).cloned()
+    }
+
+    /// Gets the symbol for the content of `reader`, interning it if it doesn't exist
+    ///
+    /// The content is read directly at the end of the backend's current
+    /// chunk, so it's never buffered twice, and it's hashed only once. If it
+    /// was already interned, the space is reclaimed, and the existing symbol
+    /// is returned.
+    ///
+    /// If reading fails, nothing is interned.
+    ///
+    /// # Example
+    /// ```
+    /// use interns::Interner;
+    /// use interns::backend::BytesBackend;
+    ///
+    /// let mut interner = Interner::<[u8], BytesBackend>::new();
+    /// let a = interner.get_or_intern_reader(&b"blob contents"[..]).unwrap();
+    /// let b = interner.get_or_intern_reader(&b"blob contents"[..]).unwrap();
+    /// assert_eq!(a, b);
+    /// assert_eq!(interner.resolve(a), Some(&b"blob contents"[..]));
+    /// ```
+    pub fn get_or_intern_reader<R: Read>(&mut self, mut reader: R) -> io::Result<Symbol> {
+        let mut buf = mem::take(&mut self.backend.chunk);
+        if let Err(err) = read_to_end(&mut reader, &mut buf, self.backend.chunk_size) {
+            buf.clear();
+            self.backend.chunk = buf;
+            return Err(err)
+        }
+
+        /* The hash of a [u8] starts with its length, so it can't be fed
+         * while reading. It's computed once here, and reused to intern. */
+        let hash = self.hasher.hash_one(&buf[..]);
+        if let Some(sym) = self.find_hashed(hash, &buf[..]) {
+            self.record_request(sym, buf.len(), false);
+            /* Roll back, so the next value reuses the space */
+            buf.clear();
+            self.backend.chunk = buf;
+            return Ok(sym)
+        }
+
+        let value = buf.split().freeze();
+        self.backend.chunk = buf;
+        self.intern_hashed(hash, &value[..], |_, backend| backend.push_bytes(value.clone()))
+            .map_err(io::Error::other)
+    }
+}
+
+/// Reads all of `reader` at the end of `buf`, `chunk_size` bytes at a time
+///
+/// Unlike [io::copy], this reads directly into `buf`, without an intermediate buffer.
+fn read_to_end<R: Read>(reader: &mut R, buf: &mut BytesMut, chunk_size: usize) -> io::Result<()> {
+    let chunk_size = chunk_size.max(1);
+    loop {
+        let len = buf.len();
+        buf.resize(len + chunk_size, 0);
+        match reader.read(&mut buf[len..]) {
+            Ok(0) => {
+                buf.truncate(len);
+                return Ok(())
+            }
+            Ok(n) => buf.truncate(len + n),
+            Err(err) if err.kind() == io::ErrorKind::Interrupted => buf.truncate(len),
+            Err(err) => return Err(err),
+        }
+    }
+}
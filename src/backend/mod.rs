@@ -3,12 +3,42 @@
 use core::borrow::Borrow;
 use std::hash::Hash;
 
+use crate::collections::SymbolIndex;
+use crate::{InternError, InvariantError, ResolveError};
+
 pub mod string;
 pub use string::StringBackend;
 
 mod vec;
 pub use vec::VecBackend;
 
+mod arc;
+pub use arc::ArcBackend;
+
+pub mod span;
+pub use span::{SpanBackend, SpanRepr};
+
+#[cfg(feature = "bytes")]
+mod bytes;
+#[cfg(feature = "bytes")]
+pub use bytes::BytesBackend;
+
+#[cfg(feature = "bumpalo")]
+mod bump;
+#[cfg(feature = "bumpalo")]
+pub use bump::BumpBackend;
+
+#[cfg(feature = "typed-arena")]
+mod arena;
+#[cfg(feature = "typed-arena")]
+pub use arena::ArenaBackend;
+
+#[cfg(feature = "shm")]
+pub mod shm;
+
+#[cfg(feature = "sqlite")]
+pub mod sqlite;
+
 
 /// Allows to specify a default backend for some type
 ///
@@ -51,6 +81,113 @@ impl<T> DefaultBackendBuilder for [T] {
     }
 }
 
+/// Integer type of the index-based symbols
+///
+/// This is the representation of the symbols of the [VecBackend],
+/// [ArcBackend] and the rest of the backends that identify their values
+/// by index. It can be changed with the `symbol-u16`, `symbol-u32` and
+/// `symbol-u64` features. If more than one is enabled, the widest one is used.
+/// By default, it's [usize].
+///
+/// Smaller symbols save memory, but limit the number of values that can
+/// be interned. Interning more values than the representation allows
+/// panics, or fails with [InternError::Full] on the
+/// [fallible](crate::Interner::try_get_or_intern) API.
+///
+/// The [StringBackend] symbols are not affected.
+#[cfg(feature = "symbol-u64")]
+pub type SymbolRepr = u64;
+
+/// Integer type of the index-based symbols
+#[cfg(all(feature = "symbol-u32", not(feature = "symbol-u64")))]
+pub type SymbolRepr = u32;
+
+/// Integer type of the index-based symbols
+#[cfg(all(feature = "symbol-u16", not(any(feature = "symbol-u32", feature = "symbol-u64"))))]
+pub type SymbolRepr = u16;
+
+/// Integer type of the index-based symbols
+///
+/// This is the representation of the symbols of the [VecBackend],
+/// [ArcBackend] and the rest of the backends that identify their values
+/// by index. It can be changed with the `symbol-u16`, `symbol-u32` and
+/// `symbol-u64` features.
+#[cfg(not(any(feature = "symbol-u16", feature = "symbol-u32", feature = "symbol-u64")))]
+pub type SymbolRepr = usize;
+
+/// Converts an index into a [SymbolRepr]
+///
+/// Fails with [InternError::Full] if it doesn't fit.
+#[allow(clippy::useless_conversion, reason = "SymbolRepr may be usize")]
+pub(crate) fn try_repr(index: usize) -> Result<SymbolRepr, InternError> {
+    SymbolRepr::try_from(index).map_err(|_| InternError::Full)
+}
+
+/// Converts an index into a [SymbolRepr]
+///
+/// # Panics
+/// If the index doesn't fit
+#[track_caller]
+pub(crate) fn repr(index: usize) -> SymbolRepr {
+    match try_repr(index) {
+        Ok(repr) => repr,
+        Err(_) => panic!("Symbol overflow: can't intern more than {} values", SymbolRepr::MAX),
+    }
+}
+
+/// Gets the element at index `repr` of `slice`
+pub(crate) fn get_index<T>(slice: &[T], repr: SymbolRepr) -> Result<&T, ResolveError> {
+    let index = index(repr);
+    slice.get(index).ok_or(ResolveError::OutOfRange { index, len: slice.len() })
+}
+
+/// Converts a [SymbolRepr] back into an index
+///
+/// Values that don't fit in a [usize] can't be valid indices,
+/// so they are mapped to [usize::MAX].
+#[allow(
+    clippy::useless_conversion,
+    clippy::unnecessary_fallible_conversions,
+    reason = "SymbolRepr may be usize or u16"
+)]
+pub(crate) fn index(repr: SymbolRepr) -> usize {
+    usize::try_from(repr).unwrap_or(usize::MAX)
+}
+
+/// Defines the `Symbol` of a backend that identifies its values by index
+///
+/// Each `impl[generics] Backend, field;` implements [IndexBackend] for a
+/// backend whose values are on `self.field`, which must have a `len` method.
+macro_rules! index_symbol {
+    ($( impl[$($gen:tt)*] $backend:ty, $values:ident; )*) => {
+        #[derive(Clone, Copy, Debug, Hash, Eq, PartialEq, PartialOrd, Ord)]
+        #[repr(transparent)]
+        pub struct Symbol($crate::backend::SymbolRepr);
+
+        const _: () = assert!(size_of::<Symbol>() == size_of::<$crate::backend::SymbolRepr>());
+
+        impl $crate::collections::SymbolIndex for Symbol {
+            fn to_index(self) -> usize {
+                $crate::backend::index(self.0)
+            }
+
+            #[track_caller]
+            fn from_index(index: usize) -> Self {
+                Symbol($crate::backend::repr(index))
+            }
+        }
+
+        $(
+            impl<$($gen)*> $crate::backend::IndexBackend for $backend {
+                fn len(&self) -> usize {
+                    self.$values.len()
+                }
+            }
+        )*
+    };
+}
+use index_symbol;
+
 /// All the constraints for a [Symbol](Backend::Symbol)
 pub trait BackendSymbol: Clone + Copy + Hash + Eq + PartialEq {}
 impl<T> BackendSymbol for T where T: Clone + Copy + Hash + Eq + PartialEq {}
@@ -68,9 +205,28 @@ pub trait Backend<T: ?Sized> {
         src.intern_into(self)
     }
 
+    /// Intern an element into `self`, returning an error if it
+    /// can't be stored
+    fn try_intern<B>(&mut self, src: &B) -> Result<Self::Symbol, InternError>
+    where
+        T: Borrow<B>,
+        B: Internable<T, Self> + ?Sized,
+    {
+        src.try_intern_into(self)
+    }
+
     /// Resolve the symbol
     fn get(&self, sym: Self::Symbol) -> Option<&T>;
 
+    /// Resolve the symbol, returning an error that describes
+    /// why it failed
+    ///
+    /// The default implementation returns [ResolveError::Unknown]
+    /// if [get](Self::get) fails.
+    fn try_get(&self, sym: Self::Symbol) -> Result<&T, ResolveError> {
+        self.get(sym).ok_or(ResolveError::Unknown)
+    }
+
     /// Resolves the symbol, without checking if it exists on
     /// the backend.
     ///
@@ -83,6 +239,104 @@ pub trait Backend<T: ?Sized> {
         /* SAFETY: the caller ensures that the symbol is valid for `self` */
         unsafe { val.unwrap_unchecked() }
     }
+
+    /// Returns the position of the value of `sym` on the backend's storage
+    ///
+    /// This is only used for debugging output, like [Interner::dump](crate::Interner::dump).
+    /// The default implementation returns None.
+    fn offset_of(&self, sym: Self::Symbol) -> Option<usize> {
+        let _ = sym;
+        None
+    }
+
+    /// Checks the internal consistency of the backend
+    ///
+    /// Called by [Interner::check_invariants](crate::Interner::check_invariants).
+    /// The default implementation doesn't check anything.
+    fn check_invariants(&self) -> Result<(), InvariantError> {
+        Ok(())
+    }
+}
+
+/// A [Backend] that numbers its symbols densely
+///
+/// The symbols are mapped to the indices `0..n`, where `n` is the number of
+/// values on the backend, and the mapping never changes as more values are
+/// added. This allows keeping side tables as plain [Vec]s, indexed with
+/// [index_of](Self::index_of), regardless of the backend's symbol type.
+///
+/// Index-based backends, like the [VecBackend], are dense by construction.
+/// A [StringBackend] only keeps track of its symbols if it's
+/// [validating](StringBackend::validating). Otherwise, both methods return None.
+///
+/// # Example
+/// ```
+/// use interns::{StringBackend, StringInterner};
+///
+/// let mut interner = StringInterner::with_backend(StringBackend::validating());
+/// let mut types = Vec::new();
+///
+/// let x = interner.get_or_intern("x");
+/// types.push("int");
+/// let y = interner.get_or_intern("y");
+/// types.push("bool");
+///
+/// assert_eq!(interner.index_of(y), Some(1));
+/// assert_eq!(types[interner.index_of(x).unwrap()], "int");
+/// assert_eq!(interner.symbol_at(1), Some(y));
+/// ```
+pub trait DenseSymbols<T: ?Sized>: Backend<T> {
+    /// Returns the index of `sym`, or None if it doesn't belong to the backend
+    fn index_of(&self, sym: Self::Symbol) -> Option<usize>;
+
+    /// Returns the symbol with index `idx`, or None if it's out of range
+    fn symbol_at(&self, idx: usize) -> Option<Self::Symbol>;
+}
+
+/// A [Backend] whose symbols are the [indices](SymbolIndex) of its values
+///
+/// Implemented with [index_symbol]. It makes the backend [DenseSymbols]
+/// and [IterableBackend].
+pub(crate) trait IndexBackend {
+    /// Returns the number of values on the backend
+    fn len(&self) -> usize;
+}
+
+impl<T, B> DenseSymbols<T> for B
+where
+    T: ?Sized,
+    B: Backend<T, Symbol: SymbolIndex> + IndexBackend,
+{
+    fn index_of(&self, sym: B::Symbol) -> Option<usize> {
+        let i = sym.to_index();
+        (i < self.len()).then_some(i)
+    }
+
+    fn symbol_at(&self, idx: usize) -> Option<B::Symbol> {
+        (idx < self.len()).then(|| B::Symbol::from_index(idx))
+    }
+}
+
+/// A [Backend] that can list the symbols of all its values
+///
+/// This allows rebuilding the interner's deduplication set from the backend
+/// alone, with [rebuild_index](crate::Interner::rebuild_index).
+///
+/// The [StringBackend] doesn't implement this trait, since it doesn't keep
+/// track of where each string starts and ends.
+pub trait IterableBackend<T: ?Sized>: Backend<T> {
+    /// Returns the symbols of every value on the backend, in the order they were added
+    fn symbols(&self) -> impl Iterator<Item = Self::Symbol> + '_;
+}
+
+impl<T, B> IterableBackend<T> for B
+where
+    T: ?Sized,
+    B: Backend<T, Symbol: SymbolIndex + 'static> + IndexBackend,
+{
+    fn symbols(&self) -> impl Iterator<Item = B::Symbol> + '_ {
+        (0..self.len()).map(B::Symbol::from_index)
+    }
 }
 
 /// Defines how to intern a type into a [Backend]
@@ -101,5 +355,13 @@ where
     B: Backend<T> + ?Sized,
 {
     fn intern_into(&self, b: &mut B) -> B::Symbol;
+
+    /// Fallible version of [intern_into](Self::intern_into)
+    ///
+    /// The default implementation never fails. Backends that may
+    /// panic while interning should override it.
+    fn try_intern_into(&self, b: &mut B) -> Result<B::Symbol, InternError> {
+        Ok(self.intern_into(b))
+    }
 }
 
@@ -0,0 +1,346 @@
+//! Shared-memory backend
+//!
+//! This backend stores its strings in a memory-mapped file, so that
+//! several processes can share one string table. Placing the file on
+//! a tmpfs (like `/dev/shm` on Linux) keeps it entirely in memory.
+//!
+//! # Synchronization protocol
+//!
+//! There's a single writer: the process that [creates](ShmBackend::create)
+//! the segment. Any number of processes can [open](ShmReader::open) it for reading.
+//!
+//! The segment has a fixed size, with space for `max_entries` spans and
+//! `capacity` bytes of string data. The header contains the number of
+//! published entries. To intern a string, the writer:
+//!
+//! 1. Copies the bytes after the end of the last published string
+//! 2. Writes the span of the string in the next free slot of the table
+//! 3. Increments the entry count, with [Release] ordering
+//!
+//! Readers load the entry count with [Acquire] ordering, and only access
+//! entries below it. Published entries are never modified, so readers never
+//! observe a partially written string.
+//!
+//! Readers don't have a dedup table of their own until they call
+//! [Interner::refresh](crate::Interner::refresh), which indexes
+//! the entries published since the last call.
+//!
+//! # Example
+//! ```
+//! use interns::Interner;
+//! use interns::backend::shm::{ShmBackend, ShmReader};
+//!
+//! let path = std::env::temp_dir().join(format!("interns-doc-{}", std::process::id()));
+//!
+//! let writer = ShmBackend::create(&path, 1024, 64 * 1024).unwrap();
+//! let mut writer = Interner::<str, ShmBackend>::with_backend(writer);
+//! let hello = writer.get_or_intern("hello");
+//!
+//! /* In another process */
+//! let reader = unsafe { ShmReader::open(&path).unwrap() };
+//! let mut reader = Interner::<str, ShmReader>::with_backend(reader);
+//! reader.refresh();
+//! assert_eq!(reader.resolve(hello), Some("hello"));
+//! assert_eq!(reader.get("hello"), Some(hello));
+//! # std::fs::remove_file(&path).unwrap();
+//! ```
+//!
+//! [Release]: Ordering::Release
+//! [Acquire]: Ordering::Acquire
+
+use core::borrow::Borrow;
+use core::hash::BuildHasher;
+use core::slice;
+use core::sync::atomic::{AtomicU64, Ordering};
+use std::fs::{File, OpenOptions};
+use std::io;
+use std::path::Path;
+
+use memmap2::{Mmap, MmapMut};
+
+use crate::backend::{index, repr, try_repr, Internable};
+use crate::{Backend, InternError, Interner, ResolveError};
+
+const MAGIC: u64 = u64::from_le_bytes(*b"INTRNSHM");
+const HEADER_LEN: usize = 32;
+const SPAN_LEN: usize = 16;
+
+/* Header layout (u64 words):
+ *   0: MAGIC
+ *   1: max_entries
+ *   2: capacity (in bytes)
+ *   3: number of published entries (atomic)
+ */
+
+super::index_symbol! {
+    impl[] ShmBackend, segment;
+    impl[] ShmReader, segment;
+}
+
+/// Read-only view of a mapped segment
+struct Segment {
+    base: *const u8,
+    max_entries: usize,
+    capacity: usize,
+}
+
+impl Segment {
+    /// # Safety
+    /// `base` must point to a segment of at least [Self::size] bytes,
+    /// for the `max_entries` and `capacity` of its header
+    unsafe fn new(base: *const u8, max_entries: usize, capacity: usize) -> Self {
+        Self { base, max_entries, capacity }
+    }
+
+    /// Returns the size of a segment, or None if it overflows
+    fn size(max_entries: usize, capacity: usize) -> Option<usize> {
+        max_entries
+            .checked_mul(SPAN_LEN)?
+            .checked_add(HEADER_LEN)?
+            .checked_add(capacity)
+    }
+
+    fn count(&self) -> &AtomicU64 {
+        /* SAFETY: The header is 8-byte aligned, since mmaps are page aligned */
+        unsafe { &*(self.base.add(24) as *const AtomicU64) }
+    }
+
+    fn span_ptr(&self, i: usize) -> *const u64 {
+        /* SAFETY: i < max_entries, so the pointer is inside the segment */
+        unsafe { self.base.add(HEADER_LEN + i * SPAN_LEN) as *const u64 }
+    }
+
+    fn data_ptr(&self) -> *const u8 {
+        /* SAFETY: The data region is inside the segment */
+        unsafe { self.base.add(HEADER_LEN + self.max_entries * SPAN_LEN) }
+    }
+
+    /// Number of published entries
+    ///
+    /// The count is clamped to `max_entries`, since the
+    /// file may have been modified by another process.
+    fn len(&self) -> usize {
+        let count = self.count().load(Ordering::Acquire);
+        usize::try_from(count).map_or(self.max_entries, |count| count.min(self.max_entries))
+    }
+
+    fn span(&self, i: usize) -> (usize, usize) {
+        let span = self.span_ptr(i);
+        /* SAFETY: The span was published before the entry count that covers it */
+        unsafe { (*span as usize, *span.add(1) as usize) }
+    }
+
+    /// Number of bytes used by the published entries
+    fn data_len(&self) -> usize {
+        match self.len() {
+            0 => 0,
+            n => {
+                let (offset, len) = self.span(n - 1);
+                offset.saturating_add(len)
+            }
+        }
+    }
+
+    fn get(&self, sym: Symbol) -> Option<&str> {
+        self.try_get(sym).ok()
+    }
+
+    fn try_get(&self, sym: Symbol) -> Result<&str, ResolveError> {
+        let i = index(sym.0);
+        let n = self.len();
+        if i >= n {
+            return Err(ResolveError::OutOfRange { index: i, len: n })
+        }
+        let (offset, len) = self.span(i);
+        if offset.checked_add(len).is_none_or(|end| end > self.capacity) {
+            return Err(ResolveError::InvalidSpan { offset, len })
+        }
+        /* SAFETY: We've checked that the span is inside the data region */
+        let bytes = unsafe { slice::from_raw_parts(self.data_ptr().add(offset), len) };
+        /* The file can be written by other processes, so
+         * its contents can't be trusted to be valid UTF-8 */
+        core::str::from_utf8(bytes).map_err(|_| ResolveError::InvalidSpan { offset, len })
+    }
+}
+
+/// Writer side of a shared string table
+///
+/// See the [module-level documentation](self) for more details.
+pub struct ShmBackend {
+    _map: MmapMut,
+    segment: Segment,
+}
+
+/* SAFETY: The raw pointer of `segment` points into `_map`, which we own */
+unsafe impl Send for ShmBackend {}
+unsafe impl Sync for ShmBackend {}
+
+impl ShmBackend {
+    /// Creates a new segment at `path`, with space for `max_entries`
+    /// strings with a total size of `capacity` bytes
+    ///
+    /// If the file exists, it's truncated.
+    ///
+    /// # Errors
+    /// Besides the errors of creating and mapping the file, fails with
+    /// [InvalidInput](io::ErrorKind::InvalidInput) if the size of the
+    /// segment overflows.
+    pub fn create<P: AsRef<Path>>(path: P, max_entries: usize, capacity: usize) -> io::Result<Self> {
+        let size = Segment::size(max_entries, capacity)
+            .and_then(|size| u64::try_from(size).ok())
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "segment size overflows"))?;
+        let file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .open(path)?;
+        file.set_len(size)?;
+        /* SAFETY: We've just created the file. Other processes must only
+         * access it through ShmReader */
+        let mut map = unsafe { MmapMut::map_mut(&file)? };
+
+        let base = map.as_mut_ptr();
+        let header = base as *mut u64;
+        /* SAFETY: The map is big enough to hold the header */
+        let segment = unsafe {
+            *header.add(1) = max_entries as u64;
+            *header.add(2) = capacity as u64;
+            *header.add(3) = 0;
+            *header = MAGIC;
+            Segment::new(base, max_entries, capacity)
+        };
+        Ok(Self { _map: map, segment })
+    }
+
+    /// Number of free bytes left in the segment
+    pub fn remaining_capacity(&self) -> usize {
+        self.segment.capacity.saturating_sub(self.segment.data_len())
+    }
+
+    fn push(&mut self, s: &str) -> Result<Symbol, InternError> {
+        let n = self.segment.len();
+        let offset = self.segment.data_len();
+        if n >= self.segment.max_entries || s.len() > self.remaining_capacity() {
+            return Err(InternError::Full)
+        }
+        let sym = Symbol(try_repr(n)?);
+
+        /* SAFETY: We've checked that both the data and the span fit in the segment.
+         * The unpublished part of the segment is only accessed by the writer. */
+        unsafe {
+            let data = self.segment.data_ptr().cast_mut();
+            data.add(offset).copy_from_nonoverlapping(s.as_ptr(), s.len());
+            let span = self.segment.span_ptr(n).cast_mut();
+            *span = offset as u64;
+            *span.add(1) = s.len() as u64;
+        }
+        self.segment.count().store(n as u64 + 1, Ordering::Release);
+        Ok(sym)
+    }
+}
+
+impl Backend<str> for ShmBackend {
+    type Symbol = Symbol;
+
+    fn get(&self, sym: Symbol) -> Option<&str> {
+        self.segment.get(sym)
+    }
+
+    fn try_get(&self, sym: Symbol) -> Result<&str, ResolveError> {
+        self.segment.try_get(sym)
+    }
+}
+
+impl<T> Internable<str, ShmBackend> for T
+where
+    str: Borrow<T>,
+    T: AsRef<str> + ?Sized,
+{
+    /// # Panics
+    /// If the segment is full
+    fn intern_into(&self, b: &mut ShmBackend) -> Symbol {
+        match b.push(self.as_ref()) {
+            Ok(sym) => sym,
+            Err(_) => panic!("Shared memory segment is full"),
+        }
+    }
+
+    fn try_intern_into(&self, b: &mut ShmBackend) -> Result<Symbol, InternError> {
+        b.push(self.as_ref())
+    }
+}
+
+/// Reader side of a shared string table
+///
+/// See the [module-level documentation](self) for more details.
+pub struct ShmReader {
+    _map: Mmap,
+    segment: Segment,
+}
+
+/* SAFETY: The raw pointer of `segment` points into `_map`, which we own */
+unsafe impl Send for ShmReader {}
+unsafe impl Sync for ShmReader {}
+
+impl ShmReader {
+    /// Opens a segment created with [ShmBackend::create]
+    ///
+    /// # Safety
+    /// The file must have been created by [ShmBackend::create], and must
+    /// not be modified by anything other than that [ShmBackend]
+    pub unsafe fn open<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let file = File::open(path)?;
+        /* SAFETY: The caller ensures that the file is only modified by the ShmBackend */
+        let map = unsafe { Mmap::map(&file)? };
+        if map.len() < HEADER_LEN {
+            return Err(io::Error::new(io::ErrorKind::InvalidData, "segment too small"))
+        }
+        let header = map.as_ptr() as *const u64;
+        /* SAFETY: The map is big enough to hold the header */
+        let (magic, max_entries, capacity) = unsafe { (*header, *header.add(1), *header.add(2)) };
+        let invalid = || io::Error::new(io::ErrorKind::InvalidData, "invalid segment");
+        if magic != MAGIC {
+            return Err(invalid())
+        }
+        let max_entries = usize::try_from(max_entries).map_err(|_| invalid())?;
+        let capacity = usize::try_from(capacity).map_err(|_| invalid())?;
+        if Segment::size(max_entries, capacity).is_none_or(|size| map.len() < size) {
+            return Err(invalid())
+        }
+        /* SAFETY: We've checked that the map is big enough for its header */
+        let segment = unsafe { Segment::new(map.as_ptr(), max_entries, capacity) };
+        Ok(Self { _map: map, segment })
+    }
+
+    /// Number of entries published by the writer
+    pub fn published(&self) -> usize {
+        self.segment.len()
+    }
+}
+
+impl Backend<str> for ShmReader {
+    type Symbol = Symbol;
+
+    fn get(&self, sym: Symbol) -> Option<&str> {
+        self.segment.get(sym)
+    }
+
+    fn try_get(&self, sym: Symbol) -> Result<&str, ResolveError> {
+        self.segment.try_get(sym)
+    }
+}
+
+impl<H: BuildHasher> Interner<str, ShmReader, H> {
+    /// Indexes the entries published by the writer since the last call
+    ///
+    /// Returns the number of new entries
+    pub fn refresh(&mut self) -> usize {
+        let start = self.len();
+        let end = self.backend.published();
+        for i in start..end {
+            self.insert_symbol(Symbol(repr(i)));
+        }
+        end - start
+    }
+}
@@ -0,0 +1,161 @@
+//! Backend for custom unsized types
+//!
+//! A [SpanBackend] stores its values as bytes, in a single buffer. How a
+//! value is written into the buffer, and how it's read back, is described
+//! by a [SpanRepr]. This allows interning user-defined unsized types, like
+//! a custom identifier or a WTF-8 string type.
+//!
+//! # Example
+//! ```
+//! use interns::Interner;
+//! use interns::backend::span::{SpanBackend, SpanRepr};
+//!
+//! #[derive(Debug, Hash, PartialEq, Eq)]
+//! #[repr(transparent)]
+//! struct Ident(str);
+//!
+//! impl Ident {
+//!     fn new(s: &str) -> &Ident {
+//!         /* SAFETY: Ident is a transparent wrapper over str */
+//!         unsafe { &*(s as *const str as *const Ident) }
+//!     }
+//! }
+//!
+//! struct IdentRepr;
+//!
+//! impl SpanRepr for IdentRepr {
+//!     type Target = Ident;
+//!
+//!     fn encode(value: &Ident, buf: &mut Vec<u8>) {
+//!         buf.extend_from_slice(value.0.as_bytes());
+//!     }
+//!
+//!     fn decode(bytes: &[u8]) -> Option<&Ident> {
+//!         std::str::from_utf8(bytes).ok().map(Ident::new)
+//!     }
+//! }
+//!
+//! let mut interner = Interner::<Ident, SpanBackend<IdentRepr>>::new();
+//! let foo = interner.get_or_intern(Ident::new("foo"));
+//! assert_eq!(interner.get_or_intern(Ident::new("foo")), foo);
+//! assert_eq!(interner.resolve(foo), Some(Ident::new("foo")));
+//! ```
+
+use core::marker::PhantomData;
+
+use crate::backend::{index, try_repr, Internable, IterableBackend, SymbolRepr};
+use crate::{Backend, DenseSymbols, InternError, ResolveError};
+
+/// Describes how to store a type in the buffer of a [SpanBackend]
+pub trait SpanRepr {
+    /// The type stored in the backend
+    type Target: ?Sized;
+
+    /// Appends the bytes of `value` to `buf`
+    fn encode(value: &Self::Target, buf: &mut Vec<u8>);
+
+    /// Reads back a value written by [encode](Self::encode)
+    ///
+    /// `bytes` are exactly the bytes written by `encode`. The buffer
+    /// has no alignment guarantees. Returns None if the bytes aren't
+    /// a valid value.
+    fn decode(bytes: &[u8]) -> Option<&Self::Target>;
+}
+
+/// Backend that stores values in a byte buffer, as described by `R`
+///
+/// See the [module-level documentation](self) for more details.
+pub struct SpanBackend<R: SpanRepr> {
+    buf: Vec<u8>,
+    /// Spans of the values, in the order they were added
+    spans: Vec<Span>,
+    _repr: PhantomData<fn() -> R>,
+}
+
+impl<R: SpanRepr> SpanBackend<R> {
+    /// Creates an empty backend
+    ///
+    /// This is a const version of [Default::default]
+    pub const fn new() -> Self {
+        Self {
+            buf: Vec::new(),
+            spans: Vec::new(),
+            _repr: PhantomData,
+        }
+    }
+}
+
+impl<R: SpanRepr> Default for SpanBackend<R> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[derive(Clone, Copy, Debug, Hash, Eq, PartialEq, PartialOrd, Ord)]
+pub struct Span {
+    start: SymbolRepr,
+    len: SymbolRepr,
+}
+
+impl<R: SpanRepr> Backend<R::Target> for SpanBackend<R> {
+    type Symbol = Span;
+
+    fn get(&self, sym: Span) -> Option<&R::Target> {
+        self.try_get(sym).ok()
+    }
+
+    fn try_get(&self, sym: Span) -> Result<&R::Target, ResolveError> {
+        let (offset, len) = (index(sym.start), index(sym.len));
+        offset
+            .checked_add(len)
+            .and_then(|end| self.buf.get(offset..end))
+            .and_then(R::decode)
+            .ok_or(ResolveError::InvalidSpan { offset, len })
+    }
+}
+
+impl<R: SpanRepr> DenseSymbols<R::Target> for SpanBackend<R> {
+    fn index_of(&self, sym: Span) -> Option<usize> {
+        /* The spans are added in increasing order */
+        self.spans.binary_search(&sym).ok()
+    }
+
+    fn symbol_at(&self, idx: usize) -> Option<Span> {
+        self.spans.get(idx).copied()
+    }
+}
+
+impl<R: SpanRepr> IterableBackend<R::Target> for SpanBackend<R> {
+    fn symbols(&self) -> impl Iterator<Item = Span> + '_ {
+        self.spans.iter().copied()
+    }
+}
+
+impl<R: SpanRepr> Internable<R::Target, SpanBackend<R>> for R::Target {
+    /// # Panics
+    /// If the buffer grows past what a [SymbolRepr] can address
+    fn intern_into(&self, b: &mut SpanBackend<R>) -> Span {
+        match self.try_intern_into(b) {
+            Ok(sym) => sym,
+            Err(err) => panic!("Couldn't intern value: {err}"),
+        }
+    }
+
+    fn try_intern_into(&self, b: &mut SpanBackend<R>) -> Result<Span, InternError> {
+        let start = b.buf.len();
+        let start_repr = try_repr(start)?;
+        b.spans.try_reserve(1)?;
+        R::encode(self, &mut b.buf);
+        let span = try_repr(b.buf.len() - start).map(|len| Span { start: start_repr, len });
+        match span {
+            Ok(span) => {
+                b.spans.push(span);
+                Ok(span)
+            }
+            Err(err) => {
+                b.buf.truncate(start);
+                Err(err)
+            }
+        }
+    }
+}
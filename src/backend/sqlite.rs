@@ -0,0 +1,314 @@
+//! SQLite-backed backend
+//!
+//! [SqliteBackend] stores every interned string as a row of an `interns`
+//! table, so symbols are stable across runs, and can be referenced from
+//! other tables of the same database.
+//!
+//! ```sql
+//! CREATE TABLE interns (
+//!     id    INTEGER PRIMARY KEY,
+//!     value TEXT NOT NULL UNIQUE
+//! );
+//! ```
+//!
+//! The values are also cached in memory, so resolving a symbol never
+//! queries the database. Only interning a new value does.
+//!
+//! The interner's deduplication set must resolve every symbol it holds, so
+//! a [SqliteBackend] loads all the rows of the table. For big databases, a
+//! [SqliteLruInterner] keeps only the recently used values in memory.
+//!
+//! # Example
+//! ```
+//! use interns::Interner;
+//! use interns::backend::sqlite::SqliteBackend;
+//!
+//! let path = std::env::temp_dir().join(format!("interns-doc-{}.db", std::process::id()));
+//!
+//! let mut interner = Interner::<str, SqliteBackend>::open_sqlite(&path).unwrap();
+//! let hello = interner.get_or_intern("hello");
+//! drop(interner);
+//!
+//! let interner = Interner::<str, SqliteBackend>::open_sqlite(&path).unwrap();
+//! assert_eq!(interner.get("hello"), Some(hello));
+//! assert_eq!(interner.resolve(hello), Some("hello"));
+//! # std::fs::remove_file(&path).unwrap();
+//! ```
+
+use core::borrow::Borrow;
+use core::hash::BuildHasher;
+use std::collections::HashMap;
+use std::path::Path;
+
+use rusqlite::{Connection, OptionalExtension};
+
+use crate::backend::{Internable, IterableBackend};
+use crate::lru::{LruInterner, LruSymbol};
+use crate::{Backend, InternError, Interner};
+
+/// Creates the `interns` table, if it doesn't exist
+fn create_table(conn: &Connection) -> rusqlite::Result<()> {
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS interns (
+            id    INTEGER PRIMARY KEY,
+            value TEXT NOT NULL UNIQUE
+        )",
+        (),
+    )?;
+    Ok(())
+}
+
+/// Inserts `value` into the `interns` table, and returns its id
+fn insert(conn: &Connection, value: &str) -> rusqlite::Result<i64> {
+    /* Another connection may have inserted the value already,
+     * in that case we reuse its id */
+    conn.prepare_cached("INSERT OR IGNORE INTO interns (value) VALUES (?1)")?
+        .execute([value])?;
+    conn.prepare_cached("SELECT id FROM interns WHERE value = ?1")?
+        .query_row([value], |row| row.get(0))
+}
+
+#[derive(Clone, Copy, Debug, Hash, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(transparent)]
+pub struct Symbol(i64);
+
+impl Symbol {
+    /// Returns the id of the row that holds this symbol's value
+    pub const fn id(&self) -> i64 {
+        self.0
+    }
+
+    /// Builds a symbol from the id of a row of the `interns` table
+    pub const fn from_id(id: i64) -> Self {
+        Self(id)
+    }
+}
+
+/// Backend that persists its values on a SQLite database
+///
+/// See the [module-level documentation](self) for more details.
+pub struct SqliteBackend {
+    conn: Connection,
+    cache: HashMap<i64, Box<str>>,
+}
+
+impl SqliteBackend {
+    /// Opens the database at `path`, creating it if it doesn't exist
+    pub fn open<P: AsRef<Path>>(path: P) -> rusqlite::Result<Self> {
+        Self::from_connection(Connection::open(path)?)
+    }
+
+    /// Creates a backend on a new in-memory database
+    pub fn open_in_memory() -> rusqlite::Result<Self> {
+        Self::from_connection(Connection::open_in_memory()?)
+    }
+
+    /// Creates a backend from an existing connection
+    ///
+    /// The `interns` table is created if it doesn't exist, and all
+    /// its rows are loaded into memory.
+    pub fn from_connection(conn: Connection) -> rusqlite::Result<Self> {
+        create_table(&conn)?;
+        let cache = conn
+            .prepare("SELECT id, value FROM interns")?
+            .query_map((), |row| Ok((row.get(0)?, row.get::<_, String>(1)?.into_boxed_str())))?
+            .collect::<rusqlite::Result<_>>()?;
+        Ok(Self { conn, cache })
+    }
+
+    /// Returns the underlying connection
+    pub fn connection(&self) -> &Connection {
+        &self.conn
+    }
+
+    fn insert(&mut self, value: &str) -> rusqlite::Result<Symbol> {
+        let id = insert(&self.conn, value)?;
+        self.cache.insert(id, value.into());
+        Ok(Symbol(id))
+    }
+}
+
+impl Backend<str> for SqliteBackend {
+    type Symbol = Symbol;
+
+    fn get(&self, sym: Symbol) -> Option<&str> {
+        self.cache.get(&sym.0).map(|s| &**s)
+    }
+}
+
+impl IterableBackend<str> for SqliteBackend {
+    /// Returns the symbols of all the values loaded into memory, by id
+    fn symbols(&self) -> impl Iterator<Item = Symbol> + '_ {
+        let mut ids: Vec<_> = self.cache.keys().copied().collect();
+        ids.sort_unstable();
+        ids.into_iter().map(Symbol)
+    }
+}
+
+impl<T> Internable<str, SqliteBackend> for T
+where
+    str: Borrow<T>,
+    T: AsRef<str> + ?Sized,
+{
+    /// # Panics
+    /// If the value can't be inserted into the database
+    fn intern_into(&self, b: &mut SqliteBackend) -> Symbol {
+        match b.insert(self.as_ref()) {
+            Ok(sym) => sym,
+            Err(err) => panic!("Couldn't insert into the database: {err}"),
+        }
+    }
+
+    fn try_intern_into(&self, b: &mut SqliteBackend) -> Result<Symbol, InternError> {
+        b.insert(self.as_ref()).map_err(|err| InternError::Backend(Box::new(err)))
+    }
+}
+
+impl<H: BuildHasher + Default> Interner<str, SqliteBackend, H> {
+    /// Opens the database at `path`, and indexes all its values
+    pub fn open_sqlite<P: AsRef<Path>>(path: P) -> rusqlite::Result<Self> {
+        SqliteBackend::open(path).map(Self::from_sqlite)
+    }
+
+    /// Builds an interner from a [SqliteBackend], indexing all its values
+    pub fn from_sqlite(backend: SqliteBackend) -> Self {
+        let mut interner = Self::with_backend(backend);
+        interner.rebuild_index();
+        interner
+    }
+}
+
+/// Interner over a SQLite database, that keeps only the recently used values in memory
+///
+/// The values are cached on a [LruInterner]. When a symbol is resolved and its
+/// value was evicted, it's read back from the database. Symbols are the ids of
+/// the rows, like the ones of a [SqliteBackend], so both can share a database.
+///
+/// # Example
+/// ```
+/// use interns::backend::sqlite::SqliteLruInterner;
+/// use rusqlite::Connection;
+///
+/// let mut interner = SqliteLruInterner::from_connection(Connection::open_in_memory().unwrap(), 2).unwrap();
+/// let a = interner.get_or_intern("a").unwrap();
+/// interner.get_or_intern("b").unwrap();
+/// interner.get_or_intern("c").unwrap();
+/// assert_eq!(interner.cached(), 2);
+///
+/// /* "a" was evicted, and is read from the database */
+/// assert_eq!(interner.resolve(a).unwrap(), Some("a"));
+/// assert_eq!(interner.get("a").unwrap(), Some(a));
+/// ```
+pub struct SqliteLruInterner {
+    conn: Connection,
+    cache: LruInterner<str>,
+    /// Symbol on the cache of each id
+    ids: HashMap<i64, LruSymbol>,
+    /// Id of the value held by each slot of the cache
+    slots: Vec<Option<i64>>,
+}
+
+impl SqliteLruInterner {
+    /// Opens the database at `path`, creating it if it doesn't exist
+    ///
+    /// At most `max_entries` values are kept in memory.
+    ///
+    /// # Panics
+    /// If `max_entries` is 0
+    pub fn open<P: AsRef<Path>>(path: P, max_entries: usize) -> rusqlite::Result<Self> {
+        Self::from_connection(Connection::open(path)?, max_entries)
+    }
+
+    /// Creates an interner from an existing connection
+    ///
+    /// The `interns` table is created if it doesn't exist. No
+    /// rows are loaded until they're used.
+    ///
+    /// # Panics
+    /// If `max_entries` is 0
+    pub fn from_connection(conn: Connection, max_entries: usize) -> rusqlite::Result<Self> {
+        let cache = LruInterner::new(max_entries);
+        create_table(&conn)?;
+        Ok(Self { conn, cache, ids: HashMap::new(), slots: Vec::new() })
+    }
+
+    /// Returns the underlying connection
+    pub fn connection(&self) -> &Connection {
+        &self.conn
+    }
+
+    /// Gets the symbol for `value`, inserting it into the database if it doesn't exist
+    pub fn get_or_intern(&mut self, value: &str) -> rusqlite::Result<Symbol> {
+        if let Some(id) = self.get_cached(value) {
+            return Ok(Symbol(id))
+        }
+        let id = insert(&self.conn, value)?;
+        self.cache_value(id, value);
+        Ok(Symbol(id))
+    }
+
+    /// Gets the symbol for `value`, without inserting it
+    pub fn get(&mut self, value: &str) -> rusqlite::Result<Option<Symbol>> {
+        if let Some(id) = self.get_cached(value) {
+            return Ok(Some(Symbol(id)))
+        }
+        let id: Option<i64> = self
+            .conn
+            .prepare_cached("SELECT id FROM interns WHERE value = ?1")?
+            .query_row([value], |row| row.get(0))
+            .optional()?;
+        if let Some(id) = id {
+            self.cache_value(id, value);
+        }
+        Ok(id.map(Symbol))
+    }
+
+    /// Resolves the symbol, reading its value from the database if it's not cached
+    ///
+    /// Returns None if there's no row with the symbol's id.
+    pub fn resolve(&mut self, sym: Symbol) -> rusqlite::Result<Option<&str>> {
+        if let Some(&lru) = self.ids.get(&sym.0)
+            && self.cache.touch(lru)
+        {
+            return Ok(self.cache.resolve(lru))
+        }
+        let value: Option<String> = self
+            .conn
+            .prepare_cached("SELECT value FROM interns WHERE id = ?1")?
+            .query_row([sym.0], |row| row.get(0))
+            .optional()?;
+        let Some(value) = value else { return Ok(None) };
+        let lru = self.cache_value(sym.0, &value);
+        Ok(self.cache.resolve(lru))
+    }
+
+    /// Returns the number of values in memory
+    pub fn cached(&self) -> usize {
+        self.cache.len()
+    }
+
+    /// Returns the id of `value` if it's cached, and marks it as used
+    fn get_cached(&mut self, value: &str) -> Option<i64> {
+        let lru = self.cache.get(value)?;
+        self.cache.touch(lru);
+        self.slots[lru.index() as usize]
+    }
+
+    /// Adds the value of the row `id` to the cache
+    fn cache_value(&mut self, id: i64, value: &str) -> LruSymbol {
+        let lru = self.cache.get_or_intern(value);
+        let index = lru.index() as usize;
+        if index >= self.slots.len() {
+            self.slots.resize(index + 1, None);
+        }
+        /* The slot may have held an evicted value. Its id is forgotten,
+         * unless it was cached again on another slot. */
+        if let Some(old) = self.slots[index].replace(id)
+            && self.ids.get(&old).is_some_and(|sym| sym.index() as usize == index)
+        {
+            self.ids.remove(&old);
+        }
+        self.ids.insert(id, lru);
+        lru
+    }
+}
@@ -1,32 +1,80 @@
 use core::borrow::Borrow;
+use core::cmp::Ordering;
 use core::hash::BuildHasher;
 use core::mem::MaybeUninit;
+use std::collections::TryReserveError;
 
-use hashbrown::hash_map::RawEntryMut;
+#[cfg(feature = "zeroize")]
+use zeroize::Zeroize;
 
 use crate::backend::Internable;
-use crate::{Backend, Interner, StringInterner};
+use crate::escape::{self, EscapeRules};
+use crate::{Backend, DenseSymbols, EscapeError, InternError, Interner, InvariantError, ResolveError, StringInterner};
 
 struct Span {
     pub offset: usize,
     pub len: usize,
 }
 
+/// State of a [StringBackend], returned by [StringBackend::mark]
+pub(crate) struct Mark {
+    buf: usize,
+    spans: usize,
+    bounds: usize,
+    max_len: usize,
+}
+
+/// Symbol of a [StringBackend]
+///
+/// # Encoding
+/// Most symbols are *inlined*: they store the position of the string inside
+/// the backend's buffer directly. This is only possible while the string starts
+/// within the first 4 GiB of the buffer, and is shorter than [u32::MAX] bytes.
+///
+/// Otherwise, the symbol is *indexed*: `len` is [u32::MAX], and `offset` is an
+/// index into a table with the real position of the string. A backend can hold
+/// up to [u32::MAX] indexed strings.
+///
+/// The buffer is append-only, so inlined symbols stay valid as the buffer grows
+/// past 4 GiB. Only the strings that start after that point are indexed.
 #[derive(Clone, Copy, Debug, Hash, Eq, PartialEq, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Symbol {
     pub offset: u32,
     pub len: u32,
 }
 
 impl Symbol {
-    pub const fn from_usize(val: usize) -> Self {
+    /// Decodes a symbol encoded with [as_u64](Self::as_u64)
+    pub const fn from_u64(val: u64) -> Self {
         Self {
             offset: (val >> 32) as u32,
-            len: (val & !( (!0) << 32 ) ) as u32,
+            len: val as u32,
         }
     }
+
+    /// Encodes this symbol into a single [u64]
+    pub const fn as_u64(&self) -> u64 {
+        ((self.offset as u64) << 32) | self.len as u64
+    }
+
+    /// Decodes a symbol encoded with [as_usize](Self::as_usize)
+    ///
+    /// Only available on 64-bit targets, since a symbol
+    /// doesn't fit in a 32-bit [usize].
+    #[cfg(target_pointer_width = "64")]
+    pub const fn from_usize(val: usize) -> Self {
+        Self::from_u64(val as u64)
+    }
+
+    /// Encodes this symbol into a single [usize]
+    ///
+    /// Only available on 64-bit targets, since a symbol
+    /// doesn't fit in a 32-bit [usize].
+    /// Use [as_u64](Self::as_u64) for portable code.
+    #[cfg(target_pointer_width = "64")]
     pub const fn as_usize(&self) -> usize {
-        ((self.offset as usize) << 32) | self.len as usize
+        self.as_u64() as usize
     }
 
     pub const fn is_inlined(&self) -> bool {
@@ -40,16 +88,403 @@ impl Symbol {
     pub const fn new_indexed(index: usize) -> Self {
         Self { offset: index as u32, len: u32::MAX }
     }
+
+    /// Builds an inlined symbol, if `offset` and `len` fit in it
+    const fn try_inline(offset: usize, len: usize) -> Option<Self> {
+        if offset <= u32::MAX as usize && len < u32::MAX as usize {
+            Some(Self::new_inlined(offset as u32, len as u32))
+        } else {
+            None
+        }
+    }
 }
 
 /// Backend for strings
-#[derive(Default)]
+///
+/// By default, inlined symbols are resolved without any checks, so a
+/// fabricated symbol resolves to an arbitrary slice of the buffer (as
+/// long as it's a valid [str]). A [validating](Self::validating) backend
+/// rejects symbols that it didn't produce.
+///
+/// With the `zeroize` feature, the buffer is wiped when the backend is
+/// dropped, and when it's reallocated to grow.
 pub struct StringBackend {
     buf: String,
     spans: Vec<Span>,
+    /// Inlined symbols produced by this backend, in increasing order
+    bounds: Option<Vec<Symbol>>,
+    /// Length of the longest string
+    max_len: usize,
 }
 
 impl StringBackend {
+    /// Creates an empty backend
+    ///
+    /// This is a const version of [Default::default]
+    pub const fn new() -> Self {
+        Self {
+            buf: String::new(),
+            spans: Vec::new(),
+            bounds: None,
+            max_len: 0,
+        }
+    }
+
+    /// Creates a backend that keeps track of the symbols it produces,
+    /// and only resolves those
+    ///
+    /// This costs 8 extra bytes per interned string, and a binary
+    /// search on every resolve.
+    ///
+    /// # Example
+    /// ```
+    /// use interns::{StringBackend, StringInterner};
+    /// use interns::backend::string::Symbol;
+    ///
+    /// let mut interner = StringInterner::with_backend(StringBackend::validating());
+    /// let hello = interner.get_or_intern("hello");
+    /// assert_eq!(interner.resolve(hello), Some("hello"));
+    ///
+    /// let fabricated = Symbol::new_inlined(1, 3);
+    /// assert_eq!(interner.resolve(fabricated), None);
+    /// ```
+    pub fn validating() -> Self {
+        Self {
+            buf: String::new(),
+            spans: Vec::new(),
+            bounds: Some(Vec::new()),
+            max_len: 0,
+        }
+    }
+
+    /// Returns true if this backend was created with [validating](Self::validating)
+    pub fn is_validating(&self) -> bool {
+        self.bounds.is_some()
+    }
+
+    fn record(&mut self, sym: Symbol) {
+        if let Some(bounds) = &mut self.bounds
+            && sym.is_inlined()
+        {
+            debug_assert!(bounds.last().is_none_or(|last| *last < sym));
+            bounds.push(sym);
+        }
+    }
+
+    /// Reserves space for at least `additional` more bytes on the buffer
+    ///
+    /// With the `zeroize` feature, the old buffer is wiped before
+    /// it's freed, if it needs to be reallocated.
+    fn try_reserve(&mut self, additional: usize) -> Result<(), TryReserveError> {
+        #[cfg(feature = "zeroize")]
+        if self.buf.capacity() - self.buf.len() < additional {
+            let capacity = self.buf.len().saturating_add(additional).max(self.buf.capacity() * 2);
+            let mut buf = String::new();
+            buf.try_reserve_exact(capacity)?;
+            buf.push_str(&self.buf);
+            self.buf.zeroize();
+            self.buf = buf;
+        }
+        self.buf.try_reserve(additional)
+    }
+
+    fn reserve(&mut self, additional: usize) {
+        if let Err(err) = self.try_reserve(additional) {
+            panic!("{err}");
+        }
+    }
+
+    /// Makes the end of the buffer, starting at `offset`, a new string
+    ///
+    /// On error, the backend is left as it was, but the
+    /// caller must remove the tail from the buffer.
+    fn commit_tail(&mut self, offset: usize) -> Result<Symbol, InternError> {
+        let len = self.buf.len() - offset;
+
+        /* Reserve everything upfront, so we don't leave the backend
+         * in an inconsistent state if an allocation fails */
+        if let Some(bounds) = &mut self.bounds {
+            bounds.try_reserve(1)?;
+        }
+
+        let sym = match Symbol::try_inline(offset, len) {
+            Some(sym) => sym,
+            None => {
+                let index = match u32::try_from(self.spans.len()) {
+                    Ok(u32::MAX) | Err(_) => return Err(InternError::Full),
+                    Ok(index) => index,
+                };
+                self.spans.try_reserve(1)?;
+                self.spans.push(Span { offset, len });
+                Symbol { offset: index, len: u32::MAX }
+            }
+        };
+        self.max_len = self.max_len.max(len);
+        self.record(sym);
+        Ok(sym)
+    }
+
+    /// Returns the length of the longest string on the backend
+    pub(crate) fn max_len(&self) -> usize {
+        self.max_len
+    }
+
+    pub(crate) fn buf_len(&self) -> usize {
+        self.buf.len()
+    }
+
+    /// Returns the current state of the backend, to [roll back](Self::rollback) to it
+    pub(crate) fn mark(&self) -> Mark {
+        Mark {
+            buf: self.buf.len(),
+            spans: self.spans.len(),
+            bounds: self.bounds.as_ref().map_or(0, Vec::len),
+            max_len: self.max_len,
+        }
+    }
+
+    /// Removes everything pushed since `mark` was taken
+    pub(crate) fn rollback(&mut self, mark: Mark) {
+        self.buf.truncate(mark.buf);
+        self.spans.truncate(mark.spans);
+        if let Some(bounds) = &mut self.bounds {
+            bounds.truncate(mark.bounds);
+        }
+        self.max_len = mark.max_len;
+    }
+
+    /// Returns the symbols of the strings that start at or past `offset`,
+    /// sorted by position
+    ///
+    /// Returns None if the backend is not [validating](Self::validating),
+    /// since it doesn't keep track of its inlined symbols.
+    pub(crate) fn symbols_from(&self, offset: usize) -> Option<Vec<Symbol>> {
+        let bounds = self.bounds.as_ref()?;
+        let inlined = &bounds[bounds.partition_point(|b| (b.offset as usize) < offset)..];
+        let first = self.spans.partition_point(|span| span.offset < offset);
+        let indexed = self.spans[first..]
+            .iter()
+            .enumerate()
+            .map(|(i, span)| (span.offset, span.len, Symbol::new_indexed(first + i)));
+        let mut symbols = inlined
+            .iter()
+            .map(|&sym| (sym.offset as usize, sym.len as usize, sym))
+            .chain(indexed)
+            .collect::<Vec<_>>();
+        symbols.sort_unstable_by_key(|&(offset, len, _)| (offset, len));
+        Some(symbols.into_iter().map(|(_, _, sym)| sym).collect())
+    }
+
+    /// Pushes `value` at `offset`, and checks that it's resolved by `sym`
+    ///
+    /// The buffer is padded if `offset` is past its end. Returns false
+    /// if `offset` has already been filled, or if `sym` is not the
+    /// symbol that would have been assigned to the value.
+    pub(crate) fn push_at(&mut self, offset: usize, sym: Symbol, value: &str) -> bool {
+        if offset < self.buf.len() {
+            return false;
+        }
+        let valid = if sym.is_inlined() {
+            sym.offset as usize == offset && sym.len as usize == value.len()
+        } else {
+            sym.offset as usize == self.spans.len()
+        };
+        if !valid {
+            return false;
+        }
+        let padding = offset - self.buf.len();
+        self.reserve(padding.saturating_add(value.len()));
+        self.buf.extend(core::iter::repeat_n('\0', padding));
+        self.buf.push_str(value);
+        self.max_len = self.max_len.max(value.len());
+        if !sym.is_inlined() {
+            self.spans.push(Span { offset, len: value.len() });
+        }
+        self.record(sym);
+        true
+    }
+}
+
+impl Default for StringBackend {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl Zeroize for StringBackend {
+    /// Wipes the contents of the buffer, and removes all the strings
+    fn zeroize(&mut self) {
+        self.buf.zeroize();
+        self.spans.clear();
+        self.max_len = 0;
+        if let Some(bounds) = &mut self.bounds {
+            bounds.clear();
+        }
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl Drop for StringBackend {
+    fn drop(&mut self) {
+        self.buf.zeroize();
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl zeroize::ZeroizeOnDrop for StringBackend {}
+
+impl Backend<str> for StringBackend {
+    type Symbol = Symbol;
+
+    /// Returns the position of `sym`'s value inside the buffer
+    fn offset_of(&self, sym: Symbol) -> Option<usize> {
+        if sym.is_inlined() {
+            Some(sym.offset as usize)
+        } else {
+            self.spans.get(sym.offset as usize).map(|span| span.offset)
+        }
+    }
+
+    fn get(&self, sym: Symbol) -> Option<&str> {
+        let (offset, len) = if sym.is_inlined() {
+            if let Some(bounds) = &self.bounds {
+                bounds.binary_search(&sym).ok()?;
+            }
+            (sym.offset as usize, sym.len as usize)
+        } else {
+            let span = self.spans.get(sym.offset as usize)?;
+            (span.offset, span.len)
+        };
+        self.buf.get(offset..offset.checked_add(len)?)
+    }
+
+    fn try_get(&self, sym: Symbol) -> Result<&str, ResolveError> {
+        let (offset, len) = if sym.is_inlined() {
+            (sym.offset as usize, sym.len as usize)
+        } else {
+            let index = sym.offset as usize;
+            let span = self.spans.get(index).ok_or(ResolveError::OutOfRange {
+                index,
+                len: self.spans.len(),
+            })?;
+            (span.offset, span.len)
+        };
+        let invalid = ResolveError::InvalidSpan { offset, len };
+        if let Some(bounds) = &self.bounds
+            && sym.is_inlined()
+            && bounds.binary_search(&sym).is_err()
+        {
+            return Err(invalid)
+        }
+        offset
+            .checked_add(len)
+            .and_then(|end| self.buf.get(offset..end))
+            .ok_or(invalid)
+    }
+
+    fn check_invariants(&self) -> Result<(), InvariantError> {
+        let check_span = |what: &str, offset: usize, len: usize| {
+            let valid = offset
+                .checked_add(len)
+                .is_some_and(|end| self.buf.get(offset..end).is_some());
+            if valid {
+                Ok(())
+            } else {
+                Err(InvariantError::new(format!("{what} ({offset}, {len}) isn't a valid slice of the buffer")))
+            }
+        };
+
+        let mut end = 0;
+        for span in &self.spans {
+            check_span("span", span.offset, span.len)?;
+            if span.offset < end {
+                return Err(InvariantError::new(format!("span at offset {} overlaps the previous one", span.offset)))
+            }
+            end = span.offset + span.len;
+        }
+
+        if let Some(bounds) = &self.bounds {
+            let mut prev: Option<Symbol> = None;
+            for sym in bounds {
+                check_span("symbol", sym.offset as usize, sym.len as usize)?;
+                if let Some(prev) = prev
+                    && prev.offset as usize + prev.len as usize > sym.offset as usize
+                {
+                    return Err(InvariantError::new(format!("symbol at offset {} overlaps the previous one", sym.offset)))
+                }
+                prev = Some(*sym);
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Finds the `i` in `0..len` with `rank(i) == target`
+///
+/// `rank` must be strictly increasing.
+fn find_rank(len: usize, target: usize, rank: impl Fn(usize) -> usize) -> Option<usize> {
+    let (mut lo, mut hi) = (0, len);
+    while lo < hi {
+        let mid = lo + (hi - lo) / 2;
+        match rank(mid).cmp(&target) {
+            Ordering::Less => lo = mid + 1,
+            Ordering::Equal => return Some(mid),
+            Ordering::Greater => hi = mid,
+        }
+    }
+    None
+}
+
+/// Strings are numbered by their position in the buffer. Inlined and
+/// indexed symbols are kept in separate tables, both ordered by offset,
+/// so an index is the position of the symbol on its own table, plus the
+/// number of symbols of the other table that come before it.
+impl DenseSymbols<str> for StringBackend {
+    fn index_of(&self, sym: Symbol) -> Option<usize> {
+        let bounds = self.bounds.as_ref()?;
+        if sym.is_inlined() {
+            let i = bounds.binary_search(&sym).ok()?;
+            let offset = sym.offset as usize;
+            Some(i + self.spans.partition_point(|span| span.offset <= offset))
+        } else {
+            let i = sym.offset as usize;
+            let offset = self.spans.get(i)?.offset;
+            Some(i + bounds.partition_point(|b| (b.offset as usize) < offset))
+        }
+    }
+
+    fn symbol_at(&self, idx: usize) -> Option<Symbol> {
+        let bounds = self.bounds.as_ref()?;
+        let inlined = find_rank(bounds.len(), idx, |i| {
+            let offset = bounds[i].offset as usize;
+            i + self.spans.partition_point(|span| span.offset <= offset)
+        });
+        if let Some(i) = inlined {
+            return Some(bounds[i])
+        }
+        find_rank(self.spans.len(), idx, |i| {
+            let offset = self.spans[i].offset;
+            i + bounds.partition_point(|b| (b.offset as usize) < offset)
+        })
+        .map(Symbol::new_indexed)
+    }
+}
+
+/// A string backend that can hold the symbols of a [StringInternerBuilder]
+///
+/// The builder assigns [indexed](Symbol) symbols to its strings, so the
+/// backend must be able to store them under those symbols.
+pub trait Prefill: Backend<str, Symbol = Symbol> {
+    /// Stores `strings` under their symbols
+    ///
+    /// # Panics
+    /// If the backend isn't empty, or a symbol isn't
+    /// the one that the backend would assign
+    fn prefill(&mut self, strings: &[(&str, Symbol)]);
+}
+
+impl Prefill for StringBackend {
     fn prefill(&mut self, strings: &[(&str, Symbol)]) {
         assert!(self.spans.is_empty());
         for (string, expected_sym) in strings {
@@ -57,28 +492,31 @@ impl StringBackend {
                 offset: self.buf.len(),
                 len: string.len(),
             };
+            self.reserve(string.len());
             self.buf.push_str(string);
             let n = self.spans.len();
             self.spans.push(span);
+            self.max_len = self.max_len.max(string.len());
             let sym = Symbol::new_indexed(n);
             assert_eq!(sym, *expected_sym);
         }
     }
 }
 
-impl Backend<str> for StringBackend {
-    type Symbol = Symbol;
-
-    fn get(&self, sym: Symbol) -> Option<&str> {
-        let (offset, len) = if sym.is_inlined() {
-            (sym.offset as usize, sym.len as usize)
-        } else {
-            let span = self.spans.get(sym.offset as usize)?;
-            (span.offset, span.len)
-        };
-        let src = &self.buf[offset..offset + len];
-        Some(src)
+/// Compares two strings in a const context
+const fn const_str_eq(a: &str, b: &str) -> bool {
+    let (a, b) = (a.as_bytes(), b.as_bytes());
+    if a.len() != b.len() {
+        return false
     }
+    let mut i = 0;
+    while i < a.len() {
+        if a[i] != b[i] {
+            return false
+        }
+        i += 1;
+    }
+    true
 }
 
 /// A helper struct to build prefilled interners
@@ -108,40 +546,295 @@ pub struct StringInternerBuilder<const N: usize>([(&'static str, Symbol); N]);
 
 impl<const N: usize> StringInternerBuilder<N> {
     /// Builds a [StringInternerBuilder] with the given const symbols
+    ///
+    /// # Panics
+    /// If a string appears more than once. When evaluated in a const
+    /// context, this makes the build fail.
+    ///
+    /// ```compile_fail
+    /// use interns::backend::string::StringInternerBuilder;
+    ///
+    /// const BUILDER: StringInternerBuilder<3> = StringInternerBuilder::with_const_symbols([
+    ///     "if", "else", "if"
+    /// ]);
+    /// ```
     pub const fn with_const_symbols(predefined: [&str; N]) -> Self {
         let mut result: [MaybeUninit<(&str, Symbol)>; N] = [ const { MaybeUninit::uninit() }; N ];
         let mut i = 0;
         while i < N {
+            let mut j = 0;
+            while j < i {
+                if const_str_eq(predefined[i], predefined[j]) {
+                    panic!("duplicate string in StringInternerBuilder");
+                }
+                j += 1;
+            }
             result[i] = MaybeUninit::new((predefined[i], Symbol::new_indexed(i)));
             i += 1;
         }
         Self(unsafe { core::mem::transmute_copy(&result) })
     }
+
+    /// Builds a new builder with the strings of `self`, followed by `more`
+    ///
+    /// The symbols of `self` keep their value on the new builder, so the
+    /// constants defined against `self` stay valid. `R` must be `N + M`.
+    ///
+    /// # Panics
+    /// If `R` isn't `N + M`, or if a string appears more than once.
+    /// When evaluated in a const context, this makes the build fail.
+    ///
+    /// # Example
+    /// ```
+    /// use interns::backend::string::*;
+    ///
+    /// const BASE: StringInternerBuilder<2> = StringInternerBuilder::with_const_symbols(["fn", "let"]);
+    /// const KWFN: Symbol = BASE.symbol_at(0);
+    ///
+    /// const EDITION_2018: StringInternerBuilder<4> = BASE.extend(["async", "await"]);
+    /// const KWASYNC: Symbol = EDITION_2018.symbol_at(2);
+    ///
+    /// let mut interner = EDITION_2018.build();
+    /// assert_eq!(interner.get_or_intern("fn"), KWFN);
+    /// assert_eq!(interner.get_or_intern("async"), KWASYNC);
+    /// ```
+    pub const fn extend<const M: usize, const R: usize>(&self, more: [&'static str; M]) -> StringInternerBuilder<R> {
+        assert!(R == N + M, "the extended builder must have N + M strings");
+        let mut strings = [""; R];
+        let mut i = 0;
+        while i < N {
+            strings[i] = self.0[i].0;
+            i += 1;
+        }
+        while i < R {
+            strings[i] = more[i - N];
+            i += 1;
+        }
+        StringInternerBuilder::with_const_symbols(strings)
+    }
+
+    /// Builds a new builder with the strings of `self`, followed by the ones of `other`
+    ///
+    /// The symbols of `other` are offset by `N`. See [extend](Self::extend).
+    pub const fn concat<const M: usize, const R: usize>(&self, other: &StringInternerBuilder<M>) -> StringInternerBuilder<R> {
+        let mut more = [""; M];
+        let mut i = 0;
+        while i < M {
+            more[i] = other.0[i].0;
+            i += 1;
+        }
+        self.extend(more)
+    }
+
     pub const fn symbol_at(&self, idx: usize) -> Symbol { self.0[idx].1 }
     pub const fn string_at(&self, idx: usize) -> &'static str { self.0[idx].0 }
 
     /// Builds a [StringInterner] with the pre-defined symbols given on [Self::with_const_symbols]
     pub fn build(&self) -> StringInterner {
-        let mut i = StringInterner::new();
-        i.prefill(&self.0);
+        self.build_into()
+    }
+
+    /// Like [build](Self::build), but the interner uses `hasher`
+    ///
+    /// # Example
+    /// ```
+    /// use interns::backend::string::StringInternerBuilder;
+    /// use interns::hash::FixedState;
+    ///
+    /// const BUILDER: StringInternerBuilder<2> = StringInternerBuilder::with_const_symbols(["fn", "let"]);
+    ///
+    /// let mut interner = BUILDER.build_with_hasher(FixedState::with_seed(42));
+    /// assert_eq!(interner.get_or_intern("let"), BUILDER.symbol_at(1));
+    /// ```
+    pub fn build_with_hasher<H: BuildHasher>(&self, hasher: H) -> Interner<str, StringBackend, H> {
+        let mut i = Interner::with_backend_and_hasher(StringBackend::new(), hasher);
+        i.prefill_const(&self.0);
+        i
+    }
+
+    /// Like [build](Self::build), but with any [Prefill] backend and hasher
+    ///
+    /// # Example
+    /// ```
+    /// use interns::{DeterministicInterner, StringBackend};
+    /// use interns::backend::string::StringInternerBuilder;
+    ///
+    /// const BUILDER: StringInternerBuilder<2> = StringInternerBuilder::with_const_symbols(["fn", "let"]);
+    ///
+    /// let interner: DeterministicInterner<str, StringBackend> = BUILDER.build_into();
+    /// assert_eq!(interner.get("fn"), Some(BUILDER.symbol_at(0)));
+    /// ```
+    pub fn build_into<B, H>(&self) -> Interner<str, B, H>
+    where
+        B: Prefill + Default,
+        H: BuildHasher + Default,
+    {
+        let mut i = Interner::new();
+        i.prefill_const(&self.0);
         i
     }
 }
 
-impl<H: BuildHasher> Interner<str, StringBackend, H> {
-    fn prefill(&mut self, syms: &[(&str, Symbol)]) {
-        let Self { hasher, backend, .. } = self;
-        backend.prefill(syms);
+impl<B: Prefill, H: BuildHasher> Interner<str, B, H> {
+    fn prefill_const(&mut self, syms: &[(&str, Symbol)]) {
+        self.backend.prefill(syms);
         for (string, sym) in syms {
+            let Self { hasher, backend, set, .. } = self;
             let hash = hasher.hash_one(string);
-            let entry = self.set.raw_entry_mut().from_hash(hash, |s| s == sym);
-            if let RawEntryMut::Vacant(vacant) = entry {
-                vacant.insert_with_hasher(hash, *sym, (), |s| {
+            if set.find(hash, |s| s == sym).is_none() {
+                set.insert_unique(hash, *sym, |s| {
+                    /* SAFETY: Every symbol on the table is on the backend */
                     let s = unsafe { backend.get_unchecked(*s) };
                     hasher.hash_one(s)
                 });
+                self.touch();
             }
+            self.debug_check_invariants(*sym);
+        }
+        self.recount_metrics();
+    }
+}
+
+impl<H: BuildHasher> Interner<str, StringBackend, H> {
+    /// Returns the longest interned string that is a prefix of `input`
+    ///
+    /// The result is the symbol of that string, and its length in bytes.
+    ///
+    /// # Example
+    /// ```
+    /// use interns::StringInterner;
+    ///
+    /// let mut interner = StringInterner::new();
+    /// let shl = interner.get_or_intern("<<");
+    /// let shl_assign = interner.get_or_intern("<<=");
+    /// interner.get_or_intern("<");
+    ///
+    /// assert_eq!(interner.longest_match("<<= 2"), Some((shl_assign, 3)));
+    /// assert_eq!(interner.longest_match("<<2"), Some((shl, 2)));
+    /// assert_eq!(interner.longest_match("2 << 1"), None);
+    /// ```
+    pub fn longest_match(&self, input: &str) -> Option<(Symbol, usize)> {
+        /* No interned string is longer than max_len, so we
+         * don't need to look at the rest of the input */
+        let max = input.len().min(self.backend.max_len());
+        (0..=max)
+            .rev()
+            .filter(|&len| input.is_char_boundary(len))
+            .find_map(|len| Some((self.get(&input[..len])?, len)))
+    }
+
+    /// Gets the symbol for the string made of `chars`, interning it if it doesn't exist
+    ///
+    /// The string is built directly at the end of the backend's buffer,
+    /// so no temporary [String] is needed. If it was already interned,
+    /// the buffer is truncated back.
+    ///
+    /// # Example
+    /// ```
+    /// use interns::StringInterner;
+    ///
+    /// let mut interner = StringInterner::new();
+    ///
+    /// /* A lexer that unescapes `\n` on the fly */
+    /// let mut chars = r"line\nbreak".chars();
+    /// let unescaped = std::iter::from_fn(|| match chars.next()? {
+    ///     '\\' => chars.next().map(|c| if c == 'n' { '\n' } else { c }),
+    ///     c => Some(c),
+    /// });
+    ///
+    /// let sym = interner.get_or_intern_chars(unescaped);
+    /// assert_eq!(interner.resolve(sym), Some("line\nbreak"));
+    /// assert_eq!(interner.get_or_intern_chars("line\nbreak".chars()), sym);
+    /// ```
+    pub fn get_or_intern_chars<I: IntoIterator<Item = char>>(&mut self, chars: I) -> Symbol {
+        let offset = self.backend.buf.len();
+        for c in chars {
+            self.backend.reserve(c.len_utf8());
+            self.backend.buf.push(c);
+        }
+        self.intern_tail(offset)
+    }
+
+    /// Gets the symbol for the concatenation of `parts`, interning it if it doesn't exist
+    ///
+    /// Like [get_or_intern_chars](Self::get_or_intern_chars), the string
+    /// is built at the end of the backend's buffer, so joining the parts
+    /// doesn't need a temporary [String].
+    ///
+    /// # Example
+    /// ```
+    /// use interns::StringInterner;
+    ///
+    /// let mut interner = StringInterner::new();
+    /// let path = interner.intern_parts(&["std", "::", "io"]);
+    /// assert_eq!(interner.resolve(path), Some("std::io"));
+    /// assert_eq!(interner.get_or_intern("std::io"), path);
+    ///
+    /// let file = interner.intern_parts(&["main", ".", "rs"]);
+    /// assert_eq!(interner.intern_parts(&["mai", "n.rs"]), file);
+    /// ```
+    pub fn intern_parts(&mut self, parts: &[&str]) -> Symbol {
+        let offset = self.backend.buf.len();
+        let len = parts.iter().map(|part| part.len()).sum();
+        self.backend.reserve(len);
+        for part in parts {
+            self.backend.buf.push_str(part);
+        }
+        self.intern_tail(offset)
+    }
+
+    /// Gets the symbol for `raw`, with its escape sequences decoded
+    ///
+    /// The escapes are decoded directly at the end of the backend's buffer,
+    /// like on [get_or_intern_chars](Self::get_or_intern_chars). On error,
+    /// nothing is interned.
+    ///
+    /// # Example
+    /// ```
+    /// use interns::{EscapeError, StringInterner};
+    /// use interns::escape::EscapeRules;
+    ///
+    /// let mut interner = StringInterner::new();
+    /// let rules = EscapeRules::new();
+    ///
+    /// let sym = interner.get_or_intern_unescaped(r#"say \"hi\"\n"#, rules).unwrap();
+    /// assert_eq!(interner.resolve(sym), Some("say \"hi\"\n"));
+    ///
+    /// let err = interner.get_or_intern_unescaped(r"bad \q", rules).unwrap_err();
+    /// assert_eq!(err, EscapeError::Unknown { offset: 4, escape: 'q' });
+    /// ```
+    pub fn get_or_intern_unescaped(&mut self, raw: &str, rules: EscapeRules) -> Result<Symbol, EscapeError> {
+        let offset = self.backend.buf.len();
+        self.backend.reserve(raw.len());
+        if let Err(err) = escape::unescape_into(raw, rules, &mut self.backend.buf) {
+            self.backend.buf.truncate(offset);
+            return Err(err)
         }
+        Ok(self.intern_tail(offset))
+    }
+
+    /// Interns the end of the backend's buffer, starting at `offset`
+    ///
+    /// If the string was already interned, the buffer is truncated back to `offset`.
+    fn intern_tail(&mut self, offset: usize) -> Symbol {
+        let len = self.backend.buf.len() - offset;
+
+        if let Some(sym) = self.get(&self.backend.buf[offset..]) {
+            self.backend.buf.truncate(offset);
+            self.record_request(sym, len, false);
+            return sym
+        }
+
+        let sym = match self.backend.commit_tail(offset) {
+            Ok(sym) => sym,
+            Err(err) => {
+                self.backend.buf.truncate(offset);
+                panic!("Couldn't intern string: {err}")
+            }
+        };
+        self.insert_symbol(sym);
+        self.record_request(sym, len, true);
+        sym
     }
 }
 
@@ -150,19 +843,21 @@ where
     str: Borrow<T>,
     T: AsRef<str> + ?Sized
 {
+    /// # Panics
+    /// If the backend already holds [u32::MAX] indexed strings.
+    /// See the [Symbol] encoding.
     fn intern_into(&self, b: &mut StringBackend) -> Symbol {
+        match self.try_intern_into(b) {
+            Ok(sym) => sym,
+            Err(err) => panic!("Couldn't intern string: {err}"),
+        }
+    }
+
+    fn try_intern_into(&self, b: &mut StringBackend) -> Result<Symbol, InternError> {
         let offset = b.buf.len();
         let src = self.as_ref();
-        let len = src.len();
+        b.try_reserve(src.len())?;
         b.buf.push_str(src);
-
-        if len < u32::MAX as usize && offset <= u32::MAX as usize {
-            Symbol::new_inlined(offset as u32, len as u32)
-        } else {
-            let span = Span { offset, len };
-            let offset = b.spans.len() as u32;
-            b.spans.push(span);
-            Symbol { offset, len: u32::MAX }
-        }
+        b.commit_tail(offset).inspect_err(|_| b.buf.truncate(offset))
     }
 }
@@ -1,6 +1,7 @@
 use core::borrow::Borrow;
 
-use crate::backend::Internable;
+use crate::backend::{get_index, index, repr, try_repr, Internable, SymbolRepr};
+use crate::{InternError, ResolveError};
 
 use super::Backend;
 
@@ -9,23 +10,50 @@ pub struct VecBackend<T> {
     buf: Vec<T>,
 }
 
+impl<T> VecBackend<T> {
+    /// Creates an empty backend
+    ///
+    /// This is a const version of [Default::default]
+    pub const fn new() -> Self {
+        Self { buf: Vec::new() }
+    }
+}
+
 impl<T> Default for VecBackend<T> {
     fn default() -> Self {
-        Self { buf: Default::default() }
+        Self::new()
     }
 }
 
-#[derive(Clone, Copy, Debug, Hash, Eq, PartialEq)]
-#[repr(transparent)]
-pub struct Symbol(usize);
+#[cfg(feature = "zeroize")]
+impl<T: zeroize::Zeroize> zeroize::Zeroize for VecBackend<T> {
+    /// Wipes all the elements, and removes them
+    fn zeroize(&mut self) {
+        self.buf.zeroize();
+    }
+}
+
+super::index_symbol! {
+    impl[T] VecBackend<T>, buf;
+}
 
 impl<T> Backend<T> for VecBackend<T> {
     type Symbol = Symbol;
 
     fn get(&self, sym: Self::Symbol) -> Option<&T> {
-        let val = self.buf.get(sym.0)?;
+        let val = self.buf.get(index(sym.0))?;
         Some(val)
     }
+
+    fn try_get(&self, sym: Self::Symbol) -> Result<&T, ResolveError> {
+        get_index(&self.buf, sym.0)
+    }
+
+    /// Returns the index of `sym`'s value
+    fn offset_of(&self, sym: Self::Symbol) -> Option<usize> {
+        let i = index(sym.0);
+        (i < self.buf.len()).then_some(i)
+    }
 }
 
 impl<T, Ref, Inter> Internable<T, VecBackend<T>> for Ref
@@ -35,32 +63,60 @@ where
     Inter: Into<T>
 {
     fn intern_into(&self, b: &mut VecBackend<T>) -> Symbol {
-        let sym = Symbol(b.buf.len());
+        let sym = Symbol(repr(b.buf.len()));
         b.buf.push(self.to_owned().into());
         sym
     }
+
+    fn try_intern_into(&self, b: &mut VecBackend<T>) -> Result<Symbol, InternError> {
+        let sym = Symbol(try_repr(b.buf.len())?);
+        b.buf.try_reserve(1)?;
+        b.buf.push(self.to_owned().into());
+        Ok(sym)
+    }
 }
 
-#[derive(Clone, Copy, Debug, Hash, Eq, PartialEq)]
+#[derive(Clone, Copy, Debug, Hash, Eq, PartialEq, PartialOrd, Ord)]
 pub struct Span {
-    start: usize,
-    len: usize,
+    start: SymbolRepr,
+    len: SymbolRepr,
 }
 
+const _: () = assert!(size_of::<Span>() == 2 * size_of::<SymbolRepr>());
+
 impl<T> Backend<[T]> for VecBackend<T> {
     type Symbol = Span;
 
     fn get(&self, sym: Self::Symbol) -> Option<&[T]> {
-        let val = self.buf.get(sym.start..sym.start + sym.len)?;
-        Some(val)
+        self.try_get(sym).ok()
+    }
+
+    fn try_get(&self, sym: Self::Symbol) -> Result<&[T], ResolveError> {
+        let (offset, len) = (index(sym.start), index(sym.len));
+        offset
+            .checked_add(len)
+            .and_then(|end| self.buf.get(offset..end))
+            .ok_or(ResolveError::InvalidSpan { offset, len })
     }
 }
 
 impl<T: Clone> Internable<[T], VecBackend<T>> for [T] {
     fn intern_into(&self, b: &mut VecBackend<T>) -> Span {
-        let start = b.buf.len();
+        let span = Span {
+            start: repr(b.buf.len()),
+            len: repr(self.len()),
+        };
+        b.buf.extend_from_slice(self);
+        span
+    }
+
+    fn try_intern_into(&self, b: &mut VecBackend<T>) -> Result<Span, InternError> {
+        let span = Span {
+            start: try_repr(b.buf.len())?,
+            len: try_repr(self.len())?,
+        };
+        b.buf.try_reserve(self.len())?;
         b.buf.extend_from_slice(self);
-        let len = b.buf.len() - start;
-        Span { start, len }
+        Ok(span)
     }
 }
@@ -0,0 +1,178 @@
+//! Loading many values at once
+
+use core::borrow::Borrow;
+use core::hash::{BuildHasher, Hash};
+
+use hashbrown::HashTable;
+
+use crate::backend::{Internable, IterableBackend};
+use crate::{Backend, Interner};
+
+impl<T, B, H> Interner<T, B, H>
+where
+    T: Hash + Eq + ?Sized,
+    B: Backend<T>,
+    H: BuildHasher,
+{
+    /// Interns all the values of `values`, and returns how many were new
+    ///
+    /// The batch is sorted and deduplicated first. Then, the values
+    /// are appended to the backend in one pass, and the dedup set is
+    /// grown only once, to its final size. For big initial loads, this
+    /// is faster than interning the values one by one, since the set
+    /// never rehashes and each value is only hashed once.
+    ///
+    /// The values are interned in sorted order, not in the order of `values`.
+    /// Values that were already on the interner are skipped. Every value of
+    /// the batch counts as a request on the [savings](Self::savings) report.
+    ///
+    /// # Example
+    /// ```
+    /// use interns::StringInterner;
+    ///
+    /// let mut interner = StringInterner::new();
+    /// interner.get_or_intern("b");
+    ///
+    /// let new = interner.bulk_load(vec!["c", "a", "b", "c"]);
+    /// assert_eq!(new, 2);
+    /// assert_eq!(interner.len(), 3);
+    /// assert!(interner.contains("a"));
+    /// ```
+    pub fn bulk_load<V, Ref>(&mut self, mut values: Vec<V>) -> usize
+    where
+        V: Borrow<Ref> + Ord,
+        Ref: Internable<T, B> + ?Sized + Hash + Eq,
+        T: Borrow<Ref>,
+    {
+        for v in &values {
+            self.usage.record(size_of_val(v.borrow()));
+        }
+        values.sort_unstable();
+        values.dedup_by(|a, b| (*a).borrow() == (*b).borrow());
+        if !self.is_empty() {
+            values.retain(|v| !self.contains(v.borrow()));
+        }
+
+        let Self { backend, set, hasher, .. } = self;
+        let entries: Vec<_> = values
+            .iter()
+            .map(|v| {
+                let src = v.borrow();
+                (hasher.hash_one(src), backend.intern(src), size_of_val(src))
+            })
+            .collect();
+
+        set.reserve(entries.len(), |sym| {
+            /* SAFETY: Every symbol on the table is on the backend */
+            let src = unsafe { backend.get_unchecked(*sym) };
+            hasher.hash_one(src)
+        });
+        for &(hash, sym, _) in &entries {
+            set.insert_unique(hash, sym, |sym| {
+                /* SAFETY: Every symbol has been interned above, or was already on the table */
+                let src = unsafe { backend.get_unchecked(*sym) };
+                hasher.hash_one(src)
+            });
+        }
+
+        for &(_, sym, len) in &entries {
+            self.trace_intern(len, true);
+            self.record_metrics(sym, true);
+        }
+        if let Some(&(_, sym, _)) = entries.last() {
+            self.touch();
+            self.debug_check_invariants(sym);
+        }
+        entries.len()
+    }
+
+    /// Appends `src` to the backend, without checking if it was already interned
+    ///
+    /// This skips the deduplication set entirely, which saves a lookup per
+    /// value when the input is known to be unique. The value is not visible
+    /// to [get](Self::get), [len](Self::len) and the rest of the interner's
+    /// methods until the set is [rebuilt](Self::rebuild_index), usually once,
+    /// after all the values have been appended. Its symbol can be resolved
+    /// right away, though.
+    ///
+    /// If `src` wasn't unique, it's stored twice. [rebuild_index](Self::rebuild_index)
+    /// reports those duplicates, and only indexes their first symbol.
+    ///
+    /// # Example
+    /// ```
+    /// use interns::Interner;
+    ///
+    /// let mut interner = Interner::<u64>::new();
+    /// let ids: Vec<_> = (0..1000).map(|id| interner.append_unique(&id)).collect();
+    /// assert_eq!(interner.resolve(ids[42]), Some(&42));
+    /// assert_eq!(interner.get(&42), None);
+    ///
+    /// assert_eq!(interner.rebuild_index(), 0);
+    /// assert_eq!(interner.get(&42), Some(ids[42]));
+    /// ```
+    pub fn append_unique<Ref>(&mut self, src: &Ref) -> B::Symbol
+    where
+        Ref: Internable<T, B> + ?Sized,
+        T: Borrow<Ref>,
+        B: IterableBackend<T>,
+    {
+        let sym = self.backend.intern(src);
+        self.usage.record(size_of_val(src));
+        sym
+    }
+
+    /// Rebuilds the deduplication set from the values on the backend
+    ///
+    /// The set is normally kept in sync by the interner. Rebuilding it is
+    /// needed after the backend has been filled by other means (e.g. loaded
+    /// from raw data, or with [append_unique](Self::append_unique)), or to recover from a corrupted set, as reported
+    /// by [check_invariants](Self::check_invariants).
+    ///
+    /// If a value is stored more than once, the first of its symbols is kept
+    /// on the set, and the rest are ignored. Returns the number of those
+    /// duplicates.
+    ///
+    /// # Example
+    /// ```
+    /// use interns::Interner;
+    ///
+    /// let mut interner = Interner::<u32>::new();
+    /// let a = interner.get_or_intern(&1);
+    /// interner.get_or_intern(&2);
+    ///
+    /// assert_eq!(interner.rebuild_index(), 0);
+    /// assert_eq!(interner.get(&1), Some(a));
+    /// assert!(interner.check_invariants().is_ok());
+    /// ```
+    pub fn rebuild_index(&mut self) -> usize
+    where
+        B: IterableBackend<T>,
+    {
+        let Self { backend, set, hasher, .. } = self;
+        let mut duplicates = 0;
+        *set = HashTable::new();
+        for sym in backend.symbols() {
+            /* SAFETY: The symbols come from the backend itself */
+            let src = unsafe { backend.get_unchecked(sym) };
+            let hash = hasher.hash_one(src);
+            let found = set.find(hash, |&s| {
+                /* SAFETY: Every symbol on the table is on the backend */
+                src == unsafe { backend.get_unchecked(s) }
+            });
+            if found.is_some() {
+                duplicates += 1;
+                continue
+            }
+            set.insert_unique(hash, sym, |s| {
+                /* SAFETY: Every symbol on the table is on the backend */
+                hasher.hash_one(unsafe { backend.get_unchecked(*s) })
+            });
+        }
+        self.touch_removed();
+        self.recount_metrics();
+        if let Some(&sym) = self.set.iter().next() {
+            self.debug_check_invariants(sym);
+        }
+        duplicates
+    }
+}
@@ -0,0 +1,160 @@
+//! Code generation for prefilled interners
+//!
+//! This module is intended to be used from a `build.rs` script. Given
+//! a list of words, [generate] writes Rust source code defining:
+//!
+//! - A [StringInternerBuilder] constant, prefilled with all the words
+//! - A [Symbol] constant for each word
+//! - A `lookup` function mapping a string to its predefined symbol
+//!
+//! # Example
+//! ```no_run
+//! // build.rs
+//! use std::{env, fs::File, path::Path};
+//!
+//! let out = Path::new(&env::var("OUT_DIR").unwrap()).join("keywords.rs");
+//! let mut file = File::create(out).unwrap();
+//! interns::codegen::generate(&mut file, &["fn", "let", "struct"], &Default::default()).unwrap();
+//!
+//! // src/main.rs
+//! // include!(concat!(env!("OUT_DIR"), "/keywords.rs"));
+//! ```
+//!
+//! [StringInternerBuilder]: crate::backend::string::StringInternerBuilder
+//! [Symbol]: crate::backend::string::Symbol
+
+use std::collections::HashSet;
+use std::io::{self, Write};
+
+/// Options for [generate]
+#[derive(Clone, Debug)]
+pub struct Options {
+    /// Name of the builder constant
+    pub builder_name: String,
+    /// Prefix for the name of each symbol constant
+    pub const_prefix: String,
+    /// Name of the lookup function
+    pub lookup_name: String,
+    /// Visibility of the generated items (e.g. `pub`, `pub(crate)`)
+    pub visibility: String,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Self {
+            builder_name: "BUILDER".to_string(),
+            const_prefix: String::new(),
+            lookup_name: "lookup".to_string(),
+            visibility: "pub".to_string(),
+        }
+    }
+}
+
+/// Converts `word` into a valid SCREAMING_CASE identifier
+///
+/// Words without ASCII alphanumerics are named after their code points
+/// (e.g. `->` is `U2D_U3E`), since otherwise all of them would become `_`.
+fn const_name(prefix: &str, word: &str) -> String {
+    let mut name = String::from(prefix);
+    if word.is_empty() {
+        name.push_str("EMPTY");
+    } else if word.contains(|c: char| c.is_ascii_alphanumeric()) {
+        for c in word.chars() {
+            if c.is_ascii_alphanumeric() {
+                name.push(c.to_ascii_uppercase());
+            } else if !name.ends_with('_') {
+                name.push('_');
+            }
+        }
+    } else {
+        let codes = word.chars().map(|c| format!("U{:X}", u32::from(c))).collect::<Vec<_>>();
+        name.push_str(&codes.join("_"));
+    }
+    if name.is_empty() || name.starts_with(|c: char| c.is_ascii_digit()) {
+        name.insert(0, '_');
+    }
+    name
+}
+
+/// Writes the source code for a prefilled interner with the given `words`
+///
+/// Duplicated words are ignored. If two different words map to the same
+/// constant name, or to the name of the builder or the lookup function,
+/// a numeric suffix is appended to the constant.
+///
+/// # Example
+/// The output for some words with conflicting names is at `src/codegen/keywords.rs`
+/// ```
+/// mod keywords {
+///     include!(concat!(env!("CARGO_MANIFEST_DIR"), "/src/codegen/keywords.rs"));
+/// }
+/// use keywords::*;
+///
+/// assert_eq!(lookup("self"), Some(SELF));
+/// assert_eq!(lookup("Self"), Some(SELF_1));
+/// assert_eq!(lookup("->"), Some(U2D_U3E));
+/// assert_eq!(BUILDER.build().resolve(BUILDER_1), Some("builder"));
+/// ```
+pub fn generate<W, S>(out: &mut W, words: &[S], options: &Options) -> io::Result<()>
+where
+    W: Write + ?Sized,
+    S: AsRef<str>,
+{
+    let Options { builder_name, const_prefix, lookup_name, visibility } = options;
+
+    let mut seen = HashSet::new();
+    let words = words.iter().map(AsRef::as_ref).filter(|w| seen.insert(*w)).collect::<Vec<_>>();
+
+    let mut names = HashSet::from([builder_name.clone(), lookup_name.clone()]);
+    let consts = words
+        .iter()
+        .map(|w| {
+            let base = const_name(const_prefix, w);
+            let mut name = base.clone();
+            let mut i = 1;
+            while !names.insert(name.clone()) {
+                name = format!("{base}_{i}");
+                i += 1;
+            }
+            name
+        })
+        .collect::<Vec<_>>();
+
+    let n = words.len();
+    writeln!(out, "/* This file was generated by interns::codegen. Don't edit it manually */")?;
+    writeln!(out)?;
+    writeln!(
+        out,
+        "{visibility} const {builder_name}: ::interns::backend::string::StringInternerBuilder<{n}> =\n    \
+         ::interns::backend::string::StringInternerBuilder::with_const_symbols(["
+    )?;
+    for word in &words {
+        writeln!(out, "        {word:?},")?;
+    }
+    writeln!(out, "    ]);")?;
+    writeln!(out)?;
+
+    for (i, (word, name)) in words.iter().zip(&consts).enumerate() {
+        writeln!(out, "/// Symbol for {word:?}")?;
+        writeln!(
+            out,
+            "{visibility} const {name}: ::interns::backend::string::Symbol = {builder_name}.symbol_at({i});"
+        )?;
+    }
+    writeln!(out)?;
+
+    writeln!(out, "/// Returns the predefined symbol for `s`, if any")?;
+    writeln!(
+        out,
+        "{visibility} fn {lookup_name}(s: &str) -> ::core::option::Option<::interns::backend::string::Symbol> {{"
+    )?;
+    writeln!(out, "    match s {{")?;
+    for (word, name) in words.iter().zip(&consts) {
+        writeln!(out, "        {word:?} => ::core::option::Option::Some({name}),")?;
+    }
+    writeln!(out, "        _ => ::core::option::Option::None,")?;
+    writeln!(out, "    }}")?;
+    writeln!(out, "}}")?;
+
+    Ok(())
+}
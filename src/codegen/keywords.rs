@@ -0,0 +1,61 @@
+/* This file was generated by interns::codegen. Don't edit it manually */
+
+pub const BUILDER: ::interns::backend::string::StringInternerBuilder<12> =
+    ::interns::backend::string::StringInternerBuilder::with_const_symbols([
+        "fn",
+        "let",
+        "self",
+        "Self",
+        "->",
+        "+",
+        "",
+        "ñ",
+        "a-b",
+        "a_b",
+        "builder",
+        "1st",
+    ]);
+
+/// Symbol for "fn"
+pub const FN: ::interns::backend::string::Symbol = BUILDER.symbol_at(0);
+/// Symbol for "let"
+pub const LET: ::interns::backend::string::Symbol = BUILDER.symbol_at(1);
+/// Symbol for "self"
+pub const SELF: ::interns::backend::string::Symbol = BUILDER.symbol_at(2);
+/// Symbol for "Self"
+pub const SELF_1: ::interns::backend::string::Symbol = BUILDER.symbol_at(3);
+/// Symbol for "->"
+pub const U2D_U3E: ::interns::backend::string::Symbol = BUILDER.symbol_at(4);
+/// Symbol for "+"
+pub const U2B: ::interns::backend::string::Symbol = BUILDER.symbol_at(5);
+/// Symbol for ""
+pub const EMPTY: ::interns::backend::string::Symbol = BUILDER.symbol_at(6);
+/// Symbol for "ñ"
+pub const UF1: ::interns::backend::string::Symbol = BUILDER.symbol_at(7);
+/// Symbol for "a-b"
+pub const A_B: ::interns::backend::string::Symbol = BUILDER.symbol_at(8);
+/// Symbol for "a_b"
+pub const A_B_1: ::interns::backend::string::Symbol = BUILDER.symbol_at(9);
+/// Symbol for "builder"
+pub const BUILDER_1: ::interns::backend::string::Symbol = BUILDER.symbol_at(10);
+/// Symbol for "1st"
+pub const _1ST: ::interns::backend::string::Symbol = BUILDER.symbol_at(11);
+
+/// Returns the predefined symbol for `s`, if any
+pub fn lookup(s: &str) -> ::core::option::Option<::interns::backend::string::Symbol> {
+    match s {
+        "fn" => ::core::option::Option::Some(FN),
+        "let" => ::core::option::Option::Some(LET),
+        "self" => ::core::option::Option::Some(SELF),
+        "Self" => ::core::option::Option::Some(SELF_1),
+        "->" => ::core::option::Option::Some(U2D_U3E),
+        "+" => ::core::option::Option::Some(U2B),
+        "" => ::core::option::Option::Some(EMPTY),
+        "ñ" => ::core::option::Option::Some(UF1),
+        "a-b" => ::core::option::Option::Some(A_B),
+        "a_b" => ::core::option::Option::Some(A_B_1),
+        "builder" => ::core::option::Option::Some(BUILDER_1),
+        "1st" => ::core::option::Option::Some(_1ST),
+        _ => ::core::option::Option::None,
+    }
+}
@@ -0,0 +1,701 @@
+//! Containers keyed by symbols
+//!
+//! Index-based symbols (like the ones of the [VecBackend](crate::backend::VecBackend))
+//! are assigned densely, starting at 0. The containers of this module use them
+//! to index into vectors directly, instead of hashing them.
+//!
+//! # Example
+//! ```
+//! use interns::Interner;
+//! use interns::collections::SymbolMap;
+//!
+//! let mut interner = Interner::<u32>::new();
+//! let mut doc = SymbolMap::new();
+//!
+//! let a = interner.get_or_intern(&10);
+//! let b = interner.get_or_intern(&20);
+//! doc.insert(b, "twenty");
+//!
+//! assert_eq!(doc.get(a), None);
+//! assert_eq!(doc.get(b), Some(&"twenty"));
+//! ```
+//!
+//! A [SymbolSet] keeps one bit per symbol, which makes it a cheap way to
+//! tag symbols (e.g. reserved keywords, or the identifiers in scope).
+//!
+//! ```
+//! use interns::Interner;
+//! use interns::collections::SymbolSet;
+//!
+//! let mut interner = Interner::<String>::new();
+//! let reserved: SymbolSet<_> = ["fn", "let"].iter().map(|kw| interner.get_or_intern(*kw)).collect();
+//!
+//! let ident = interner.get_or_intern("foo");
+//! assert!(!reserved.contains(ident));
+//! assert!(reserved.contains(interner.get_or_intern("let")));
+//! ```
+//!
+//! The sets support the usual algebra (`|`, `&` and `-`), and the result
+//! can be turned into a new [sub-interner](Interner::sub_interner).
+
+use core::fmt;
+use core::hash::{BuildHasher, Hash};
+use core::marker::PhantomData;
+use core::ops::{BitAnd, BitOr, Index, IndexMut, Sub};
+
+use crate::backend::Internable;
+use crate::{Backend, DenseSymbols, Interner, RemapTable};
+
+/// A symbol that can be converted to and from a dense index
+///
+/// The index-based backends implement [DenseSymbols] with it.
+pub trait SymbolIndex: Copy {
+    /// Returns the index of this symbol
+    fn to_index(self) -> usize;
+
+    /// Builds the symbol with the given index
+    ///
+    /// # Panics
+    /// If `index` doesn't fit in the symbol
+    fn from_index(index: usize) -> Self;
+}
+
+/// Map from symbols to values, backed by a [Vec]
+///
+/// Its memory usage is proportional to the largest symbol
+/// inserted, so it's best used when most symbols have a value.
+pub struct SymbolMap<S, V> {
+    values: Vec<Option<V>>,
+    len: usize,
+    _marker: PhantomData<fn(S) -> S>,
+}
+
+impl<S, V> SymbolMap<S, V> {
+    /// Creates an empty map
+    pub const fn new() -> Self {
+        Self {
+            values: Vec::new(),
+            len: 0,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Creates an empty map, with space for the symbols below `capacity`
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            values: Vec::with_capacity(capacity),
+            len: 0,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Returns the number of values on the map
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns true if the map is empty
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Removes all the values
+    pub fn clear(&mut self) {
+        self.values.clear();
+        self.len = 0;
+    }
+}
+
+impl<S: SymbolIndex, V> SymbolMap<S, V> {
+    /// Inserts `value` for `sym`, returning the previous value
+    pub fn insert(&mut self, sym: S, value: V) -> Option<V> {
+        let slot = self.slot(sym);
+        let old = slot.replace(value);
+        if old.is_none() {
+            self.len += 1;
+        }
+        old
+    }
+
+    /// Returns the value of `sym`
+    pub fn get(&self, sym: S) -> Option<&V> {
+        self.values.get(sym.to_index())?.as_ref()
+    }
+
+    /// Returns a mutable reference to the value of `sym`
+    pub fn get_mut(&mut self, sym: S) -> Option<&mut V> {
+        self.values.get_mut(sym.to_index())?.as_mut()
+    }
+
+    /// Returns true if `sym` has a value
+    pub fn contains_key(&self, sym: S) -> bool {
+        self.get(sym).is_some()
+    }
+
+    /// Removes the value of `sym`, and returns it
+    pub fn remove(&mut self, sym: S) -> Option<V> {
+        let old = self.values.get_mut(sym.to_index())?.take();
+        if old.is_some() {
+            self.len -= 1;
+        }
+        old
+    }
+
+    /// Iterates over the (symbol, value) pairs, in increasing symbol order
+    pub fn iter(&self) -> impl Iterator<Item = (S, &V)> + '_ {
+        self.values
+            .iter()
+            .enumerate()
+            .filter_map(|(i, v)| Some((S::from_index(i), v.as_ref()?)))
+    }
+
+    /// Iterates mutably over the (symbol, value) pairs, in increasing symbol order
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = (S, &mut V)> + '_ {
+        self.values
+            .iter_mut()
+            .enumerate()
+            .filter_map(|(i, v)| Some((S::from_index(i), v.as_mut()?)))
+    }
+
+    /// Iterates over the symbols with a value
+    pub fn keys(&self) -> impl Iterator<Item = S> + '_ {
+        self.iter().map(|(sym, _)| sym)
+    }
+
+    /// Iterates over the values, in increasing symbol order
+    pub fn values(&self) -> impl Iterator<Item = &V> + '_ {
+        self.values.iter().flatten()
+    }
+
+    /// Gets the [Entry] of `sym`, for in-place manipulation
+    ///
+    /// The map grows to fit `sym` if needed.
+    ///
+    /// # Example
+    /// ```
+    /// use interns::Interner;
+    /// use interns::collections::SymbolMap;
+    ///
+    /// let mut interner = Interner::<String>::new();
+    /// let mut counts = SymbolMap::new();
+    ///
+    /// for word in "a b a c a".split(' ') {
+    ///     let sym = interner.get_or_intern(word);
+    ///     *counts.entry(sym).or_insert(0) += 1;
+    /// }
+    /// assert_eq!(counts[interner.get("a").unwrap()], 3);
+    /// ```
+    pub fn entry(&mut self, sym: S) -> Entry<'_, S, V> {
+        let index = sym.to_index();
+        if index >= self.values.len() {
+            self.values.resize_with(index + 1, || None);
+        }
+        let slot = &mut self.values[index];
+        if slot.is_some() {
+            Entry::Occupied(OccupiedEntry { sym, slot, len: &mut self.len })
+        } else {
+            Entry::Vacant(VacantEntry { sym, slot, len: &mut self.len })
+        }
+    }
+
+    /// Returns the slot of `sym`, growing the map if needed
+    fn slot(&mut self, sym: S) -> &mut Option<V> {
+        let index = sym.to_index();
+        if index >= self.values.len() {
+            self.values.resize_with(index + 1, || None);
+        }
+        &mut self.values[index]
+    }
+}
+
+/// Side table indexed by symbols
+///
+/// This is the same type as [SymbolMap]. It grows as new symbols are
+/// [inserted](SymbolMap::insert), and its [entry](SymbolMap::entry) API
+/// fills the values on demand.
+///
+/// # Example
+/// ```
+/// use interns::Interner;
+/// use interns::collections::SecondaryVec;
+///
+/// let mut interner = Interner::<String>::new();
+/// let mut lens = SecondaryVec::new();
+///
+/// for word in ["foo", "hello", "foo"] {
+///     let sym = interner.get_or_intern(word);
+///     lens.entry(sym).or_insert_with(|| word.len());
+/// }
+/// assert_eq!(lens.len(), 2);
+/// ```
+pub type SecondaryVec<S, V> = SymbolMap<S, V>;
+
+/// A view into a single slot of a [SymbolMap]
+///
+/// Returned by [SymbolMap::entry]
+pub enum Entry<'a, S, V> {
+    /// The symbol has a value
+    Occupied(OccupiedEntry<'a, S, V>),
+    /// The symbol doesn't have a value
+    Vacant(VacantEntry<'a, S, V>),
+}
+
+/// A slot of a [SymbolMap] that has a value
+pub struct OccupiedEntry<'a, S, V> {
+    sym: S,
+    slot: &'a mut Option<V>,
+    len: &'a mut usize,
+}
+
+/// A slot of a [SymbolMap] without a value
+pub struct VacantEntry<'a, S, V> {
+    sym: S,
+    slot: &'a mut Option<V>,
+    len: &'a mut usize,
+}
+
+impl<'a, S: Copy, V> Entry<'a, S, V> {
+    /// Returns the symbol of this entry
+    pub fn key(&self) -> S {
+        match self {
+            Entry::Occupied(e) => e.sym,
+            Entry::Vacant(e) => e.sym,
+        }
+    }
+
+    /// Inserts `default` if the entry is vacant, and returns a reference to the value
+    pub fn or_insert(self, default: V) -> &'a mut V {
+        self.or_insert_with(|| default)
+    }
+
+    /// Inserts the result of `f` if the entry is vacant, and returns a reference to the value
+    pub fn or_insert_with(self, f: impl FnOnce() -> V) -> &'a mut V {
+        match self {
+            Entry::Occupied(e) => e.into_mut(),
+            Entry::Vacant(e) => e.insert(f()),
+        }
+    }
+
+    /// Like [or_insert_with](Self::or_insert_with), but `f` receives the symbol
+    pub fn or_insert_with_key(self, f: impl FnOnce(S) -> V) -> &'a mut V {
+        let sym = self.key();
+        self.or_insert_with(|| f(sym))
+    }
+
+    /// Calls `f` with the value, if the entry is occupied
+    pub fn and_modify(mut self, f: impl FnOnce(&mut V)) -> Self {
+        if let Entry::Occupied(e) = &mut self {
+            f(e.get_mut());
+        }
+        self
+    }
+}
+
+impl<'a, S: Copy, V: Default> Entry<'a, S, V> {
+    /// Inserts the default value if the entry is vacant, and returns a reference to the value
+    pub fn or_default(self) -> &'a mut V {
+        self.or_insert_with(V::default)
+    }
+}
+
+impl<'a, S: Copy, V> OccupiedEntry<'a, S, V> {
+    /// Returns the symbol of this entry
+    pub fn key(&self) -> S {
+        self.sym
+    }
+
+    /// Returns a reference to the value
+    pub fn get(&self) -> &V {
+        self.slot.as_ref().expect("Occupied entries have a value")
+    }
+
+    /// Returns a mutable reference to the value
+    pub fn get_mut(&mut self) -> &mut V {
+        self.slot.as_mut().expect("Occupied entries have a value")
+    }
+
+    /// Converts the entry into a mutable reference to the value
+    pub fn into_mut(self) -> &'a mut V {
+        self.slot.as_mut().expect("Occupied entries have a value")
+    }
+
+    /// Replaces the value, and returns the old one
+    pub fn insert(&mut self, value: V) -> V {
+        core::mem::replace(self.get_mut(), value)
+    }
+
+    /// Removes the value from the map, and returns it
+    pub fn remove(self) -> V {
+        *self.len -= 1;
+        self.slot.take().expect("Occupied entries have a value")
+    }
+}
+
+impl<'a, S: Copy, V> VacantEntry<'a, S, V> {
+    /// Returns the symbol of this entry
+    pub fn key(&self) -> S {
+        self.sym
+    }
+
+    /// Inserts `value`, and returns a reference to it
+    pub fn insert(self, value: V) -> &'a mut V {
+        *self.len += 1;
+        self.slot.insert(value)
+    }
+}
+
+impl<S, V> Default for SymbolMap<S, V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<S, V: Clone> Clone for SymbolMap<S, V> {
+    fn clone(&self) -> Self {
+        Self {
+            values: self.values.clone(),
+            len: self.len,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<S: SymbolIndex + fmt::Debug, V: fmt::Debug> fmt::Debug for SymbolMap<S, V> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+impl<S: SymbolIndex, V: PartialEq> PartialEq for SymbolMap<S, V> {
+    fn eq(&self, other: &Self) -> bool {
+        self.len == other.len && self.iter().all(|(sym, v)| other.get(sym) == Some(v))
+    }
+}
+
+impl<S: SymbolIndex, V: Eq> Eq for SymbolMap<S, V> {}
+
+/// # Panics
+/// If `sym` doesn't have a value
+impl<S: SymbolIndex, V> Index<S> for SymbolMap<S, V> {
+    type Output = V;
+
+    #[track_caller]
+    fn index(&self, sym: S) -> &V {
+        self.get(sym).expect("Symbol not present on the map")
+    }
+}
+
+/// # Panics
+/// If `sym` doesn't have a value
+impl<S: SymbolIndex, V> IndexMut<S> for SymbolMap<S, V> {
+    #[track_caller]
+    fn index_mut(&mut self, sym: S) -> &mut V {
+        self.get_mut(sym).expect("Symbol not present on the map")
+    }
+}
+
+impl<S: SymbolIndex, V> Extend<(S, V)> for SymbolMap<S, V> {
+    fn extend<I: IntoIterator<Item = (S, V)>>(&mut self, iter: I) {
+        for (sym, value) in iter {
+            self.insert(sym, value);
+        }
+    }
+}
+
+impl<S: SymbolIndex, V> FromIterator<(S, V)> for SymbolMap<S, V> {
+    fn from_iter<I: IntoIterator<Item = (S, V)>>(iter: I) -> Self {
+        let mut map = Self::new();
+        map.extend(iter);
+        map
+    }
+}
+
+const WORD_BITS: usize = u64::BITS as usize;
+
+/// Set of symbols, stored as a bitset
+///
+/// Like [SymbolMap], its memory usage is proportional to
+/// the largest symbol inserted: one bit per symbol.
+pub struct SymbolSet<S> {
+    words: Vec<u64>,
+    _marker: PhantomData<fn(S) -> S>,
+}
+
+impl<S> SymbolSet<S> {
+    /// Creates an empty set
+    pub const fn new() -> Self {
+        Self {
+            words: Vec::new(),
+            _marker: PhantomData,
+        }
+    }
+
+    /// Creates an empty set, with space for the symbols below `capacity`
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            words: Vec::with_capacity(capacity.div_ceil(WORD_BITS)),
+            _marker: PhantomData,
+        }
+    }
+
+    /// Returns the number of symbols on the set
+    pub fn len(&self) -> usize {
+        self.words.iter().map(|w| w.count_ones() as usize).sum()
+    }
+
+    /// Returns true if the set is empty
+    pub fn is_empty(&self) -> bool {
+        self.words.iter().all(|&w| w == 0)
+    }
+
+    /// Removes all the symbols
+    pub fn clear(&mut self) {
+        self.words.clear();
+    }
+
+    /// Adds all the symbols of `other` to this set
+    pub fn union_with(&mut self, other: &Self) {
+        if self.words.len() < other.words.len() {
+            self.words.resize(other.words.len(), 0);
+        }
+        for (w, o) in self.words.iter_mut().zip(&other.words) {
+            *w |= o;
+        }
+    }
+
+    /// Removes the symbols that are not on `other`
+    pub fn intersect_with(&mut self, other: &Self) {
+        self.words.truncate(other.words.len());
+        for (w, o) in self.words.iter_mut().zip(&other.words) {
+            *w &= o;
+        }
+    }
+
+    /// Removes the symbols that are on `other`
+    pub fn difference_with(&mut self, other: &Self) {
+        for (w, o) in self.words.iter_mut().zip(&other.words) {
+            *w &= !o;
+        }
+    }
+
+    /// Returns true if all the symbols of this set are on `other`
+    pub fn is_subset(&self, other: &Self) -> bool {
+        self.words.iter().enumerate().all(|(i, w)| {
+            let o = other.words.get(i).copied().unwrap_or(0);
+            w & !o == 0
+        })
+    }
+
+    /// Returns true if the sets have no symbols in common
+    pub fn is_disjoint(&self, other: &Self) -> bool {
+        self.words.iter().zip(&other.words).all(|(w, o)| w & o == 0)
+    }
+}
+
+impl<S: SymbolIndex> SymbolSet<S> {
+    /// Adds `sym` to the set
+    ///
+    /// Returns true if it wasn't already present
+    pub fn insert(&mut self, sym: S) -> bool {
+        let (word, bit) = split(sym);
+        if word >= self.words.len() {
+            self.words.resize(word + 1, 0);
+        }
+        let w = &mut self.words[word];
+        let inserted = *w & bit == 0;
+        *w |= bit;
+        inserted
+    }
+
+    /// Removes `sym` from the set
+    ///
+    /// Returns true if it was present
+    pub fn remove(&mut self, sym: S) -> bool {
+        let (word, bit) = split(sym);
+        let Some(w) = self.words.get_mut(word) else { return false };
+        let removed = *w & bit != 0;
+        *w &= !bit;
+        removed
+    }
+
+    /// Returns true if `sym` is on the set
+    pub fn contains(&self, sym: S) -> bool {
+        let (word, bit) = split(sym);
+        self.words.get(word).is_some_and(|w| w & bit != 0)
+    }
+
+    /// Iterates over the symbols, in increasing order
+    pub fn iter(&self) -> impl Iterator<Item = S> + '_ {
+        self.words.iter().enumerate().flat_map(|(i, &word)| {
+            let mut word = word;
+            core::iter::from_fn(move || {
+                if word == 0 {
+                    return None;
+                }
+                let bit = word.trailing_zeros() as usize;
+                word &= word - 1;
+                Some(S::from_index(i * WORD_BITS + bit))
+            })
+        })
+    }
+}
+
+/// Returns the word index and bit mask of `sym`
+fn split<S: SymbolIndex>(sym: S) -> (usize, u64) {
+    let index = sym.to_index();
+    (index / WORD_BITS, 1 << (index % WORD_BITS))
+}
+
+impl<S> Default for SymbolSet<S> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<S> Clone for SymbolSet<S> {
+    fn clone(&self) -> Self {
+        Self {
+            words: self.words.clone(),
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<S: SymbolIndex + fmt::Debug> fmt::Debug for SymbolSet<S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
+
+impl<S> PartialEq for SymbolSet<S> {
+    fn eq(&self, other: &Self) -> bool {
+        let (short, long) = if self.words.len() <= other.words.len() {
+            (&self.words, &other.words)
+        } else {
+            (&other.words, &self.words)
+        };
+        long[..short.len()] == short[..] && long[short.len()..].iter().all(|&w| w == 0)
+    }
+}
+
+impl<S> Eq for SymbolSet<S> {}
+
+impl<S> BitOr for &SymbolSet<S> {
+    type Output = SymbolSet<S>;
+
+    /// Returns the union of both sets
+    fn bitor(self, other: Self) -> SymbolSet<S> {
+        let mut set = self.clone();
+        set.union_with(other);
+        set
+    }
+}
+
+impl<S> BitAnd for &SymbolSet<S> {
+    type Output = SymbolSet<S>;
+
+    /// Returns the intersection of both sets
+    fn bitand(self, other: Self) -> SymbolSet<S> {
+        let mut set = self.clone();
+        set.intersect_with(other);
+        set
+    }
+}
+
+impl<S> Sub for &SymbolSet<S> {
+    type Output = SymbolSet<S>;
+
+    /// Returns the symbols of `self` that are not on `other`
+    fn sub(self, other: Self) -> SymbolSet<S> {
+        let mut set = self.clone();
+        set.difference_with(other);
+        set
+    }
+}
+
+impl<S: SymbolIndex> Extend<S> for SymbolSet<S> {
+    fn extend<I: IntoIterator<Item = S>>(&mut self, iter: I) {
+        for sym in iter {
+            self.insert(sym);
+        }
+    }
+}
+
+impl<S: SymbolIndex> FromIterator<S> for SymbolSet<S> {
+    fn from_iter<I: IntoIterator<Item = S>>(iter: I) -> Self {
+        let mut set = Self::new();
+        set.extend(iter);
+        set
+    }
+}
+
+impl<T, B, H> Interner<T, B, H>
+where
+    T: Hash + Eq + ?Sized,
+    B: Backend<T>,
+    H: BuildHasher,
+    B::Symbol: SymbolIndex,
+{
+    /// Returns the set of all the symbols of this interner
+    pub fn symbol_set(&self) -> SymbolSet<B::Symbol> {
+        self.iter().map(|(sym, _)| sym).collect()
+    }
+
+    /// Builds a new interner with the values of the symbols in `keep`
+    ///
+    /// The values are interned in the order of their symbols. The returned
+    /// [RemapTable] maps the symbols of this interner to the new ones.
+    /// Symbols of `keep` that don't resolve are ignored.
+    ///
+    /// # Example
+    /// ```
+    /// use interns::Interner;
+    /// use interns::collections::SymbolSet;
+    ///
+    /// let mut vocab = Interner::<String>::new();
+    /// let words: Vec<_> = ["the", "cat", "a", "sat"].iter().map(|w| vocab.get_or_intern(*w)).collect();
+    /// let stopwords: SymbolSet<_> = [words[0], words[2]].into_iter().collect();
+    ///
+    /// let (pruned, remap) = vocab.sub_interner(&(&vocab.symbol_set() - &stopwords));
+    /// assert_eq!(pruned.len(), 2);
+    /// assert_eq!(remap.get(words[0]), None);
+    /// let cat = remap.get(words[1]).unwrap();
+    /// assert_eq!(pruned.resolve(cat).map(String::as_str), Some("cat"));
+    /// ```
+    pub fn sub_interner(&self, keep: &SymbolSet<B::Symbol>) -> (Self, RemapTable<B::Symbol>)
+    where
+        T: Internable<T, B>,
+        B: Default,
+        H: Default,
+    {
+        let mut sub = Self::with_capacity(keep.len());
+        let mut remap = RemapTable::with_capacity(keep.len());
+        for sym in keep.iter() {
+            if let Some(value) = self.resolve(sym) {
+                remap.insert(sym, sub.get_or_intern(value));
+            }
+        }
+        (sub, remap)
+    }
+}
+
+impl<T, B, H> Interner<T, B, H>
+where
+    T: Hash + Eq + ?Sized,
+    B: DenseSymbols<T>,
+    H: BuildHasher,
+{
+    /// Returns the dense index of `sym`
+    ///
+    /// See [DenseSymbols] for more details.
+    pub fn index_of(&self, sym: B::Symbol) -> Option<usize> {
+        self.backend.index_of(sym)
+    }
+
+    /// Returns the symbol with the dense index `idx`
+    ///
+    /// See [DenseSymbols] for more details.
+    pub fn symbol_at(&self, idx: usize) -> Option<B::Symbol> {
+        self.backend.symbol_at(idx)
+    }
+}
@@ -0,0 +1,162 @@
+//! Removal of values, and compaction of the backend
+
+use core::borrow::Borrow;
+use core::hash::{BuildHasher, Hash};
+use core::mem;
+
+use hashbrown::HashTable;
+
+use crate::backend::Internable;
+use crate::{Backend, Interner, RemapTable};
+
+impl<T, B, H> Interner<T, B, H>
+where
+    T: Hash + Eq + ?Sized,
+    B: Backend<T>,
+    H: BuildHasher,
+{
+    /// Removes `src` from the interner, and returns its symbol
+    ///
+    /// The value is only removed from the deduplication set: it stays on
+    /// the backend, and its symbol still resolves, until the interner is
+    /// [compacted](Self::compact). Interning it again stores a new copy,
+    /// under a new symbol.
+    ///
+    /// # Example
+    /// ```
+    /// use interns::StringInterner;
+    ///
+    /// let mut interner = StringInterner::new();
+    /// let tmp = interner.get_or_intern("tmp");
+    /// assert_eq!(interner.remove("tmp"), Some(tmp));
+    /// assert!(!interner.contains("tmp"));
+    /// assert_eq!(interner.remove("tmp"), None);
+    /// ```
+    pub fn remove<Ref>(&mut self, src: &Ref) -> Option<B::Symbol>
+    where
+        Ref: ?Sized + Hash + Eq,
+        T: Borrow<Ref>,
+    {
+        let Self { backend, set, hasher, .. } = self;
+        let hash = hasher.hash_one(src);
+        let entry = set.find_entry(hash, |&sym| {
+            /* SAFETY: If the symbol is on the table it must also be on the backend. */
+            src == unsafe { backend.get_unchecked(sym) }.borrow()
+        });
+        let (sym, _) = entry.ok()?.remove();
+        self.record_removal(sym);
+        self.touch_removed();
+        Some(sym)
+    }
+
+    /// Keeps only the values for which `f` returns true
+    ///
+    /// Like on [remove](Self::remove), the removed values stay
+    /// on the backend until the interner is [compacted](Self::compact).
+    ///
+    /// # Example
+    /// ```
+    /// use interns::StringInterner;
+    ///
+    /// let mut interner = StringInterner::from_lines(["a", "tmp1", "b", "tmp2"]);
+    /// interner.retain(|_, s| !s.starts_with("tmp"));
+    /// assert_eq!(interner.len(), 2);
+    /// ```
+    pub fn retain(&mut self, mut f: impl FnMut(B::Symbol, &T) -> bool) {
+        let len = self.len();
+        let Self { backend, set, usage, .. } = self;
+        set.retain(|sym| {
+            /* SAFETY: If the symbol is on the table it must also be on the backend. */
+            let value = unsafe { backend.get_unchecked(*sym) };
+            let keep = f(*sym, value);
+            if !keep {
+                usage.removed(size_of_val(value));
+            }
+            keep
+        });
+        if self.len() != len {
+            self.touch_removed();
+        }
+    }
+
+    /// Rebuilds the backend with only the values of the interner
+    ///
+    /// This reclaims the space of the values that were [removed](Self::remove).
+    /// The values are moved to a new [default](Default) backend, in the order
+    /// of their symbols, and get new symbols. The returned [RemapTable] maps
+    /// every old symbol to its new one.
+    ///
+    /// The new backend doesn't keep the configuration of the old one
+    /// (e.g. a [validating](crate::StringBackend::validating) backend).
+    /// Use [compact_with](Self::compact_with) to provide it.
+    ///
+    /// # Example
+    /// ```
+    /// use interns::StringInterner;
+    ///
+    /// let mut interner = StringInterner::new();
+    /// let big = interner.get_or_intern("a big temporary string");
+    /// let x = interner.get_or_intern("x");
+    ///
+    /// interner.remove("a big temporary string");
+    /// let remap = interner.compact();
+    /// assert_eq!(remap.get(big), None);
+    ///
+    /// let x = remap.get(x).unwrap();
+    /// assert_eq!(interner.resolve(x), Some("x"));
+    /// ```
+    pub fn compact(&mut self) -> RemapTable<B::Symbol>
+    where
+        T: Internable<T, B>,
+        B: Default,
+        B::Symbol: Ord,
+    {
+        self.compact_with(B::default())
+    }
+
+    /// Like [compact](Self::compact), but moves the values to `backend`
+    ///
+    /// `backend` should be empty. The values already
+    /// on it are not added to the interner.
+    ///
+    /// # Example
+    /// ```
+    /// use interns::{StringBackend, StringInterner};
+    ///
+    /// let mut interner = StringInterner::with_backend(StringBackend::validating());
+    /// interner.get_or_intern("old");
+    /// interner.remove("old");
+    ///
+    /// interner.compact_with(StringBackend::validating());
+    /// assert!(interner.is_empty());
+    /// ```
+    pub fn compact_with(&mut self, backend: B) -> RemapTable<B::Symbol>
+    where
+        T: Internable<T, B>,
+        B::Symbol: Ord,
+    {
+        let syms = self.ordered_symbols();
+        let old = mem::replace(&mut self.backend, backend);
+        let Self { backend, set, hasher, .. } = self;
+
+        let mut remap = RemapTable::with_capacity(syms.len());
+        *set = HashTable::with_capacity(syms.len());
+        for old_sym in syms {
+            /* SAFETY: The symbol was on the table, so it's on the old backend */
+            let value = unsafe { old.get_unchecked(old_sym) };
+            let hash = hasher.hash_one(value);
+            let sym = backend.intern(value);
+            set.insert_unique(hash, sym, |sym| {
+                /* SAFETY: Every symbol on the table has been interned on the new backend */
+                let src = unsafe { backend.get_unchecked(*sym) };
+                hasher.hash_one(src)
+            });
+            remap.insert(old_sym, sym);
+        }
+        self.touch_removed();
+        if let Some((_, sym)) = remap.iter().next() {
+            self.debug_check_invariants(sym);
+        }
+        remap
+    }
+}
@@ -0,0 +1,28 @@
+//! Owned string resolution through [compact_str]
+
+use core::hash::BuildHasher;
+
+use compact_str::CompactString;
+
+use crate::{Backend, Interner};
+
+impl<B, H> Interner<str, B, H>
+where
+    B: Backend<str>,
+    H: BuildHasher,
+{
+    /// Resolves the [symbol](Backend::Symbol) into a [CompactString], which stores short strings inline
+    ///
+    /// # Example
+    /// ```
+    /// use interns::StringInterner;
+    ///
+    /// let mut interner = StringInterner::new();
+    /// let sym = interner.get_or_intern("hello");
+    /// let owned = interner.resolve_compact(sym).unwrap();
+    /// assert_eq!(owned, "hello");
+    /// ```
+    pub fn resolve_compact(&self, sym: B::Symbol) -> Option<CompactString> {
+        self.resolve(sym).map(CompactString::new)
+    }
+}
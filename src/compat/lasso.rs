@@ -0,0 +1,121 @@
+//! Implementations of [lasso]'s traits
+//!
+//! This allows code written against lasso's [`Interner`](lasso::Interner),
+//! [`Reader`](lasso::Reader) and [`Resolver`](lasso::Resolver) traits to use
+//! a [StringInterner](crate::StringInterner) instead.
+
+use core::hash::BuildHasher;
+use std::sync::OnceLock;
+
+use lasso::{Capacity, Key, LassoError, LassoResult, MemoryLimits, Rodeo};
+
+use crate::backend::string::Symbol;
+use crate::{Backend, InternError, Interner, StringBackend};
+
+/* SAFETY: `as_usize` and `from_usize` are perfectly symmetrical */
+unsafe impl Key for Symbol {
+    fn into_usize(self) -> usize {
+        self.as_usize()
+    }
+
+    fn try_from_usize(int: usize) -> Option<Self> {
+        Some(Symbol::from_usize(int))
+    }
+}
+
+impl<H: BuildHasher> lasso::Resolver<Symbol> for Interner<str, StringBackend, H> {
+    fn resolve<'a>(&'a self, key: &Symbol) -> &'a str {
+        Interner::resolve(self, *key).expect("Key out of bounds")
+    }
+
+    fn try_resolve<'a>(&'a self, key: &Symbol) -> Option<&'a str> {
+        Interner::resolve(self, *key)
+    }
+
+    unsafe fn resolve_unchecked<'a>(&'a self, key: &Symbol) -> &'a str {
+        /* SAFETY: The caller guarantees that the key belongs to this interner */
+        unsafe { self.backend.get_unchecked(*key) }
+    }
+
+    fn contains_key(&self, key: &Symbol) -> bool {
+        Interner::resolve(self, *key).is_some()
+    }
+
+    fn len(&self) -> usize {
+        Interner::len(self)
+    }
+}
+
+impl<H: BuildHasher> lasso::Reader<Symbol> for Interner<str, StringBackend, H> {
+    fn get(&self, val: &str) -> Option<Symbol> {
+        Interner::get(self, val)
+    }
+
+    fn contains(&self, val: &str) -> bool {
+        Interner::contains(self, val)
+    }
+}
+
+impl<H: BuildHasher> lasso::Interner<Symbol> for Interner<str, StringBackend, H> {
+    fn get_or_intern(&mut self, val: &str) -> Symbol {
+        Interner::get_or_intern(self, val)
+    }
+
+    fn try_get_or_intern(&mut self, val: &str) -> LassoResult<Symbol> {
+        Interner::try_get_or_intern(self, val).map_err(lasso_error)
+    }
+
+    fn get_or_intern_static(&mut self, val: &'static str) -> Symbol {
+        Interner::get_or_intern(self, val)
+    }
+
+    fn try_get_or_intern_static(&mut self, val: &'static str) -> LassoResult<Symbol> {
+        Interner::try_get_or_intern(self, val).map_err(lasso_error)
+    }
+}
+
+/// Key that can't represent any index
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+struct NoKeys;
+
+/* SAFETY: No key is ever produced */
+unsafe impl Key for NoKeys {
+    fn into_usize(self) -> usize {
+        unreachable!("NoKeys has no values")
+    }
+
+    fn try_from_usize(_: usize) -> Option<Self> {
+        None
+    }
+}
+
+/// Converts an [InternError] into a [LassoError]
+///
+/// A [Full](InternError::Full) backend maps to
+/// [KeySpaceExhaustion](lasso::LassoErrorKind::KeySpaceExhaustion), and the
+/// rest of the errors to [MemoryLimitReached](lasso::LassoErrorKind::MemoryLimitReached).
+///
+/// [LassoError] can't be built outside of lasso, so the first time each
+/// kind is needed, a [Rodeo] is made fail with it. The error is cached, and
+/// cloned afterwards.
+pub(crate) fn lasso_error(err: InternError) -> LassoError {
+    static KEY_SPACE_EXHAUSTION: OnceLock<LassoError> = OnceLock::new();
+    static MEMORY_LIMIT_REACHED: OnceLock<LassoError> = OnceLock::new();
+
+    let err = match err {
+        InternError::Full => KEY_SPACE_EXHAUSTION.get_or_init(|| {
+            Rodeo::<NoKeys>::new().try_get_or_intern("").expect_err("The rodeo must fail")
+        }),
+        InternError::CapacityOverflow | InternError::Backend(_) => {
+            MEMORY_LIMIT_REACHED.get_or_init(|| {
+                /* The value doesn't fit on the first (1 byte) bucket,
+                 * and there's no memory left for another one */
+                let limits = MemoryLimits::for_memory_usage(0);
+                Rodeo::<lasso::Spur>::with_capacity_and_memory_limits(Capacity::minimal(), limits)
+                    .try_get_or_intern("__")
+                    .expect_err("The rodeo must fail")
+            })
+        }
+    };
+    err.clone()
+}
@@ -0,0 +1,23 @@
+//! Integration with other crates
+
+/* Our symbols are encoded as a 64-bit usize for these crates */
+
+#[cfg(all(any(feature = "lasso", feature = "string-interner"), not(target_pointer_width = "64")))]
+compile_error!("the `lasso` and `string-interner` features are only supported on 64-bit targets");
+
+#[cfg(all(feature = "lasso", target_pointer_width = "64"))]
+pub(crate) mod lasso;
+
+#[cfg(all(feature = "string-interner", target_pointer_width = "64"))]
+mod string_interner;
+#[cfg(all(feature = "string-interner", target_pointer_width = "64"))]
+pub use string_interner::SymbolOutOfRange;
+
+#[cfg(feature = "string_cache")]
+mod string_cache;
+
+#[cfg(feature = "smol_str")]
+mod smol_str;
+
+#[cfg(feature = "compact_str")]
+mod compact_str;
@@ -0,0 +1,28 @@
+//! Owned string resolution through [smol_str]
+
+use core::hash::BuildHasher;
+
+use smol_str::SmolStr;
+
+use crate::{Backend, Interner};
+
+impl<B, H> Interner<str, B, H>
+where
+    B: Backend<str>,
+    H: BuildHasher,
+{
+    /// Resolves the [symbol](Backend::Symbol) into a [SmolStr], which is cheap to clone
+    ///
+    /// # Example
+    /// ```
+    /// use interns::StringInterner;
+    ///
+    /// let mut interner = StringInterner::new();
+    /// let sym = interner.get_or_intern("hello");
+    /// let owned = interner.resolve_smol(sym).unwrap();
+    /// assert_eq!(owned, "hello");
+    /// ```
+    pub fn resolve_smol(&self, sym: B::Symbol) -> Option<SmolStr> {
+        self.resolve(sym).map(SmolStr::new)
+    }
+}
@@ -0,0 +1,57 @@
+//! Bridge between [string_cache]'s [Atom]s and interned symbols
+//!
+//! Tools that already pass [Atom]s around can intern them into an
+//! [Interner] without going through an intermediate [String], and
+//! get them back as atoms when needed.
+
+use core::hash::BuildHasher;
+
+use string_cache::{Atom, StaticAtomSet};
+
+use crate::backend::Internable;
+use crate::{Backend, Interner};
+
+impl<B, H> Interner<str, B, H>
+where
+    B: Backend<str>,
+    H: BuildHasher,
+    str: Internable<str, B>,
+{
+    /// Gets the [Symbol](Backend::Symbol) for the given [Atom],
+    /// interning it if it doesn't exist.
+    ///
+    /// # Example
+    /// ```
+    /// use interns::StringInterner;
+    /// use string_cache::DefaultAtom;
+    ///
+    /// let mut interner = StringInterner::new();
+    /// let atom = DefaultAtom::from("div");
+    /// let sym = interner.get_or_intern_atom(&atom);
+    /// assert_eq!(sym, interner.get_or_intern("div"));
+    /// ```
+    pub fn get_or_intern_atom<S: StaticAtomSet>(&mut self, atom: &Atom<S>) -> B::Symbol {
+        self.get_or_intern(&**atom)
+    }
+
+    /// Gets the [Symbol](Backend::Symbol) for the given [Atom], if it's been interned
+    pub fn get_atom<S: StaticAtomSet>(&self, atom: &Atom<S>) -> Option<B::Symbol> {
+        self.get(&**atom)
+    }
+
+    /// Resolves the [symbol](Backend::Symbol) into an [Atom]
+    ///
+    /// # Example
+    /// ```
+    /// use interns::StringInterner;
+    /// use string_cache::DefaultAtom;
+    ///
+    /// let mut interner = StringInterner::new();
+    /// let sym = interner.get_or_intern("span");
+    /// let atom: DefaultAtom = interner.resolve_atom(sym).unwrap();
+    /// assert_eq!(&*atom, "span");
+    /// ```
+    pub fn resolve_atom<S: StaticAtomSet>(&self, sym: B::Symbol) -> Option<Atom<S>> {
+        self.resolve(sym).map(Atom::from)
+    }
+}
@@ -0,0 +1,102 @@
+//! Interoperability with the [string_interner] crate
+//!
+//! Our [Symbol] implements string_interner's [`Symbol`](string_interner::Symbol)
+//! trait, and can be [encoded](Symbol::encode_foreign) into its symbol types, to be
+//! stored where those are expected. This makes it possible to migrate a codebase
+//! piece by piece.
+//!
+//! The symbol types of string_interner also implement `TryFrom<Symbol>`,
+//! and Symbol implements `From` them. These conversions use the same
+//! opaque encoding.
+
+use string_interner::symbol::{SymbolU16, SymbolU32, SymbolUsize};
+
+use crate::backend::string::Symbol;
+
+impl string_interner::Symbol for Symbol {
+    fn try_from_usize(index: usize) -> Option<Self> {
+        Some(Symbol::from_usize(index))
+    }
+
+    fn to_usize(self) -> usize {
+        self.as_usize()
+    }
+}
+
+/// Error returned when a [Symbol] doesn't fit into
+/// the target string_interner symbol type
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SymbolOutOfRange;
+
+impl core::fmt::Display for SymbolOutOfRange {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str("symbol out of range for the target type")
+    }
+}
+
+impl std::error::Error for SymbolOutOfRange {}
+
+impl Symbol {
+    /// Encodes this symbol into a string_interner symbol type `S`
+    ///
+    /// The result is opaque: it packs the [u64](Self::as_u64) representation
+    /// of this symbol, and doesn't point to the same string on a string_interner
+    /// interner. It's only meaningful to [decode](Self::decode_foreign) it back.
+    ///
+    /// Fails if the representation doesn't fit on `S`, like when encoding
+    /// a symbol with a non-zero offset into a [SymbolU32].
+    ///
+    /// # Example
+    /// ```
+    /// use interns::StringInterner;
+    /// use interns::backend::string::Symbol;
+    /// use string_interner::symbol::{SymbolU16, SymbolUsize};
+    ///
+    /// let mut interner = StringInterner::new();
+    /// let hello = interner.get_or_intern("hello");
+    /// let world = interner.get_or_intern("world");
+    ///
+    /// let encoded: SymbolUsize = world.encode_foreign().unwrap();
+    /// assert_eq!(Symbol::decode_foreign(encoded), world);
+    /// assert!(world.encode_foreign::<SymbolU16>().is_err());
+    /// assert!(hello.encode_foreign::<SymbolU16>().is_ok());
+    /// ```
+    pub fn encode_foreign<S: string_interner::Symbol>(self) -> Result<S, SymbolOutOfRange> {
+        /* string_interner truncates out of range values, so
+         * we need to check that the conversion roundtrips */
+        let n = self.as_usize();
+        S::try_from_usize(n).filter(|s| s.to_usize() == n).ok_or(SymbolOutOfRange)
+    }
+
+    /// Decodes a symbol encoded with [encode_foreign](Self::encode_foreign)
+    ///
+    /// Symbols that weren't encoded by [encode_foreign](Self::encode_foreign)
+    /// decode into meaningless symbols.
+    pub fn decode_foreign<S: string_interner::Symbol>(sym: S) -> Self {
+        Symbol::from_usize(sym.to_usize())
+    }
+}
+
+macro_rules! impl_conversions {
+    ($($t:ty),*) => {
+        $(
+            impl TryFrom<Symbol> for $t {
+                type Error = SymbolOutOfRange;
+
+                /// Same as [Symbol::encode_foreign]
+                fn try_from(sym: Symbol) -> Result<Self, Self::Error> {
+                    sym.encode_foreign()
+                }
+            }
+
+            impl From<$t> for Symbol {
+                /// Same as [Symbol::decode_foreign]
+                fn from(sym: $t) -> Self {
+                    Symbol::decode_foreign(sym)
+                }
+            }
+        )*
+    };
+}
+
+impl_conversions!(SymbolU16, SymbolU32, SymbolUsize);
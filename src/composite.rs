@@ -0,0 +1,83 @@
+//! Interning of composite keys
+//!
+//! A composite key is a tuple of symbols of other interners, like the
+//! (module, name) of a qualified name. A [PairInterner] gives each pair a
+//! compact symbol of its own. Since the pairs are made of symbols, interning
+//! them hashes and compares the symbols, not the values behind them.
+//!
+//! # Example
+//! ```
+//! use interns::StringInterner;
+//! use interns::backend::string::Symbol;
+//! use interns::composite::PairInterner;
+//!
+//! let mut names = StringInterner::new();
+//! let mut qualified = PairInterner::<Symbol>::new();
+//!
+//! let std = names.get_or_intern("std");
+//! let vec = names.get_or_intern("Vec");
+//! let std_vec = qualified.get_or_intern_pair(std, vec);
+//!
+//! assert_eq!(qualified.get_pair(std, vec), Some(std_vec));
+//! assert_eq!(qualified.resolve_pair(std_vec), Some((std, vec)));
+//! assert_eq!(qualified.resolve_parts(std_vec, &names), Some(("std", "Vec")));
+//! ```
+
+use core::hash::{BuildHasher, Hash};
+
+use crate::backend::{Backend, Internable, VecBackend};
+use crate::hash::DefaultHashBuilder;
+use crate::{Interner, Resolve};
+
+/// Symbol of the pairs of a [PairInterner]
+pub type PairSymbol = <VecBackend<()> as Backend<()>>::Symbol;
+
+/// Interner for pairs of symbols
+///
+/// See the [module-level documentation](self).
+pub type PairInterner<A, B = A, H = DefaultHashBuilder> = Interner<(A, B), VecBackend<(A, B)>, H>;
+
+impl<A, C, B, H> Interner<(A, C), B, H>
+where
+    A: Copy + Hash + Eq,
+    C: Copy + Hash + Eq,
+    B: Backend<(A, C)>,
+    H: BuildHasher,
+    (A, C): Internable<(A, C), B>,
+{
+    /// Gets the symbol for the pair `(first, second)`, interning it if it doesn't exist
+    pub fn get_or_intern_pair(&mut self, first: A, second: C) -> B::Symbol {
+        self.get_or_intern(&(first, second))
+    }
+
+    /// Gets the symbol for the pair `(first, second)`, without interning it
+    pub fn get_pair(&self, first: A, second: C) -> Option<B::Symbol> {
+        self.get(&(first, second))
+    }
+
+    /// Resolves the symbol into its pair
+    pub fn resolve_pair(&self, sym: B::Symbol) -> Option<(A, C)> {
+        self.resolve(sym).copied()
+    }
+}
+
+impl<A, B, H> Interner<(A, A), B, H>
+where
+    A: Copy + Hash + Eq,
+    B: Backend<(A, A)>,
+    H: BuildHasher,
+    (A, A): Internable<(A, A), B>,
+{
+    /// Resolves the symbol into its pair, and then resolves
+    /// each part of the pair with `parts`
+    ///
+    /// Returns None if any of the symbols doesn't resolve.
+    pub fn resolve_parts<'r, V, R>(&self, sym: B::Symbol, parts: &'r R) -> Option<(&'r V, &'r V)>
+    where
+        V: ?Sized,
+        R: Resolve<V, Symbol = A> + ?Sized,
+    {
+        let (first, second) = self.resolve_pair(sym)?;
+        Some((parts.resolve(first)?, parts.resolve(second)?))
+    }
+}
@@ -0,0 +1,172 @@
+//! Frequency counting
+//!
+//! A [CountingInterner] keeps track of how many times each
+//! value was interned, which allows finding the most common ones.
+//!
+//! # Example
+//! ```
+//! use interns::counting::CountingInterner;
+//!
+//! let mut interner = CountingInterner::<str>::new();
+//! for word in "the cat and the hat and the bat".split(' ') {
+//!     interner.get_or_intern(word);
+//! }
+//!
+//! let the = interner.get("the").unwrap();
+//! assert_eq!(interner.count(the), 3);
+//!
+//! let top: Vec<_> = interner
+//!     .most_common(2)
+//!     .into_iter()
+//!     .map(|(sym, n)| (interner.resolve(sym).unwrap(), n))
+//!     .collect();
+//! assert_eq!(top, [("the", 3), ("and", 2)]);
+//! ```
+
+use core::borrow::Borrow;
+use core::hash::{BuildHasher, Hash};
+use std::collections::HashMap;
+
+use crate::backend::{DefaultBackend, DefaultBackendBuilder, Internable};
+use crate::hash::DefaultHashBuilder;
+use crate::{Backend, Interner};
+
+/// Interner that counts how many times each value is requested
+///
+/// See the [module-level documentation](self) for more details.
+pub struct CountingInterner<T, B = DefaultBackend<T>, H = DefaultHashBuilder>
+where
+    T: Hash + Eq + ?Sized,
+    B: Backend<T>,
+    H: BuildHasher,
+{
+    inner: Interner<T, B, H>,
+    /// Symbols with their count, in the order they were first seen
+    counts: Vec<(B::Symbol, u64)>,
+    /// Position of each symbol on `counts`
+    index: HashMap<B::Symbol, usize>,
+}
+
+impl<T> CountingInterner<T>
+where
+    T: Hash + Eq + ?Sized + DefaultBackendBuilder,
+    DefaultBackend<T>: Default,
+{
+    /// Creates an empty interner
+    pub fn new() -> Self {
+        Self::with_interner(Interner::new())
+    }
+}
+
+impl<T, B, H> CountingInterner<T, B, H>
+where
+    T: Hash + Eq + ?Sized,
+    B: Backend<T>,
+    H: BuildHasher,
+{
+    /// Wraps `interner`
+    ///
+    /// The values already on `interner` start with a count of 0.
+    pub fn with_interner(interner: Interner<T, B, H>) -> Self {
+        Self {
+            inner: interner,
+            counts: Vec::new(),
+            index: HashMap::new(),
+        }
+    }
+
+    /// Gets the symbol for `src`, interning it if it doesn't exist,
+    /// and increments its count
+    pub fn get_or_intern<Ref>(&mut self, src: &Ref) -> B::Symbol
+    where
+        Ref: Internable<T, B> + ?Sized + Hash + Eq,
+        T: Borrow<Ref>,
+    {
+        let sym = self.inner.get_or_intern(src);
+        let i = *self.index.entry(sym).or_insert_with(|| {
+            self.counts.push((sym, 0));
+            self.counts.len() - 1
+        });
+        self.counts[i].1 += 1;
+        sym
+    }
+
+    /// Gets the symbol for `src`, without interning it
+    ///
+    /// This doesn't increment its count.
+    pub fn get<Ref>(&self, src: &Ref) -> Option<B::Symbol>
+    where
+        Ref: ?Sized + Hash + Eq,
+        T: Borrow<Ref>,
+    {
+        self.inner.get(src)
+    }
+
+    /// Resolves the symbol
+    pub fn resolve(&self, sym: B::Symbol) -> Option<&T> {
+        self.inner.resolve(sym)
+    }
+
+    /// Returns the number of unique values
+    pub fn len(&self) -> usize {
+        self.inner.len()
+    }
+
+    /// Returns true if nothing has been interned yet
+    pub fn is_empty(&self) -> bool {
+        self.inner.is_empty()
+    }
+
+    /// Returns how many times `sym` has been requested
+    pub fn count(&self, sym: B::Symbol) -> u64 {
+        self.index.get(&sym).map_or(0, |&i| self.counts[i].1)
+    }
+
+    /// Returns the total number of requests
+    pub fn total(&self) -> u64 {
+        self.counts.iter().map(|(_, n)| n).sum()
+    }
+
+    /// Iterates over the symbols and their counts, in the order they were first requested
+    pub fn counts(&self) -> impl Iterator<Item = (B::Symbol, u64)> + '_ {
+        self.counts.iter().copied()
+    }
+
+    /// Returns the `k` most requested symbols, with their counts
+    ///
+    /// The result is sorted by decreasing count. Symbols with the
+    /// same count are sorted in the order they were first requested.
+    pub fn most_common(&self, k: usize) -> Vec<(B::Symbol, u64)> {
+        let mut counts = self.counts.clone();
+        counts.sort_by_key(|&(_, n)| core::cmp::Reverse(n));
+        counts.truncate(k);
+        counts
+    }
+
+    /// Sets all the counts to 0
+    pub fn reset_counts(&mut self) {
+        self.counts.clear();
+        self.index.clear();
+    }
+
+    /// Returns the underlying interner
+    pub fn interner(&self) -> &Interner<T, B, H> {
+        &self.inner
+    }
+
+    /// Returns the underlying interner, discarding the counts
+    pub fn into_interner(self) -> Interner<T, B, H> {
+        self.inner
+    }
+}
+
+impl<T, B, H> Default for CountingInterner<T, B, H>
+where
+    T: Hash + Eq + ?Sized,
+    B: Backend<T> + Default,
+    H: BuildHasher + Default,
+{
+    fn default() -> Self {
+        Self::with_interner(Interner::new())
+    }
+}
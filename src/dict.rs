@@ -0,0 +1,139 @@
+//! Dictionary encoding of strings
+//!
+//! Dictionary encoding replaces each value of a column with a code: the
+//! position of the value on a dictionary of the unique values. It's the
+//! base of the string tables of many columnar and compressed formats.
+//!
+//! A [DictEncoder] builds the dictionary and the codes in a single pass.
+//! The dictionary is a [validating](StringBackend::validating) [StringInterner],
+//! and the codes are the [dense indices](crate::DenseSymbols) of its symbols,
+//! in order of first appearance.
+//!
+//! # Example
+//! ```
+//! use interns::dict::DictEncoder;
+//!
+//! let (codes, dict) = DictEncoder::encode(["red", "green", "red", "blue", "green"]);
+//! assert_eq!(codes, [0, 1, 0, 2, 1]);
+//! assert_eq!(dict.len(), 3);
+//!
+//! let values = DictEncoder::decode(&codes, &dict).unwrap();
+//! assert_eq!(values, ["red", "green", "red", "blue", "green"]);
+//! ```
+
+use crate::{Interner, StringBackend, StringInterner};
+
+/// Smallest dictionary capacity reserved from a size hint
+const MIN_DICT_CAPACITY: usize = 16;
+
+/// Encodes strings into codes of a dictionary
+///
+/// See the [module-level documentation](self) for more details.
+pub struct DictEncoder {
+    dict: StringInterner,
+    codes: Vec<u32>,
+}
+
+impl DictEncoder {
+    /// Creates an empty encoder
+    pub fn new() -> Self {
+        Self::with_capacity(0, 0)
+    }
+
+    /// Creates an empty encoder, with space for `values` codes,
+    /// and `unique` values on the dictionary
+    pub fn with_capacity(values: usize, unique: usize) -> Self {
+        let dict = Interner::with_backend_and_capacity(StringBackend::validating(), unique);
+        Self { dict, codes: Vec::with_capacity(values) }
+    }
+
+    /// Encodes all the values of `iter`
+    ///
+    /// Returns the codes of the values, and the dictionary.
+    ///
+    /// The capacity of the codes is taken from the [size hint](Iterator::size_hint)
+    /// of `iter`. The dictionary starts with space for the square root of it,
+    /// and grows as needed.
+    ///
+    /// # Panics
+    /// If there are more than [u32::MAX] unique values
+    pub fn encode<I>(iter: I) -> (Vec<u32>, StringInterner)
+    where
+        I: IntoIterator,
+        I::Item: AsRef<str>,
+    {
+        let iter = iter.into_iter();
+        let (values, _) = iter.size_hint();
+        let mut encoder = Self::with_capacity(values, values.isqrt().max(MIN_DICT_CAPACITY).min(values));
+        encoder.extend(iter);
+        encoder.finish()
+    }
+
+    /// Encodes `value`, adding it to the dictionary if it's new, and returns its code
+    ///
+    /// # Panics
+    /// If there are more than [u32::MAX] unique values
+    pub fn push(&mut self, value: &str) -> u32 {
+        let len = self.dict.len();
+        let sym = self.dict.get_or_intern(value);
+        let index = if self.dict.len() > len {
+            /* New values go at the end of the dictionary */
+            len
+        } else {
+            self.dict.index_of(sym).expect("The dictionary is validating")
+        };
+        let code = u32::try_from(index).expect("A DictEncoder can't hold more than u32::MAX unique values");
+        self.codes.push(code);
+        code
+    }
+
+    /// Returns the codes of the values encoded so far
+    pub fn codes(&self) -> &[u32] {
+        &self.codes
+    }
+
+    /// Returns the dictionary of the values encoded so far
+    pub fn dict(&self) -> &StringInterner {
+        &self.dict
+    }
+
+    /// Returns the codes, and the dictionary
+    pub fn finish(self) -> (Vec<u32>, StringInterner) {
+        (self.codes, self.dict)
+    }
+
+    /// Returns the values of the dictionary, indexed by their code
+    ///
+    /// Returns None if `dict` is not [validating](StringBackend::validating),
+    /// since the codes of its values are unknown.
+    pub fn dictionary(dict: &StringInterner) -> Option<Vec<&str>> {
+        let mut values = vec![""; dict.len()];
+        for (sym, value) in dict.iter() {
+            *values.get_mut(dict.index_of(sym)?)? = value;
+        }
+        Some(values)
+    }
+
+    /// Decodes the `codes` with the dictionary `dict`
+    ///
+    /// Returns None if any code is not on the dictionary,
+    /// or it's not [validating](StringBackend::validating).
+    pub fn decode<'a>(codes: &[u32], dict: &'a StringInterner) -> Option<Vec<&'a str>> {
+        let values = Self::dictionary(dict)?;
+        codes.iter().map(|&code| values.get(code as usize).copied()).collect()
+    }
+}
+
+impl Default for DictEncoder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<S: AsRef<str>> Extend<S> for DictEncoder {
+    fn extend<I: IntoIterator<Item = S>>(&mut self, iter: I) {
+        for value in iter {
+            self.push(value.as_ref());
+        }
+    }
+}
@@ -0,0 +1,79 @@
+//! Differences between interners
+//!
+//! [Interner::diff] lists the values that are only on one of two
+//! interners. This is useful for cache invalidation, or to check that
+//! two pipelines intern the same vocabulary.
+//!
+//! # Example
+//! ```
+//! use interns::StringInterner;
+//!
+//! let mut old = StringInterner::new();
+//! let mut new = StringInterner::new();
+//! old.get_or_intern("kept");
+//! let removed = old.get_or_intern("removed");
+//! new.get_or_intern("kept");
+//! let added = new.get_or_intern("added");
+//!
+//! let diff = old.diff(&new);
+//! assert_eq!(diff.only_left, [(removed, "removed")]);
+//! assert_eq!(diff.only_right, [(added, "added")]);
+//! ```
+
+use core::fmt;
+use core::hash::{BuildHasher, Hash};
+
+use crate::{Backend, Interner};
+
+/// Values that are only on one of two interners
+///
+/// Returned by [Interner::diff]. The order of the values is unspecified.
+pub struct Diff<'a, T: ?Sized, L, R> {
+    /// Values only on the left interner, with their symbols
+    pub only_left: Vec<(L, &'a T)>,
+    /// Values only on the right interner, with their symbols
+    pub only_right: Vec<(R, &'a T)>,
+}
+
+impl<T: ?Sized, L, R> Diff<'_, T, L, R> {
+    /// Returns true if both interners have the same values
+    pub fn is_empty(&self) -> bool {
+        self.only_left.is_empty() && self.only_right.is_empty()
+    }
+}
+
+impl<T, L, R> fmt::Debug for Diff<'_, T, L, R>
+where
+    T: fmt::Debug + ?Sized,
+    L: fmt::Debug,
+    R: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Diff")
+            .field("only_left", &self.only_left)
+            .field("only_right", &self.only_right)
+            .finish()
+    }
+}
+
+impl<T, B, H> Interner<T, B, H>
+where
+    T: Hash + Eq + ?Sized,
+    B: Backend<T>,
+    H: BuildHasher,
+{
+    /// Compares the values of this interner with the ones of `other`
+    ///
+    /// The symbols don't matter, only the values.
+    /// See the [module-level documentation](crate::diff).
+    pub fn diff<'a, B2, H2>(&'a self, other: &'a Interner<T, B2, H2>) -> Diff<'a, T, B::Symbol, B2::Symbol>
+    where
+        B2: Backend<T>,
+        H2: BuildHasher,
+    {
+        Diff {
+            only_left: self.iter().filter(|(_, value)| !other.contains(*value)).collect(),
+            only_right: other.iter().filter(|(_, value)| !self.contains(*value)).collect(),
+        }
+    }
+}
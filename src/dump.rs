@@ -0,0 +1,141 @@
+//! Human-readable dumps of an interner
+//!
+//! [Interner::dump] writes a table with the symbol, [offset](Backend::offset_of)
+//! and value of every interned value. The rows are sorted by offset, so two
+//! interners built the same way produce the same dump, and can be diffed.
+//!
+//! # Example
+//! ```
+//! use interns::Interner;
+//! use interns::dump::DumpOptions;
+//!
+//! let mut interner = Interner::<String>::new();
+//! interner.get_or_intern("hello");
+//! interner.get_or_intern("a much longer value");
+//!
+//! let mut out = String::new();
+//! interner.dump(&mut out, DumpOptions::new().max_value_width(8)).unwrap();
+//! assert_eq!(out, "\
+//! symbol     offset  value
+//! Symbol(0)       0  \"hello\"
+//! Symbol(1)       1  \"a much…
+//! ");
+//! ```
+
+use core::fmt::{self, Debug};
+use core::hash::{BuildHasher, Hash};
+
+use crate::{Backend, Interner};
+
+/// Options of [Interner::dump]
+///
+/// By default, all the rows are written, values are not
+/// truncated, and there's a header row.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct DumpOptions {
+    max_value_width: usize,
+    max_rows: usize,
+    header: bool,
+}
+
+impl DumpOptions {
+    /// Creates the default options
+    pub const fn new() -> Self {
+        Self {
+            max_value_width: usize::MAX,
+            max_rows: usize::MAX,
+            header: true,
+        }
+    }
+
+    /// Truncates the values to `width` characters
+    ///
+    /// Truncated values end with `…`.
+    pub const fn max_value_width(mut self, width: usize) -> Self {
+        self.max_value_width = width;
+        self
+    }
+
+    /// Writes at most `rows` values
+    ///
+    /// If there are more, a last line tells how many were omitted.
+    pub const fn max_rows(mut self, rows: usize) -> Self {
+        self.max_rows = rows;
+        self
+    }
+
+    /// Writes a header row with the names of the columns
+    pub const fn header(mut self, header: bool) -> Self {
+        self.header = header;
+        self
+    }
+}
+
+impl Default for DumpOptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Truncates `s` to `width` characters, marking it with `…`
+fn truncate(mut s: String, width: usize) -> String {
+    if let Some((i, _)) = s.char_indices().nth(width) {
+        let keep = s[..i].char_indices().nth(width.saturating_sub(1)).map_or(0, |(j, _)| j);
+        s.truncate(keep);
+        s.push('…');
+    }
+    s
+}
+
+impl<T, B, H> Interner<T, B, H>
+where
+    T: Hash + Eq + ?Sized,
+    B: Backend<T>,
+    H: BuildHasher,
+{
+    /// Writes a table with the contents of the interner
+    ///
+    /// Symbols and values are written with their [Debug] implementation.
+    /// See the [module-level documentation](crate::dump).
+    pub fn dump<W>(&self, out: &mut W, options: DumpOptions) -> fmt::Result
+    where
+        W: fmt::Write + ?Sized,
+        T: Debug,
+        B::Symbol: Debug,
+    {
+        let mut rows: Vec<_> = self
+            .iter()
+            .map(|(sym, value)| {
+                let offset = self.backend.offset_of(sym);
+                (offset, format!("{sym:?}"), value)
+            })
+            .collect();
+        /* Values without an offset go last */
+        rows.sort_unstable_by(|a, b| (a.0.is_none(), a.0, &a.1).cmp(&(b.0.is_none(), b.0, &b.1)));
+
+        let omitted = rows.len().saturating_sub(options.max_rows);
+        rows.truncate(options.max_rows);
+        let rows: Vec<_> = rows
+            .into_iter()
+            .map(|(offset, sym, value)| {
+                let offset = offset.map_or_else(|| "-".to_string(), |o| o.to_string());
+                (sym, offset, truncate(format!("{value:?}"), options.max_value_width))
+            })
+            .collect();
+
+        let (sym_header, offset_header) = if options.header { ("symbol", "offset") } else { ("", "") };
+        let sym_width = rows.iter().map(|r| r.0.chars().count()).chain([sym_header.len()]).max().unwrap_or(0);
+        let offset_width = rows.iter().map(|r| r.1.len()).chain([offset_header.len()]).max().unwrap_or(0);
+
+        if options.header {
+            writeln!(out, "{sym_header:<sym_width$}  {offset_header:>offset_width$}  value")?;
+        }
+        for (sym, offset, value) in &rows {
+            writeln!(out, "{sym:<sym_width$}  {offset:>offset_width$}  {value}")?;
+        }
+        if omitted > 0 {
+            writeln!(out, "... {omitted} more")?;
+        }
+        Ok(())
+    }
+}
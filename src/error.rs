@@ -0,0 +1,206 @@
+//! Errors returned by the fallible APIs of the [Interner](crate::Interner)
+
+use core::fmt;
+use std::collections::TryReserveError;
+use std::error::Error;
+
+/// Error returned when a value can't be interned
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum InternError {
+    /// Couldn't allocate memory for the value
+    CapacityOverflow,
+    /// The backend has no space left for the value
+    Full,
+    /// The backend failed to store the value
+    Backend(Box<dyn Error + Send + Sync>),
+}
+
+impl fmt::Display for InternError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InternError::CapacityOverflow => write!(f, "capacity overflow"),
+            InternError::Full => write!(f, "backend is full"),
+            InternError::Backend(err) => write!(f, "backend error: {err}"),
+        }
+    }
+}
+
+impl Error for InternError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            InternError::Backend(err) => Some(&**err),
+            _ => None,
+        }
+    }
+}
+
+impl From<TryReserveError> for InternError {
+    fn from(_: TryReserveError) -> Self {
+        InternError::CapacityOverflow
+    }
+}
+
+/// Error returned when a symbol can't be resolved
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ResolveError {
+    /// The symbol doesn't belong to the interner, for
+    /// a reason the backend doesn't know about
+    Unknown,
+    /// The index of the symbol is past the end of the backend
+    OutOfRange {
+        /// Index of the symbol
+        index: usize,
+        /// Number of elements on the backend
+        len: usize,
+    },
+    /// The symbol's slot has been reused since the symbol was produced
+    StaleGeneration {
+        /// Generation of the symbol
+        generation: u32,
+        /// Current generation of the slot
+        current: u32,
+    },
+    /// The symbol's span doesn't point to a value of the backend
+    InvalidSpan {
+        /// Start of the span
+        offset: usize,
+        /// Length of the span
+        len: usize,
+    },
+}
+
+impl fmt::Display for ResolveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ResolveError::Unknown => write!(f, "unknown symbol"),
+            ResolveError::OutOfRange { index, len } => {
+                write!(f, "symbol index {index} is out of range (len is {len})")
+            }
+            ResolveError::StaleGeneration { generation, current } => {
+                write!(f, "stale symbol of generation {generation} (current is {current})")
+            }
+            ResolveError::InvalidSpan { offset, len } => {
+                write!(f, "invalid span of {len} bytes at offset {offset}")
+            }
+        }
+    }
+}
+
+impl Error for ResolveError {}
+
+/// Error returned by [Interner::prefill](crate::Interner::prefill)
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum PrefillError {
+    /// A string appears more than once on the list
+    Duplicate {
+        /// The repeated string
+        string: String,
+        /// Position of its first occurrence
+        first: usize,
+        /// Position of the repeated occurrence
+        index: usize,
+    },
+    /// A string couldn't be interned
+    Intern(InternError),
+}
+
+impl fmt::Display for PrefillError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PrefillError::Duplicate { string, first, index } => {
+                write!(f, "duplicate string {string:?} at positions {first} and {index}")
+            }
+            PrefillError::Intern(err) => write!(f, "couldn't intern string: {err}"),
+        }
+    }
+}
+
+impl Error for PrefillError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            PrefillError::Intern(err) => Some(err),
+            PrefillError::Duplicate { .. } => None,
+        }
+    }
+}
+
+impl From<InternError> for PrefillError {
+    fn from(err: InternError) -> Self {
+        PrefillError::Intern(err)
+    }
+}
+
+/// Error returned when a string has an invalid [escape sequence](crate::escape)
+///
+/// The offsets are the byte positions, on the raw string,
+/// of the escape character that starts the sequence.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum EscapeError {
+    /// The string ends with an escape character
+    Trailing {
+        /// Position of the escape character
+        offset: usize,
+    },
+    /// The escaped character is not a known escape
+    Unknown {
+        /// Position of the escape sequence
+        offset: usize,
+        /// The escaped character
+        escape: char,
+    },
+    /// A `\x` escape isn't followed by two hex digits
+    InvalidHex {
+        /// Position of the escape sequence
+        offset: usize,
+    },
+    /// A `\u` escape isn't a valid `\u{NNNN}` character
+    InvalidUnicode {
+        /// Position of the escape sequence
+        offset: usize,
+    },
+}
+
+impl fmt::Display for EscapeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EscapeError::Trailing { offset } => write!(f, "unterminated escape at offset {offset}"),
+            EscapeError::Unknown { offset, escape } => {
+                write!(f, "unknown escape {escape:?} at offset {offset}")
+            }
+            EscapeError::InvalidHex { offset } => write!(f, "invalid hex escape at offset {offset}"),
+            EscapeError::InvalidUnicode { offset } => write!(f, "invalid unicode escape at offset {offset}"),
+        }
+    }
+}
+
+impl Error for EscapeError {}
+
+/// Error returned by [Interner::check_invariants](crate::Interner::check_invariants)
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct InvariantError {
+    message: String,
+}
+
+impl InvariantError {
+    /// Creates an error with a description of the violated invariant
+    pub fn new(message: impl Into<String>) -> Self {
+        Self { message: message.into() }
+    }
+
+    /// Returns the description of the violated invariant
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+}
+
+impl fmt::Display for InvariantError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl Error for InvariantError {}
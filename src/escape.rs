@@ -0,0 +1,143 @@
+//! Decoding of escape sequences
+//!
+//! [EscapeRules] describe the escape sequences of a string literal.
+//! [get_or_intern_unescaped](crate::Interner::get_or_intern_unescaped)
+//! decodes them while writing the string into the interner, so a lexer
+//! doesn't need a temporary buffer for each literal.
+//!
+//! # Example
+//! ```
+//! use interns::StringInterner;
+//! use interns::escape::EscapeRules;
+//!
+//! let mut interner = StringInterner::new();
+//! let sym = interner.get_or_intern_unescaped(r"tab\there \u{1F980}", EscapeRules::new()).unwrap();
+//! assert_eq!(interner.resolve(sym), Some("tab\there 🦀"));
+//! ```
+//!
+//! By default, the rules are the ones of Rust string literals:
+//!
+//! | Escape       | Value                               |
+//! |--------------|-------------------------------------|
+//! | `\n`         | Line feed                           |
+//! | `\r`         | Carriage return                     |
+//! | `\t`         | Tab                                 |
+//! | `\0`         | Null                                |
+//! | `\\`         | Backslash                           |
+//! | `\'`, `\"`   | Quotes                              |
+//! | `\xNN`       | The character with code point `NN`  |
+//! | `\u{NNNN}`   | The character with code point `NNNN`|
+
+use crate::EscapeError;
+
+/// Escape sequences accepted by
+/// [get_or_intern_unescaped](crate::Interner::get_or_intern_unescaped)
+///
+/// See the [module-level documentation](self) for more details.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct EscapeRules {
+    escape: char,
+    hex: bool,
+    unicode: bool,
+    strict: bool,
+}
+
+impl EscapeRules {
+    /// Creates the default rules
+    pub const fn new() -> Self {
+        Self {
+            escape: '\\',
+            hex: true,
+            unicode: true,
+            strict: true,
+        }
+    }
+
+    /// Character that starts an escape sequence
+    ///
+    /// By default, it's a backslash.
+    pub const fn escape_char(mut self, escape: char) -> Self {
+        self.escape = escape;
+        self
+    }
+
+    /// Accept `\xNN` escapes
+    pub const fn hex(mut self, hex: bool) -> Self {
+        self.hex = hex;
+        self
+    }
+
+    /// Accept `\u{NNNN}` escapes
+    pub const fn unicode(mut self, unicode: bool) -> Self {
+        self.unicode = unicode;
+        self
+    }
+
+    /// Reject unknown escapes
+    ///
+    /// If disabled, an unknown escape is replaced by the escaped
+    /// character (e.g. `\q` becomes `q`).
+    pub const fn strict(mut self, strict: bool) -> Self {
+        self.strict = strict;
+        self
+    }
+}
+
+impl Default for EscapeRules {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Appends `raw` to `out`, decoding its escape sequences
+///
+/// The decoded string is never longer than `raw`. On error,
+/// `out` may have been partially written.
+pub(crate) fn unescape_into(raw: &str, rules: EscapeRules, out: &mut String) -> Result<(), EscapeError> {
+    let mut chars = raw.char_indices();
+    while let Some((offset, c)) = chars.next() {
+        if c != rules.escape {
+            out.push(c);
+            continue
+        }
+        let Some((_, e)) = chars.next() else {
+            return Err(EscapeError::Trailing { offset })
+        };
+        let c = match e {
+            'n' => '\n',
+            'r' => '\r',
+            't' => '\t',
+            '0' => '\0',
+            '\'' | '"' => e,
+            e if e == rules.escape => e,
+            'x' if rules.hex => {
+                let digits = chars.as_str().get(..2).filter(|d| d.bytes().all(|b| b.is_ascii_hexdigit()));
+                let code = digits
+                    .and_then(|d| u8::from_str_radix(d, 16).ok())
+                    .ok_or(EscapeError::InvalidHex { offset })?;
+                chars.nth(1);
+                char::from(code)
+            }
+            'u' if rules.unicode => {
+                let rest = chars.as_str();
+                let digits = rest
+                    .strip_prefix('{')
+                    .and_then(|r| r.split_once('}'))
+                    .map(|(digits, _)| digits)
+                    .filter(|d| (1..=6).contains(&d.len()) && d.bytes().all(|b| b.is_ascii_hexdigit()))
+                    .ok_or(EscapeError::InvalidUnicode { offset })?;
+                let c = u32::from_str_radix(digits, 16)
+                    .ok()
+                    .and_then(char::from_u32)
+                    .ok_or(EscapeError::InvalidUnicode { offset })?;
+                /* Skip the braces and the digits */
+                chars.nth(digits.len() + 1);
+                c
+            }
+            e if !rules.strict => e,
+            e => return Err(EscapeError::Unknown { offset, escape: e }),
+        };
+        out.push(c);
+    }
+    Ok(())
+}
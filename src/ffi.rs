@@ -0,0 +1,142 @@
+//! C API for the [StringInterner]
+//!
+//! The interner is exposed as an opaque handle, created with [interner_new]
+//! and destroyed with [interner_free]. Symbols are passed by value as
+//! [InternerSymbol] structs.
+//!
+//! A C header with the declarations of this module can be found at
+//! `include/interns.h`. To build a shared library, run
+//! `cargo rustc --release --features ffi --crate-type cdylib`
+//!
+//! # Example (C)
+//! ```c
+//! Interner *interner = interner_new();
+//! InternerSymbol sym;
+//! if (interner_intern(interner, "hello", 5, &sym)) {
+//!     size_t len;
+//!     const char *s = interner_resolve(interner, sym, &len);
+//!     printf("%.*s\n", (int)len, s);
+//! }
+//! interner_free(interner);
+//! ```
+
+use core::{ptr, slice};
+
+use crate::backend::string::Symbol;
+use crate::StringInterner;
+
+/// A [Symbol] with a stable C layout
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct InternerSymbol {
+    pub offset: u32,
+    pub len: u32,
+}
+
+impl From<Symbol> for InternerSymbol {
+    fn from(sym: Symbol) -> Self {
+        Self { offset: sym.offset, len: sym.len }
+    }
+}
+
+impl From<InternerSymbol> for Symbol {
+    fn from(sym: InternerSymbol) -> Self {
+        Self { offset: sym.offset, len: sym.len }
+    }
+}
+
+/// Creates a new [StringInterner]
+///
+/// The returned pointer must be released with [interner_free]
+#[unsafe(no_mangle)]
+pub extern "C" fn interner_new() -> *mut StringInterner {
+    Box::into_raw(Box::new(StringInterner::new()))
+}
+
+/// Interns the `len` bytes pointed by `ptr`, and stores the symbol in `out`
+///
+/// Returns false if any of the pointers is NULL, the string
+/// is not valid UTF-8, or there's no memory left to store it.
+///
+/// # Safety
+/// - `interner` must be a pointer returned by [interner_new]
+/// - `ptr` must be valid for reads of `len` bytes
+/// - `out` must be valid for writes
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn interner_intern(
+    interner: *mut StringInterner,
+    ptr: *const u8,
+    len: usize,
+    out: *mut InternerSymbol,
+) -> bool {
+    if interner.is_null() || ptr.is_null() || out.is_null() {
+        return false;
+    }
+    /* SAFETY: The caller ensures that `ptr` is valid for `len` bytes */
+    let bytes = unsafe { slice::from_raw_parts(ptr, len) };
+    let Ok(src) = core::str::from_utf8(bytes) else { return false };
+    /* SAFETY: The caller ensures that `interner` and `out` are valid */
+    unsafe {
+        let Ok(sym) = (*interner).try_get_or_intern(src) else { return false };
+        out.write(sym.into());
+    }
+    true
+}
+
+/// Resolves `sym`, and returns a pointer to its bytes
+///
+/// The length of the string is stored in `out_len`. The string
+/// is NOT NUL-terminated. Returns NULL if the symbol doesn't
+/// belong to the interner.
+///
+/// The returned pointer is valid until the next call to
+/// [interner_intern] or [interner_free].
+///
+/// # Safety
+/// - `interner` must be a pointer returned by [interner_new]
+/// - `out_len` must be valid for writes
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn interner_resolve(
+    interner: *const StringInterner,
+    sym: InternerSymbol,
+    out_len: *mut usize,
+) -> *const u8 {
+    if interner.is_null() || out_len.is_null() {
+        return ptr::null();
+    }
+    /* SAFETY: The caller ensures that `interner` is valid */
+    let Some(s) = (unsafe { (*interner).resolve(sym.into()) }) else {
+        return ptr::null();
+    };
+    /* SAFETY: The caller ensures that `out_len` is valid */
+    unsafe { out_len.write(s.len()) };
+    s.as_ptr()
+}
+
+/// Returns the number of unique strings in the interner
+///
+/// # Safety
+/// `interner` must be a pointer returned by [interner_new]
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn interner_len(interner: *const StringInterner) -> usize {
+    if interner.is_null() {
+        return 0;
+    }
+    /* SAFETY: The caller ensures that `interner` is valid */
+    unsafe { (*interner).len() }
+}
+
+/// Frees an interner created with [interner_new]
+///
+/// Passing NULL is a no-op.
+///
+/// # Safety
+/// `interner` must be a pointer returned by [interner_new],
+/// and must not be used after this call.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn interner_free(interner: *mut StringInterner) {
+    if !interner.is_null() {
+        /* SAFETY: The pointer was created by Box::into_raw on `interner_new` */
+        drop(unsafe { Box::from_raw(interner) });
+    }
+}
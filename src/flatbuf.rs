@@ -0,0 +1,102 @@
+//! FlatBuffers string table export
+//!
+//! [Interner::write_flatbuffer] writes the interned strings as a FlatBuffer
+//! with the following schema:
+//!
+//! ```text
+//! table StringTable {
+//!   strings: [string];
+//! }
+//! root_type StringTable;
+//! ```
+//!
+//! The buffer can be read in place by any FlatBuffers implementation, and its
+//! strings can be referenced by index from other schemas.
+
+use core::hash::BuildHasher;
+use std::io::{self, Write};
+
+use crate::{Backend, Interner};
+
+/* Layout of the header of the buffer:
+ *
+ *  0: u32 offset to the root table (= 12)
+ *  4: vtable: [u16 vtable size, u16 table size, u16 offset of field 0]
+ * 10: 2 bytes of padding
+ * 12: table: [i32 offset to vtable, u32 offset to the strings vector]
+ * 20: vector: [u32 length, u32 offset to each string]
+ *
+ * Each string is [u32 length, bytes, NUL], padded to 4 bytes. */
+const VTABLE: usize = 4;
+const TABLE: usize = 12;
+const VECTOR: usize = 20;
+
+const fn padding(len: usize) -> usize {
+    (4 - len % 4) % 4
+}
+
+impl<B, H> Interner<str, B, H>
+where
+    B: Backend<str>,
+    B::Symbol: Ord,
+    H: BuildHasher,
+{
+    /// Writes all the interned strings as a FlatBuffers string table
+    ///
+    /// Returns the symbols of the strings, in the same order as they
+    /// appear on the table. The strings are ordered by symbol.
+    ///
+    /// See the [module-level documentation](crate::flatbuf) for the schema.
+    ///
+    /// # Example
+    /// ```
+    /// use interns::StringInterner;
+    ///
+    /// let interner = StringInterner::from_lines(["foo", "bar"]);
+    /// let mut buf = Vec::new();
+    /// let order = interner.write_flatbuffer(&mut buf).unwrap();
+    /// assert_eq!(order, [interner.get("foo").unwrap(), interner.get("bar").unwrap()]);
+    /// assert_eq!(buf.len() % 4, 0);
+    /// ```
+    pub fn write_flatbuffer<W: Write>(&self, mut w: W) -> io::Result<Vec<B::Symbol>> {
+        let syms = self.ordered_symbols();
+        let values = syms.iter().map(|&sym| {
+            /* SAFETY: every symbol on the set is on the backend */
+            unsafe { self.backend.get_unchecked(sym) }
+        });
+
+        let u32_at = |n: usize| {
+            u32::try_from(n).map_err(|_| io::Error::new(io::ErrorKind::InvalidData, "buffer too big"))
+        };
+
+        /* Header */
+        w.write_all(&(TABLE as u32).to_le_bytes())?;
+        w.write_all(&6u16.to_le_bytes())?;
+        w.write_all(&8u16.to_le_bytes())?;
+        w.write_all(&4u16.to_le_bytes())?;
+        w.write_all(&[0; 2])?;
+        w.write_all(&((TABLE - VTABLE) as i32).to_le_bytes())?;
+        w.write_all(&((VECTOR - (TABLE + 4)) as u32).to_le_bytes())?;
+
+        /* Vector of offsets */
+        w.write_all(&u32_at(syms.len())?.to_le_bytes())?;
+        let mut pos = VECTOR + 4 + syms.len() * 4;
+        for (i, value) in values.clone().enumerate() {
+            let slot = VECTOR + 4 + i * 4;
+            w.write_all(&u32_at(pos - slot)?.to_le_bytes())?;
+            let len = 4 + value.len() + 1;
+            pos += len + padding(len);
+        }
+        u32_at(pos)?;
+
+        /* Strings */
+        for value in values {
+            w.write_all(&u32_at(value.len())?.to_le_bytes())?;
+            w.write_all(value.as_bytes())?;
+            let len = 4 + value.len() + 1;
+            w.write_all(&[0; 4][..1 + padding(len)])?;
+        }
+
+        Ok(syms)
+    }
+}
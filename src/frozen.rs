@@ -0,0 +1,426 @@
+//! Read-only interners without a hash table
+//!
+//! [Interner::freeze] turns an interner into a [FrozenInterner], which
+//! drops the hash table, and keeps the symbols sorted by their values
+//! instead. Lookups are a binary search, so they're slower, but the
+//! index only takes one symbol per value.
+//!
+//! # Example
+//! ```
+//! use interns::StringInterner;
+//!
+//! let mut interner = StringInterner::new();
+//! let sym = interner.get_or_intern("frozen");
+//! interner.get_or_intern("solid");
+//!
+//! let frozen = interner.freeze();
+//! assert_eq!(frozen.get("frozen"), Some(sym));
+//! assert_eq!(frozen.resolve(sym), Some("frozen"));
+//! assert_eq!(frozen.get("melted"), None);
+//! ```
+//!
+//! [Interner::freeze_mph] builds a [minimal perfect hash](MphInterner)
+//! instead. Lookups are O(1), and the index takes one symbol per value,
+//! plus about 3 bits per value for the hash function.
+
+use core::borrow::Borrow;
+use core::hash::{BuildHasher, Hash};
+
+use crate::{Backend, Interner};
+
+/// Read-only interner that looks up values by binary search
+///
+/// See the [module-level documentation](self) for more details.
+pub struct FrozenInterner<T: ?Sized, B: Backend<T>> {
+    backend: B,
+    /// Symbols, sorted by their values
+    sorted: Box<[B::Symbol]>,
+}
+
+impl<T, B, H> Interner<T, B, H>
+where
+    T: Hash + Eq + Ord + ?Sized,
+    B: Backend<T>,
+    H: BuildHasher,
+{
+    /// Turns this interner into a read-only [FrozenInterner]
+    ///
+    /// The symbols stay the same.
+    pub fn freeze(self) -> FrozenInterner<T, B> {
+        let sorted = self.sorted_symbols().into_boxed_slice();
+        FrozenInterner {
+            backend: self.backend,
+            sorted,
+        }
+    }
+}
+
+impl<T, B> FrozenInterner<T, B>
+where
+    T: Ord + ?Sized,
+    B: Backend<T>,
+{
+    /// Gets the symbol for `src`
+    pub fn get<Ref>(&self, src: &Ref) -> Option<B::Symbol>
+    where
+        Ref: Ord + ?Sized,
+        T: Borrow<Ref>,
+    {
+        self.sorted
+            .binary_search_by(|&sym| {
+                /* SAFETY: The symbols come from the interner, so they're on the backend */
+                let value = unsafe { self.backend.get_unchecked(sym) };
+                value.borrow().cmp(src)
+            })
+            .ok()
+            .map(|i| self.sorted[i])
+    }
+
+    /// Returns true if `src` is on the interner
+    pub fn contains<Ref>(&self, src: &Ref) -> bool
+    where
+        Ref: Ord + ?Sized,
+        T: Borrow<Ref>,
+    {
+        self.get(src).is_some()
+    }
+
+    /// Resolves the symbol
+    pub fn resolve(&self, sym: B::Symbol) -> Option<&T> {
+        self.backend.get(sym)
+    }
+
+    /// Returns the number of values
+    pub fn len(&self) -> usize {
+        self.sorted.len()
+    }
+
+    /// Returns true if the interner is empty
+    pub fn is_empty(&self) -> bool {
+        self.sorted.is_empty()
+    }
+
+    /// Iterates over the symbols and their values, sorted by value
+    pub fn iter(&self) -> impl Iterator<Item = (B::Symbol, &T)> {
+        self.sorted.iter().map(|&sym| {
+            /* SAFETY: The symbols come from the interner, so they're on the backend */
+            (sym, unsafe { self.backend.get_unchecked(sym) })
+        })
+    }
+
+    /// Turns this back into a regular [Interner], rebuilding its hash table
+    ///
+    /// The symbols stay the same.
+    pub fn thaw<H>(self) -> Interner<T, B, H>
+    where
+        T: Hash + Eq,
+        H: BuildHasher + Default,
+    {
+        let mut interner = Interner::with_backend(self.backend);
+        for &sym in &self.sorted {
+            interner.insert_symbol(sym);
+        }
+        interner
+    }
+}
+
+/// Average number of keys per bucket of an [MphInterner]
+const LAMBDA: usize = 5;
+/// Number of seeds to try before giving up on building an [MphInterner]
+const MAX_SEEDS: u64 = 32;
+/// Fraction of the keys that go to the dense buckets, out of [u32::MAX]
+const DENSE_KEYS: u64 = (u32::MAX as u64) * 6 / 10;
+
+/// Finalizer of SplitMix64, used to derive hashes from the value's hash
+const fn mix(mut x: u64) -> u64 {
+    x = (x ^ (x >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+    x = (x ^ (x >> 27)).wrapping_mul(0x94d049bb133111eb);
+    x ^ (x >> 31)
+}
+
+/// Slot of a key with hash `x`, for the displacement `d`
+fn slot_of(x: u64, d: u64, n: usize) -> usize {
+    (mix(x ^ mix(d)) % n as u64) as usize
+}
+
+/// Bucket of a key with hash `x`
+///
+/// Like in PTHash, 60% of the keys go to the first 30% of the buckets.
+/// These dense buckets are placed first, while there's more room, which
+/// keeps the displacements of the rest small.
+fn bucket_of(x: u64, buckets: usize) -> usize {
+    let h = x >> 32;
+    let dense = (buckets * 3).div_ceil(10);
+    if h < DENSE_KEYS || dense == buckets {
+        (h % dense as u64) as usize
+    } else {
+        dense + (h % (buckets - dense) as u64) as usize
+    }
+}
+
+/// Array of integers packed with the bits of the biggest one
+struct PackedArray {
+    words: Box<[u64]>,
+    width: u32,
+    len: usize,
+}
+
+impl PackedArray {
+    fn new(values: &[u64]) -> Self {
+        let width = values.iter().max().map_or(0, |max| u64::BITS - max.leading_zeros());
+        let mut words = vec![0; (values.len() * width as usize).div_ceil(64)].into_boxed_slice();
+        for (i, &value) in values.iter().enumerate().filter(|&(_, &value)| value != 0) {
+            let bit = i * width as usize;
+            let (word, offset) = (bit / 64, bit % 64);
+            words[word] |= value << offset;
+            if offset + width as usize > 64 {
+                words[word + 1] |= value >> (64 - offset);
+            }
+        }
+        Self { words, width, len: values.len() }
+    }
+
+    fn get(&self, i: usize) -> u64 {
+        if self.width == 0 {
+            return 0
+        }
+        let bit = i * self.width as usize;
+        let (word, offset) = (bit / 64, bit % 64);
+        let mut value = self.words[word] >> offset;
+        if offset + self.width as usize > 64 {
+            value |= self.words[word + 1] << (64 - offset);
+        }
+        value & (u64::MAX >> (64 - self.width))
+    }
+
+    fn len(&self) -> usize {
+        self.len
+    }
+
+    fn bits(&self) -> usize {
+        self.words.len() * 64
+    }
+}
+
+/// Hash function of an [MphInterner]
+///
+/// The keys are hashed into 1% more slots than keys, and the
+/// ones that land past the end are remapped to the free slots.
+struct Mph {
+    /// Displacement of each bucket
+    disps: PackedArray,
+    /// Slot for each of the slots past the end
+    remap: PackedArray,
+    /// Number of slots, including the ones past the end
+    table_len: usize,
+}
+
+impl Mph {
+    /// Finds a displacement for every bucket, such that each key gets a
+    /// different slot
+    ///
+    /// `keys` are the hashes of the values, already mixed with the seed.
+    /// Returns the hash function, and the key that goes on each slot.
+    fn build(keys: &[u64]) -> Option<(Self, Vec<usize>)> {
+        let n = keys.len();
+        let table_len = n + n.div_ceil(100);
+        let buckets_len = n.div_ceil(LAMBDA).max(1);
+        let mut buckets = vec![Vec::new(); buckets_len];
+        for (i, &x) in keys.iter().enumerate() {
+            buckets[bucket_of(x, buckets_len)].push(i);
+        }
+        /* The biggest buckets are placed first, while there's more room */
+        let mut order: Vec<usize> = (0..buckets_len).collect();
+        order.sort_unstable_by_key(|&b| core::cmp::Reverse(buckets[b].len()));
+
+        let mut disps = vec![0; buckets_len];
+        let mut slots = vec![usize::MAX; table_len];
+        let mut positions = Vec::new();
+        let max_tries = n.saturating_mul(16).max(1024) as u64;
+        for b in order {
+            let bucket = &buckets[b];
+            if bucket.is_empty() {
+                break
+            }
+            let d = (0..max_tries).find(|&d| {
+                positions.clear();
+                bucket.iter().all(|&k| {
+                    let pos = slot_of(keys[k], d, table_len);
+                    let free = slots[pos] == usize::MAX && !positions.contains(&pos);
+                    positions.push(pos);
+                    free
+                })
+            })?;
+            disps[b] = d;
+            for (&k, &pos) in bucket.iter().zip(&positions) {
+                slots[pos] = k;
+            }
+        }
+
+        /* Move the keys past the end to the free slots. There
+         * are as many of them as keys past the end */
+        let free: Vec<usize> = (0..n).filter(|&i| slots[i] == usize::MAX).collect();
+        let past_end: Vec<usize> = (n..table_len).filter(|&i| slots[i] != usize::MAX).collect();
+        let mut remap = vec![0; table_len - n];
+        for (i, pos) in past_end.into_iter().zip(free) {
+            remap[i - n] = pos as u64;
+            slots[pos] = slots[i];
+        }
+        slots.truncate(n);
+
+        let mph = Self {
+            disps: PackedArray::new(&disps),
+            remap: PackedArray::new(&remap),
+            table_len,
+        };
+        Some((mph, slots))
+    }
+
+    /// Returns the slot of the key with hash `x`
+    ///
+    /// There must be at least one key.
+    fn slot(&self, x: u64) -> usize {
+        let d = self.disps.get(bucket_of(x, self.disps.len()));
+        let pos = slot_of(x, d, self.table_len);
+        let n = self.table_len - self.remap.len();
+        if pos < n { pos } else { self.remap.get(pos - n) as usize }
+    }
+
+    fn bits(&self) -> usize {
+        self.disps.bits() + self.remap.bits()
+    }
+}
+
+/// Read-only interner that looks up values with a minimal perfect hash
+///
+/// Built by [Interner::freeze_mph]. See the
+/// [module-level documentation](self) for more details.
+pub struct MphInterner<T: ?Sized, B: Backend<T>, H> {
+    backend: B,
+    hasher: H,
+    seed: u64,
+    mph: Mph,
+    /// Symbol on each slot of the hash function
+    slots: Box<[B::Symbol]>,
+}
+
+impl<T, B, H> Interner<T, B, H>
+where
+    T: Hash + Eq + ?Sized,
+    B: Backend<T>,
+    H: BuildHasher,
+{
+    /// Turns this interner into a read-only [MphInterner]
+    ///
+    /// The symbols stay the same.
+    ///
+    /// # Panics
+    /// If the perfect hash function can't be built. This only happens
+    /// if two values have the same 64 bit hash.
+    ///
+    /// # Example
+    /// ```
+    /// use interns::StringInterner;
+    ///
+    /// let mut interner = StringInterner::new();
+    /// let words: Vec<_> = ["GET", "POST", "PUT"].iter().map(|w| interner.get_or_intern(w)).collect();
+    ///
+    /// let mph = interner.freeze_mph();
+    /// assert_eq!(mph.get("POST"), Some(words[1]));
+    /// assert_eq!(mph.get("PATCH"), None);
+    /// ```
+    pub fn freeze_mph(self) -> MphInterner<T, B, H> {
+        let entries: Vec<(u64, B::Symbol)> = self
+            .iter()
+            .map(|(sym, value)| (self.hasher.hash_one(value), sym))
+            .collect();
+        for seed in 0..MAX_SEEDS {
+            let keys: Vec<u64> = entries.iter().map(|&(h, _)| mix(h ^ mix(seed))).collect();
+            if let Some((mph, slots)) = Mph::build(&keys) {
+                return MphInterner {
+                    slots: slots.into_iter().map(|k| entries[k].1).collect(),
+                    backend: self.backend,
+                    hasher: self.hasher,
+                    seed,
+                    mph,
+                }
+            }
+        }
+        panic!("Couldn't build a perfect hash function for the interner");
+    }
+}
+
+impl<T, B, H> MphInterner<T, B, H>
+where
+    T: Hash + Eq + ?Sized,
+    B: Backend<T>,
+    H: BuildHasher,
+{
+    /// Gets the symbol for `src`
+    pub fn get<Ref>(&self, src: &Ref) -> Option<B::Symbol>
+    where
+        Ref: Hash + Eq + ?Sized,
+        T: Borrow<Ref>,
+    {
+        if self.slots.is_empty() {
+            return None
+        }
+        let x = mix(self.hasher.hash_one(src) ^ mix(self.seed));
+        let sym = self.slots[self.mph.slot(x)];
+        /* A value that isn't on the interner also lands on some
+         * slot, so we have to check that it's the same one */
+        /* SAFETY: The symbols come from the interner, so they're on the backend */
+        let value = unsafe { self.backend.get_unchecked(sym) };
+        (value.borrow() == src).then_some(sym)
+    }
+
+    /// Returns true if `src` is on the interner
+    pub fn contains<Ref>(&self, src: &Ref) -> bool
+    where
+        Ref: Hash + Eq + ?Sized,
+        T: Borrow<Ref>,
+    {
+        self.get(src).is_some()
+    }
+
+    /// Resolves the symbol
+    pub fn resolve(&self, sym: B::Symbol) -> Option<&T> {
+        self.backend.get(sym)
+    }
+
+    /// Returns the number of values
+    pub fn len(&self) -> usize {
+        self.slots.len()
+    }
+
+    /// Returns the size of the hash function in bits, without the symbols
+    pub fn hash_bits(&self) -> usize {
+        self.mph.bits()
+    }
+
+    /// Returns true if the interner is empty
+    pub fn is_empty(&self) -> bool {
+        self.slots.is_empty()
+    }
+
+    /// Iterates over the symbols and their values
+    ///
+    /// The iteration order is unspecified.
+    pub fn iter(&self) -> impl Iterator<Item = (B::Symbol, &T)> {
+        self.slots.iter().map(|&sym| {
+            /* SAFETY: The symbols come from the interner, so they're on the backend */
+            (sym, unsafe { self.backend.get_unchecked(sym) })
+        })
+    }
+
+    /// Turns this back into a regular [Interner], rebuilding its hash table
+    ///
+    /// The symbols stay the same.
+    pub fn thaw(self) -> Interner<T, B, H> {
+        let mut interner = Interner::with_backend_and_hasher(self.backend, self.hasher);
+        for &sym in &self.slots {
+            interner.insert_symbol(sym);
+        }
+        interner
+    }
+}
@@ -0,0 +1,156 @@
+//! Hashing utilities
+
+use core::hash::{BuildHasher, Hasher};
+
+/// The default [BuildHasher] of the [Interner](crate::Interner)
+///
+/// This is [RandomState](std::hash::RandomState), unless one of the
+/// following features is enabled. If more than one is, the first one of
+/// the list is used.
+///
+/// - `foldhash`: [foldhash::fast::RandomState]
+/// - `ahash`: [ahash::RandomState]
+/// - `fxhash`: [rustc_hash::FxBuildHasher]. This one is not seeded, so
+///   it's not resistant to HashDoS attacks.
+///
+/// These hash short keys (like identifiers) much faster than the std hasher.
+#[cfg(feature = "foldhash")]
+pub type DefaultHashBuilder = foldhash::fast::RandomState;
+
+/// The default [BuildHasher] of the [Interner](crate::Interner)
+#[cfg(all(feature = "ahash", not(feature = "foldhash")))]
+pub type DefaultHashBuilder = ahash::RandomState;
+
+/// The default [BuildHasher] of the [Interner](crate::Interner)
+#[cfg(all(feature = "fxhash", not(any(feature = "foldhash", feature = "ahash"))))]
+pub type DefaultHashBuilder = rustc_hash::FxBuildHasher;
+
+/// The default [BuildHasher] of the [Interner](crate::Interner)
+///
+/// This is [RandomState](std::hash::RandomState), unless one of the
+/// `foldhash`, `ahash` or `fxhash` features is enabled.
+#[cfg(not(any(feature = "foldhash", feature = "ahash", feature = "fxhash")))]
+pub type DefaultHashBuilder = std::hash::RandomState;
+
+/// A [BuildHasher] with a fixed seed
+///
+/// Unlike [RandomState](std::hash::RandomState), every [FixedState] with
+/// the same seed produces the same hashes, on every run and machine (integers
+/// are always hashed in little-endian order). Interners using it behave
+/// exactly the same for the same sequence of operations, including the
+/// iteration order of [Interner::iter](crate::Interner::iter).
+///
+/// This is not resistant to HashDoS attacks, so it should not be used
+/// with untrusted input.
+///
+/// # Example
+/// ```
+/// use interns::DeterministicInterner;
+///
+/// let mut a = DeterministicInterner::<str>::new();
+/// let mut b = DeterministicInterner::<str>::new();
+/// for word in ["x", "y", "z"] {
+///     assert_eq!(a.get_or_intern(word), b.get_or_intern(word));
+/// }
+/// assert!(a.iter().eq(b.iter()));
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct FixedState {
+    seed: u64,
+}
+
+impl FixedState {
+    const DEFAULT_SEED: u64 = 0x243f_6a88_85a3_08d3;
+
+    /// Creates a [FixedState] with the default seed
+    pub const fn new() -> Self {
+        Self::with_seed(Self::DEFAULT_SEED)
+    }
+
+    /// Creates a [FixedState] with the given seed
+    pub const fn with_seed(seed: u64) -> Self {
+        Self { seed }
+    }
+}
+
+impl Default for FixedState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl BuildHasher for FixedState {
+    type Hasher = FixedHasher;
+
+    fn build_hasher(&self) -> FixedHasher {
+        FixedHasher { state: self.seed }
+    }
+}
+
+/// The [Hasher] built by [FixedState]
+#[derive(Clone, Debug)]
+pub struct FixedHasher {
+    state: u64,
+}
+
+impl FixedHasher {
+    const K: u64 = 0x9e37_79b9_7f4a_7c15;
+
+    #[inline]
+    fn add(&mut self, word: u64) {
+        self.state = (self.state ^ word).wrapping_mul(Self::K).rotate_left(31);
+    }
+}
+
+impl Hasher for FixedHasher {
+    fn write(&mut self, bytes: &[u8]) {
+        let mut chunks = bytes.chunks_exact(8);
+        for chunk in &mut chunks {
+            /* SAFETY: `chunks_exact` yields slices of exactly 8 bytes */
+            let word = u64::from_le_bytes(unsafe { chunk.try_into().unwrap_unchecked() });
+            self.add(word);
+        }
+        let rest = chunks.remainder();
+        let mut last = [0; 8];
+        last[..rest.len()].copy_from_slice(rest);
+        /* Mix the length, so that trailing zeroes are not ignored */
+        self.add(u64::from_le_bytes(last) ^ ((rest.len() as u64) << 59));
+    }
+
+    fn write_u8(&mut self, i: u8) {
+        self.add(i as u64);
+    }
+
+    fn write_u16(&mut self, i: u16) {
+        self.add(i as u64);
+    }
+
+    fn write_u32(&mut self, i: u32) {
+        self.add(i as u64);
+    }
+
+    fn write_u64(&mut self, i: u64) {
+        self.add(i);
+    }
+
+    fn write_u128(&mut self, i: u128) {
+        self.add(i as u64);
+        self.add((i >> 64) as u64);
+    }
+
+    fn write_usize(&mut self, i: usize) {
+        /* Hash usizes as u64, so that 32 and 64-bit machines agree */
+        self.add(i as u64);
+    }
+
+    fn finish(&self) -> u64 {
+        /* Finalizer of MurmurHash3 */
+        let mut h = self.state;
+        h ^= h >> 33;
+        h = h.wrapping_mul(0xff51_afd7_ed55_8ccd);
+        h ^= h >> 33;
+        h = h.wrapping_mul(0xc4ce_b9fe_1a85_ec53);
+        h ^= h >> 33;
+        h
+    }
+}
@@ -0,0 +1,389 @@
+//! Hash-consing of trees and DAGs
+//!
+//! Hash-consing interns the nodes of a tree bottom-up, storing the
+//! children of each node as symbols of the same interner. Structurally
+//! equal subtrees get the same symbol, so the tree becomes a DAG where
+//! equality checks are symbol comparisons.
+//!
+//! Any type can be a node, by implementing [Node]. [Expr] is a
+//! generic node: an operator applied to a list of children.
+//!
+//! # Example
+//! ```
+//! use interns::hashcons::{Expr, ExprInterner};
+//!
+//! let mut interner = ExprInterner::<&str>::new();
+//! let x = interner.get_or_intern(&Expr::leaf("x"));
+//! let y = interner.get_or_intern(&Expr::leaf("y"));
+//! let a = interner.get_or_intern(&Expr::new("+", [x, y]));
+//! let b = interner.get_or_intern(&Expr::new("+", [x, y]));
+//! let mul = interner.get_or_intern(&Expr::new("*", [a, b]));
+//!
+//! assert_eq!(a, b);
+//! assert_eq!(interner.display_tree(mul).to_string(), "(* (+ x y) (+ x y))");
+//! assert_eq!(interner.depth(mul), Some(3));
+//! ```
+//!
+//! [pretty](Interner::pretty) prints trees with more options, like indenting
+//! them, or writing shared subtrees only once.
+
+use core::fmt;
+use core::hash::{BuildHasher, Hash};
+use std::collections::{HashMap, HashSet};
+
+use crate::backend::{Backend, VecBackend};
+use crate::hash::DefaultHashBuilder;
+use crate::Interner;
+
+/// A node of a hash-consed tree
+///
+/// `S` is the type of the symbols of the interner
+/// that holds the nodes.
+pub trait Node<S> {
+    /// Returns the children of this node
+    fn children(&self) -> &[S];
+
+    /// Writes the label of this node, without its children
+    fn fmt_label(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result;
+}
+
+/// Symbol of the nodes of an [ExprInterner]
+pub type ExprSymbol = <VecBackend<()> as Backend<()>>::Symbol;
+
+/// Interner for [Expr] nodes
+pub type ExprInterner<Op, H = DefaultHashBuilder> = Interner<Expr<Op>, VecBackend<Expr<Op>>, H>;
+
+/// An operator applied to a list of children
+#[derive(Clone, Debug, Hash, Eq, PartialEq, PartialOrd, Ord)]
+pub struct Expr<Op, S = ExprSymbol> {
+    pub op: Op,
+    pub children: Vec<S>,
+}
+
+impl<Op, S> Expr<Op, S> {
+    /// Creates a node with the given children
+    pub fn new(op: Op, children: impl Into<Vec<S>>) -> Self {
+        Self { op, children: children.into() }
+    }
+
+    /// Creates a node without children
+    pub fn leaf(op: Op) -> Self {
+        Self { op, children: Vec::new() }
+    }
+
+    /// Returns true if this node doesn't have children
+    pub fn is_leaf(&self) -> bool {
+        self.children.is_empty()
+    }
+}
+
+impl<Op: fmt::Display, S> Node<S> for Expr<Op, S> {
+    fn children(&self) -> &[S] {
+        &self.children
+    }
+
+    fn fmt_label(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.op, f)
+    }
+}
+
+impl<T, B, H> Interner<T, B, H>
+where
+    T: Node<B::Symbol> + Hash + Eq,
+    B: Backend<T>,
+    H: BuildHasher,
+{
+    /// Folds the tree rooted at `sym` bottom-up
+    ///
+    /// `f` receives each node, along with the results of its children.
+    /// Shared subtrees are only folded once, and their result is cloned.
+    ///
+    /// Returns None if any node doesn't resolve, or if the nodes form a cycle.
+    ///
+    /// # Example
+    /// ```
+    /// use interns::hashcons::{Expr, ExprInterner};
+    ///
+    /// let mut interner = ExprInterner::<i32>::new();
+    /// let two = interner.get_or_intern(&Expr::leaf(2));
+    /// let three = interner.get_or_intern(&Expr::leaf(3));
+    /// let sum = interner.get_or_intern(&Expr::new(0, [two, three, two]));
+    ///
+    /// let total = interner.fold(sum, |node, children: &[i32]| node.op + children.iter().sum::<i32>());
+    /// assert_eq!(total, Some(7));
+    /// ```
+    pub fn fold<R: Clone>(&self, sym: B::Symbol, mut f: impl FnMut(&T, &[R]) -> R) -> Option<R> {
+        /* None for the nodes being folded, which allows detecting cycles */
+        let mut memo = HashMap::<B::Symbol, Option<R>>::new();
+        memo.insert(sym, None);
+        /* Nodes being folded, with the number of their children already visited.
+         * It's a loop instead of a recursion, so deep trees don't overflow the stack */
+        let mut stack = vec![(sym, self.resolve(sym)?, 0)];
+        /* Results of the visited children of the nodes in the stack */
+        let mut results = Vec::new();
+        while let Some(&mut (sym, node, ref mut visited)) = stack.last_mut() {
+            if let Some(&child) = node.children().get(*visited) {
+                *visited += 1;
+                match memo.get(&child) {
+                    Some(result) => results.push(result.clone()?),
+                    None => {
+                        memo.insert(child, None);
+                        stack.push((child, self.resolve(child)?, 0));
+                    }
+                }
+                continue
+            }
+            let children = results.split_off(results.len() - node.children().len());
+            let result = f(node, &children);
+            memo.insert(sym, Some(result.clone()));
+            results.push(result);
+            stack.pop();
+        }
+        results.pop()
+    }
+
+    /// Returns the depth of the tree rooted at `sym`
+    ///
+    /// A leaf has depth 1. Returns None if the tree can't be [folded](Self::fold).
+    pub fn depth(&self, sym: B::Symbol) -> Option<usize> {
+        self.fold(sym, |_, children| 1 + children.iter().max().unwrap_or(&0))
+    }
+
+    /// Returns the number of unique nodes reachable from `sym`, including itself
+    ///
+    /// Returns None if the tree can't be [folded](Self::fold).
+    pub fn dag_size(&self, sym: B::Symbol) -> Option<usize> {
+        let mut size = 0;
+        self.fold(sym, |_, _| size += 1)?;
+        Some(size)
+    }
+
+    /// Displays the tree rooted at `sym` as an s-expression
+    ///
+    /// Leaves are written as their label, and the rest of the
+    /// nodes as `(label child...)`. Nodes that don't resolve are
+    /// written as `<unknown>`, and cycles as `<cycle>`.
+    pub fn display_tree(&self, sym: B::Symbol) -> TreeDisplay<'_, T, B, H> {
+        TreeDisplay { interner: self, root: sym }
+    }
+
+    /// Returns a [printer](PrettyTree) for the tree rooted at `sym`
+    ///
+    /// # Example
+    /// ```
+    /// use interns::StringInterner;
+    /// use interns::backend::string::Symbol;
+    /// use interns::hashcons::{Expr, ExprInterner};
+    ///
+    /// let mut names = StringInterner::new();
+    /// let mut interner = ExprInterner::<Symbol>::new();
+    /// let x = interner.get_or_intern(&Expr::leaf(names.get_or_intern("x")));
+    /// let sq = interner.get_or_intern(&Expr::new(names.get_or_intern("mul"), [x, x]));
+    /// let sum = interner.get_or_intern(&Expr::new(names.get_or_intern("add"), [sq, sq]));
+    ///
+    /// let pretty = interner
+    ///     .pretty(sum)
+    ///     .share(true)
+    ///     .indent(2)
+    ///     .labels(|node, f| f.write_str(names.resolve(node.op).unwrap()));
+    /// assert_eq!(pretty.to_string(), "(add\n  #1=(mul\n    x\n    x)\n  #1#)");
+    /// ```
+    pub fn pretty(&self, sym: B::Symbol) -> PrettyTree<'_, T, B, H> {
+        PrettyTree {
+            interner: self,
+            root: sym,
+            indent: None,
+            share: false,
+            label: T::fmt_label,
+        }
+    }
+}
+
+/// Displays a hash-consed tree
+///
+/// Returned by [Interner::display_tree]
+pub struct TreeDisplay<'a, T, B, H>
+where
+    T: Hash + Eq,
+    B: Backend<T>,
+    H: BuildHasher,
+{
+    interner: &'a Interner<T, B, H>,
+    root: B::Symbol,
+}
+
+impl<T, B, H> fmt::Display for TreeDisplay<'_, T, B, H>
+where
+    T: Node<B::Symbol> + Hash + Eq,
+    B: Backend<T>,
+    H: BuildHasher,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.interner.pretty(self.root), f)
+    }
+}
+
+/// Configurable printer of hash-consed trees
+///
+/// Returned by [Interner::pretty]. By default, it prints the tree
+/// inline, like [display_tree](Interner::display_tree).
+pub struct PrettyTree<'a, T, B, H, L = fn(&T, &mut fmt::Formatter<'_>) -> fmt::Result>
+where
+    T: Hash + Eq,
+    B: Backend<T>,
+    H: BuildHasher,
+{
+    interner: &'a Interner<T, B, H>,
+    root: B::Symbol,
+    indent: Option<usize>,
+    share: bool,
+    label: L,
+}
+
+impl<'a, T, B, H, L> PrettyTree<'a, T, B, H, L>
+where
+    T: Node<B::Symbol> + Hash + Eq,
+    B: Backend<T>,
+    H: BuildHasher,
+    L: Fn(&T, &mut fmt::Formatter<'_>) -> fmt::Result,
+{
+    /// Writes every child on its own line, indented `width` spaces
+    /// more than its parent
+    pub fn indent(mut self, width: usize) -> Self {
+        self.indent = Some(width);
+        self
+    }
+
+    /// Writes the subtrees that appear more than once only the first time
+    ///
+    /// The first occurrence is written as `#n=(...)`, and the rest as `#n#`.
+    /// Leaves are always written in full.
+    pub fn share(mut self, share: bool) -> Self {
+        self.share = share;
+        self
+    }
+
+    /// Writes the labels of the nodes with `label`, instead of [Node::fmt_label]
+    ///
+    /// This allows resolving the labels with another interner.
+    pub fn labels<L2>(self, label: L2) -> PrettyTree<'a, T, B, H, L2>
+    where
+        L2: Fn(&T, &mut fmt::Formatter<'_>) -> fmt::Result,
+    {
+        PrettyTree {
+            interner: self.interner,
+            root: self.root,
+            indent: self.indent,
+            share: self.share,
+            label,
+        }
+    }
+
+    /// Returns the nodes reachable from the root that have more than one parent
+    fn shared_nodes(&self) -> HashSet<B::Symbol> {
+        let mut parents = HashMap::<B::Symbol, usize>::new();
+        let mut pending = vec![self.root];
+        let mut visited = HashSet::from([self.root]);
+        while let Some(sym) = pending.pop() {
+            let Some(node) = self.interner.resolve(sym) else { continue };
+            for &child in node.children() {
+                *parents.entry(child).or_default() += 1;
+                if visited.insert(child) {
+                    pending.push(child);
+                }
+            }
+        }
+        parents
+            .into_iter()
+            .filter(|&(sym, n)| n > 1 && self.interner.resolve(sym).is_some_and(|node| !node.children().is_empty()))
+            .map(|(sym, _)| sym)
+            .collect()
+    }
+
+    /// Writes the tree with a work stack instead of recursing,
+    /// so deep trees don't overflow the stack
+    fn fmt_tree(&self, state: &mut PrintState<B::Symbol>, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut work = vec![Step::Node(self.root)];
+        while let Some(step) = work.pop() {
+            let sym = match step {
+                Step::Node(sym) => sym,
+                Step::Child(sym) => {
+                    match self.indent {
+                        Some(width) => write!(f, "\n{:1$}", "", width * state.path.len())?,
+                        None => f.write_str(" ")?,
+                    }
+                    sym
+                }
+                Step::Close(sym) => {
+                    state.path.remove(&sym);
+                    f.write_str(")")?;
+                    continue
+                }
+            };
+            if state.path.contains(&sym) {
+                f.write_str("<cycle>")?;
+                continue
+            }
+            let Some(node) = self.interner.resolve(sym) else {
+                f.write_str("<unknown>")?;
+                continue
+            };
+            if state.shared.contains(&sym) {
+                if let Some(id) = state.ids.get(&sym) {
+                    write!(f, "#{id}#")?;
+                    continue
+                }
+                let id = state.ids.len() + 1;
+                state.ids.insert(sym, id);
+                write!(f, "#{id}=")?;
+            }
+            if node.children().is_empty() {
+                (self.label)(node, f)?;
+                continue
+            }
+            f.write_str("(")?;
+            (self.label)(node, f)?;
+            state.path.insert(sym);
+            work.push(Step::Close(sym));
+            work.extend(node.children().iter().rev().map(|&child| Step::Child(child)));
+        }
+        Ok(())
+    }
+}
+
+/// Pending work of a [PrettyTree]
+enum Step<S> {
+    /// Writes the root
+    Node(S),
+    /// Writes a separator and then a child
+    Child(S),
+    /// Closes the parentheses of a node
+    Close(S),
+}
+
+/// State of a [PrettyTree] while it's being written
+struct PrintState<S> {
+    /// Nodes from the root to the current one
+    path: HashSet<S>,
+    /// Nodes written as `#n#` after their first occurrence
+    shared: HashSet<S>,
+    /// Ids of the shared nodes already written
+    ids: HashMap<S, usize>,
+}
+
+impl<T, B, H, L> fmt::Display for PrettyTree<'_, T, B, H, L>
+where
+    T: Node<B::Symbol> + Hash + Eq,
+    B: Backend<T>,
+    H: BuildHasher,
+    L: Fn(&T, &mut fmt::Formatter<'_>) -> fmt::Result,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut state = PrintState {
+            path: HashSet::new(),
+            shared: if self.share { self.shared_nodes() } else { HashSet::new() },
+            ids: HashMap::new(),
+        };
+        self.fmt_tree(&mut state, f)
+    }
+}
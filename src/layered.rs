@@ -0,0 +1,216 @@
+//! Interners layered on top of a shared parent
+//!
+//! A [LayeredInterner] looks up values on a read-only parent interner
+//! first, and only interns locally the values the parent lacks. The
+//! parent is behind an [Arc], so many children can share it.
+//!
+//! The [symbols](LayeredSymbol) remember the layer they belong to.
+//!
+//! # Example
+//! ```
+//! use std::sync::Arc;
+//! use interns::StringInterner;
+//! use interns::layered::{LayeredInterner, LayeredSymbol};
+//!
+//! let mut std = StringInterner::new();
+//! let vec = std.get_or_intern("Vec");
+//! let std = Arc::new(std);
+//!
+//! let mut krate = LayeredInterner::new(Arc::clone(&std));
+//! assert_eq!(krate.get_or_intern("Vec"), LayeredSymbol::Parent(vec));
+//!
+//! let foo = krate.get_or_intern("Foo");
+//! assert!(foo.is_local());
+//! assert_eq!(krate.resolve(foo), Some("Foo"));
+//! assert_eq!(std.get("Foo"), None);
+//! ```
+//!
+//! [Interner::fork] is a shorthand to create a layer on top of an interner.
+
+use core::borrow::Borrow;
+use core::hash::{BuildHasher, Hash};
+use std::sync::Arc;
+
+use crate::backend::{DefaultBackend, Internable};
+use crate::hash::DefaultHashBuilder;
+use crate::{Backend, Interner};
+
+/// Symbol of a [LayeredInterner]
+#[derive(Clone, Copy, Debug, Hash, Eq, PartialEq, PartialOrd, Ord)]
+pub enum LayeredSymbol<S> {
+    /// Symbol of the parent interner
+    Parent(S),
+    /// Symbol of the local interner
+    Local(S),
+}
+
+impl<S> LayeredSymbol<S> {
+    /// Returns true if the symbol belongs to the parent interner
+    pub fn is_parent(&self) -> bool {
+        matches!(self, Self::Parent(_))
+    }
+
+    /// Returns true if the symbol belongs to the local interner
+    pub fn is_local(&self) -> bool {
+        matches!(self, Self::Local(_))
+    }
+
+    /// Returns the symbol, without the layer
+    pub fn into_inner(self) -> S {
+        match self {
+            Self::Parent(sym) | Self::Local(sym) => sym,
+        }
+    }
+}
+
+/// Interner that extends a shared, read-only parent
+///
+/// See the [module-level documentation](self) for more details.
+pub struct LayeredInterner<T, B = DefaultBackend<T>, H = DefaultHashBuilder>
+where
+    T: Hash + Eq + ?Sized,
+    B: Backend<T>,
+    H: BuildHasher,
+{
+    parent: Arc<Interner<T, B, H>>,
+    local: Interner<T, B, H>,
+}
+
+impl<T, B, H> LayeredInterner<T, B, H>
+where
+    T: Hash + Eq + ?Sized,
+    B: Backend<T>,
+    H: BuildHasher,
+{
+    /// Creates an empty layer on top of `parent`
+    pub fn new(parent: Arc<Interner<T, B, H>>) -> Self
+    where
+        B: Default,
+        H: Default,
+    {
+        Self::with_local(parent, Interner::new())
+    }
+
+    /// Uses `local` as the layer on top of `parent`
+    ///
+    /// The values of `local` that are also on `parent` are
+    /// shadowed, and resolve to the parent's symbol.
+    pub fn with_local(parent: Arc<Interner<T, B, H>>, local: Interner<T, B, H>) -> Self {
+        Self { parent, local }
+    }
+
+    /// Gets the symbol for `src`, interning it on the local layer if
+    /// it's not on any layer
+    pub fn get_or_intern<Ref>(&mut self, src: &Ref) -> LayeredSymbol<B::Symbol>
+    where
+        Ref: Internable<T, B> + ?Sized + Hash + Eq,
+        T: Borrow<Ref>,
+    {
+        match self.parent.get(src) {
+            Some(sym) => LayeredSymbol::Parent(sym),
+            None => LayeredSymbol::Local(self.local.get_or_intern(src)),
+        }
+    }
+
+    /// Gets the symbol for `src`, without interning it
+    pub fn get<Ref>(&self, src: &Ref) -> Option<LayeredSymbol<B::Symbol>>
+    where
+        Ref: ?Sized + Hash + Eq,
+        T: Borrow<Ref>,
+    {
+        self.parent
+            .get(src)
+            .map(LayeredSymbol::Parent)
+            .or_else(|| self.local.get(src).map(LayeredSymbol::Local))
+    }
+
+    /// Returns true if `src` is on any layer
+    pub fn contains<Ref>(&self, src: &Ref) -> bool
+    where
+        Ref: ?Sized + Hash + Eq,
+        T: Borrow<Ref>,
+    {
+        self.get(src).is_some()
+    }
+
+    /// Resolves the symbol on its layer
+    pub fn resolve(&self, sym: LayeredSymbol<B::Symbol>) -> Option<&T> {
+        match sym {
+            LayeredSymbol::Parent(sym) => self.parent.resolve(sym),
+            LayeredSymbol::Local(sym) => self.local.resolve(sym),
+        }
+    }
+
+    /// Returns the number of values on both layers
+    ///
+    /// Values shadowed by the parent (see [with_local](Self::with_local))
+    /// are counted twice.
+    pub fn len(&self) -> usize {
+        self.parent.len() + self.local.len()
+    }
+
+    /// Returns true if both layers are empty
+    pub fn is_empty(&self) -> bool {
+        self.parent.is_empty() && self.local.is_empty()
+    }
+
+    /// Returns the parent interner
+    pub fn parent(&self) -> &Arc<Interner<T, B, H>> {
+        &self.parent
+    }
+
+    /// Returns the local interner
+    pub fn local(&self) -> &Interner<T, B, H> {
+        &self.local
+    }
+
+    /// Returns the local interner, discarding the parent
+    pub fn into_local(self) -> Interner<T, B, H> {
+        self.local
+    }
+}
+
+impl<T, B, H> Default for LayeredInterner<T, B, H>
+where
+    T: Hash + Eq + ?Sized,
+    B: Backend<T> + Default,
+    H: BuildHasher + Default,
+{
+    /// Creates a layer on top of an empty parent
+    fn default() -> Self {
+        Self::new(Arc::new(Interner::new()))
+    }
+}
+
+impl<T, B, H> Interner<T, B, H>
+where
+    T: Hash + Eq + ?Sized,
+    B: Backend<T> + Default,
+    H: BuildHasher + Default,
+{
+    /// Creates an empty [layer](LayeredInterner) on top of this interner
+    ///
+    /// The fork shares the storage of this interner, and only allocates
+    /// for the values it interns itself. This is cheap enough to do for
+    /// every request, and the fork can be thrown away at any moment.
+    ///
+    /// # Example
+    /// ```
+    /// use std::sync::Arc;
+    /// use interns::StringInterner;
+    ///
+    /// let mut base = StringInterner::new();
+    /// base.get_or_intern("fn");
+    /// let base = Arc::new(base);
+    ///
+    /// let mut speculative = base.fork();
+    /// assert!(speculative.get_or_intern("fn").is_parent());
+    /// speculative.get_or_intern("maybe");
+    /// drop(speculative);
+    ///
+    /// assert_eq!(base.len(), 1);
+    /// ```
+    pub fn fork(self: &Arc<Self>) -> LayeredInterner<T, B, H> {
+        LayeredInterner::new(Arc::clone(self))
+    }
+}
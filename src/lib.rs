@@ -42,13 +42,111 @@
 //! assert_ne!(b, c);
 //! ```
 
-use hashbrown::hash_map::RawEntryMut;
-use hashbrown::HashMap;
+use hashbrown::HashTable;
+pub use hashbrown::Equivalent;
 use core::borrow::Borrow;
-use std::hash::{BuildHasher, Hash, RandomState};
+use core::convert::Infallible;
+use std::hash::{BuildHasher, Hash};
+use std::sync::Arc;
 
 pub mod backend;
-pub use backend::{Backend, DefaultBackendBuilder, StringBackend};
+pub use backend::{ArcBackend, Backend, DefaultBackendBuilder, DenseSymbols, StringBackend};
+
+pub mod compat;
+
+#[cfg(feature = "ffi")]
+pub mod ffi;
+
+#[cfg(feature = "wasm")]
+pub mod wasm;
+
+pub mod snapshot;
+
+pub mod remap;
+pub use remap::RemapTable;
+
+pub mod error;
+pub use error::{EscapeError, InternError, InvariantError, PrefillError, ResolveError};
+
+pub mod hash;
+use hash::{DefaultHashBuilder, FixedState};
+
+pub mod flatbuf;
+
+pub mod collections;
+
+pub mod mixed;
+
+pub mod hashcons;
+
+pub mod composite;
+
+pub mod qualified;
+
+pub mod dict;
+
+#[cfg(feature = "aho-corasick")]
+pub mod matcher;
+
+pub mod typed;
+
+#[cfg(feature = "unicode")]
+pub mod unicode;
+
+pub mod normalize;
+
+pub mod path;
+
+pub mod escape;
+
+pub mod counting;
+
+pub mod lru;
+
+pub mod observer;
+
+pub mod layered;
+
+pub mod namespace;
+
+pub mod persistent;
+
+pub mod diff;
+
+pub mod sort;
+
+pub mod frozen;
+
+pub mod resolve;
+pub use resolve::Resolve;
+
+pub mod view;
+
+pub mod dump;
+
+pub mod stats;
+use stats::Usage;
+
+#[cfg(feature = "url")]
+pub mod url;
+
+mod read;
+
+mod write;
+
+mod bulk;
+
+mod compact;
+
+pub mod codegen;
+
+#[cfg(feature = "presets")]
+pub mod presets;
+
+pub mod scope;
+
+#[cfg(feature = "serde")]
+pub mod serde_symbol;
 
 use crate::backend::Internable;
 
@@ -56,11 +154,20 @@ pub type Symbol<T, B = <T as DefaultBackendBuilder>::Backend> = <B as Backend<T>
 
 pub type StringInterner = Interner<str,StringBackend>;
 
+/// An [Interner] with a [fixed-seed hasher](FixedState)
+///
+/// Use it when the behaviour of the interner must be reproducible across
+/// runs and machines (e.g. reproducible builds).
+pub type DeterministicInterner<T, B = <T as DefaultBackendBuilder>::Backend> = Interner<T, B, FixedState>;
+
 /// Interner
 ///
 /// This struct is responsible for tracking objects and
 /// interning them.
 ///
+/// By default, it uses the [DefaultHashBuilder], which can
+/// be changed with the `foldhash`, `ahash` and `fxhash` features.
+///
 /// # Example
 /// ```
 /// use interns::*;
@@ -86,7 +193,7 @@ pub type StringInterner = Interner<str,StringBackend>;
 pub struct Interner<
     T,
     B = <T as DefaultBackendBuilder>::Backend,
-    H = RandomState
+    H = DefaultHashBuilder
 >
 where
     T: Hash + Eq + PartialEq + ?Sized,
@@ -94,8 +201,12 @@ where
     B: Backend<T>,
 {
     backend: B,
-    set: HashMap<B::Symbol, (), ()>,
+    set: HashTable<B::Symbol>,
     hasher: H,
+    usage: Usage,
+    generation: u64,
+    /// Number of times values were removed or moved
+    removals: u64,
 }
 
 impl<T, B, H> Interner<T, B, H>
@@ -113,8 +224,29 @@ where
     {
         Self {
             backend: B::default(),
-            set: HashMap::default(),
+            set: HashTable::new(),
+            hasher: H::default(),
+            usage: Usage::new(),
+            generation: 0,
+            removals: 0,
+        }
+    }
+
+    /// Create a new Interner with a default [backend](Backend)
+    /// and [hasher](BuildHasher), with space for at least
+    /// `capacity` unique elements
+    pub fn with_capacity(capacity: usize) -> Self
+    where
+        B: Default,
+        H: Default,
+    {
+        Self {
+            backend: B::default(),
+            set: HashTable::with_capacity(capacity),
             hasher: H::default(),
+            usage: Usage::new(),
+            generation: 0,
+            removals: 0,
         }
     }
 
@@ -126,8 +258,11 @@ where
     {
         Self {
             backend: B::default(),
-            set: HashMap::default(),
+            set: HashTable::new(),
             hasher,
+            usage: Usage::new(),
+            generation: 0,
+            removals: 0,
         }
     }
 
@@ -139,18 +274,59 @@ where
     {
         Self {
             backend,
-            set: HashMap::default(),
+            set: HashTable::new(),
             hasher: H::default(),
+            usage: Usage::new(),
+            generation: 0,
+            removals: 0,
+        }
+    }
+
+    /// Create a new Interner with a default [hasher](BuildHasher) and
+    /// the given [backend](Backend), with space for at least `capacity`
+    /// unique elements
+    pub fn with_backend_and_capacity(backend: B, capacity: usize) -> Self
+    where
+        H: Default,
+    {
+        Self {
+            backend,
+            set: HashTable::with_capacity(capacity),
+            hasher: H::default(),
+            usage: Usage::new(),
+            generation: 0,
+            removals: 0,
         }
     }
 
     /// Create a new Interner with the given [backend](Backend)
     /// and [hasher](BuildHasher)
+    ///
+    /// This function is const, so it can be used to initialize statics.
+    /// The built-in backends have const `new` functions, and [FixedState]
+    /// can be built in a const context (unlike [RandomState](std::hash::RandomState)).
+    ///
+    /// # Example
+    /// ```
+    /// use std::sync::Mutex;
+    /// use interns::{DeterministicInterner, Interner, StringBackend};
+    /// use interns::hash::FixedState;
+    ///
+    /// static INTERNER: Mutex<DeterministicInterner<str>> = Mutex::new(
+    ///     Interner::with_backend_and_hasher(StringBackend::new(), FixedState::new())
+    /// );
+    ///
+    /// let sym = INTERNER.lock().unwrap().get_or_intern("hello");
+    /// assert_eq!(INTERNER.lock().unwrap().resolve(sym), Some("hello"));
+    /// ```
     pub const fn with_backend_and_hasher(backend: B, hasher: H) -> Self {
         Self {
             backend,
             hasher,
-            set: HashMap::with_hasher(()),
+            set: HashTable::new(),
+            usage: Usage::new(),
+            generation: 0,
+            removals: 0,
         }
     }
 
@@ -169,58 +345,473 @@ where
     where
         Ref: Internable<T, B> + ?Sized + Hash + Eq,
         T: Borrow<Ref>,
+    {
+        let Ok(sym) = self.intern_with(src, |src, backend| Ok::<_, Infallible>(backend.intern(src)));
+        sym
+    }
+
+    /// Like [get_or_intern](Self::get_or_intern), but returns an error
+    /// instead of panicking if the backend can't store `src`
+    ///
+    /// # Example
+    /// ```
+    /// use interns::Interner;
+    ///
+    /// let mut interner = Interner::<str>::new();
+    /// let name = interner.try_get_or_intern("Abcd").unwrap();
+    /// assert_eq!(interner.get_or_intern("Abcd"), name);
+    /// ```
+    pub fn try_get_or_intern<Ref>(&mut self, src: &Ref) -> Result<B::Symbol, InternError>
+    where
+        Ref: Internable<T, B> + ?Sized + Hash + Eq,
+        T: Borrow<Ref>,
+    {
+        let Self { backend, set, hasher, .. } = self;
+        set.try_reserve(1, |sym| {
+            /* SAFETY: Every symbol on the table is on the backend */
+            let src = unsafe { backend.get_unchecked(*sym) };
+            hasher.hash_one(src)
+        })
+        .map_err(|_| InternError::CapacityOverflow)?;
+        self.intern_with(src, |src, backend| backend.try_intern(src))
+    }
+
+    /// Gets the symbol for `src`, or interns it with `intern` if it doesn't exist
+    fn intern_with<Ref, E>(
+        &mut self,
+        src: &Ref,
+        intern: impl FnOnce(&Ref, &mut B) -> Result<B::Symbol, E>,
+    ) -> Result<B::Symbol, E>
+    where
+        Ref: ?Sized + Hash + Eq,
+        T: Borrow<Ref>,
+    {
+        let hash = self.hasher.hash_one(src);
+        self.intern_hashed(hash, src, intern)
+    }
+
+    /// Like [intern_with](Self::intern_with), but with the `hash` of `src` already computed
+    fn intern_hashed<Ref, E>(
+        &mut self,
+        hash: u64,
+        src: &Ref,
+        intern: impl FnOnce(&Ref, &mut B) -> Result<B::Symbol, E>,
+    ) -> Result<B::Symbol, E>
+    where
+        Ref: ?Sized + Hash + Eq,
+        T: Borrow<Ref>,
     {
         /* We are doing shenanigans here.
          *
-         * We are storing B::Symbol as the key, but we don't hash the
+         * We are storing B::Symbol on the table, but we don't hash the
          * Symbol itself. `src` is a reference to T, so we have no way
          * of getting a Symbol from `src`.
          *
          * When we look for a symbol, we pass the hash or `src`, and also provide
-         * a custom function to check if the keys match (in case of collision).
+         * a custom function to check if the symbols match (in case of collision).
          * This function must resolve the Symbol to a value of T, and test it against `src`.
          *
          * When we insert a new element, we also need to provide a custom hasher function.
          * This is because an insertion could cause the table to resize, thus causing all
-         * keys to be rehashed. If we don't provide a custom hasher function, a resize
-         * would reallocate the keys according to the Symbol, not the `T` value they
-         * resolve to, making it imposible to retrive those symbols from a `T` reference
-         * in the future.
+         * symbols to be rehashed. The hasher function must also resolve the symbol, and
+         * hash the `T` value it resolves to. Otherwise, a resize would reallocate the
+         * symbols according to the Symbol, not its value, making it imposible to retrive
+         * those symbols from a `T` reference in the future.
          *
          * For this trick to work, we need to make sure that we _always_ access the table
          * with a custom function, that resolves the Symbols before hashing/comparing.
          */
 
-        let Self {
-            backend,
-            set,
-            hasher,
-        } = self;
+        if let Some(sym) = self.find_hashed(hash, src) {
+            self.record_request(sym, size_of_val(src), false);
+            return Ok(sym)
+        }
 
-        let hash = hasher.hash_one(src);
+        let Self { backend, set, hasher, .. } = self;
+        let sym = intern(src, backend)?;
+        set.insert_unique(hash, sym, |sym| {
+            /* SAFETY: We've interned the symbol on the call to `intern` above,
+             * and the rest of the symbols of the table are on the backend */
+            let src = unsafe { backend.get_unchecked(*sym) };
+            hasher.hash_one(src)
+        });
+
+        self.touch();
+        self.record_request(sym, size_of_val(src), true);
+        self.debug_check_invariants(sym);
+        Ok(sym)
+    }
+
+    /// Finds the symbol of `src`, given its `hash`
+    fn find_hashed<Ref>(&self, hash: u64, src: &Ref) -> Option<B::Symbol>
+    where
+        Ref: ?Sized + Eq,
+        T: Borrow<Ref>,
+    {
+        let Self { backend, set, .. } = self;
+        set.find(hash, |&sym| {
+            /* SAFETY: If the symbol is on the table it must also be on the backend. */
+            src == unsafe { backend.get_unchecked(sym) }.borrow()
+        })
+        .copied()
+    }
+
+    /// Gets the [Symbol](Backend::Symbol) for `src`, without interning it.
+    ///
+    /// Returns None if `src` hasn't been interned yet.
+    ///
+    /// # Example
+    /// ```
+    /// use interns::Interner;
+    ///
+    /// let mut interner = Interner::<str>::new();
+    /// assert_eq!(interner.get("Abcd"), None);
+    /// let name = interner.get_or_intern("Abcd");
+    /// assert_eq!(interner.get("Abcd"), Some(name));
+    /// ```
+    pub fn get<Ref>(&self, src: &Ref) -> Option<B::Symbol>
+    where
+        Ref: ?Sized + Hash + Eq,
+        T: Borrow<Ref>,
+    {
+        self.get_equivalent(src)
+    }
 
-        let entry = set
-            .raw_entry_mut()
-            .from_hash(hash, |&sym| {
+    /// Returns true if `src` has been interned
+    pub fn contains<Ref>(&self, src: &Ref) -> bool
+    where
+        Ref: ?Sized + Hash + Eq,
+        T: Borrow<Ref>,
+    {
+        self.get(src).is_some()
+    }
+
+    /// Like [get](Self::get), but `src` can be any type [Equivalent] to `T`
+    ///
+    /// This includes every type `T` can be [borrowed](Borrow) as, but
+    /// also custom key wrappers, which don't need to build a `T` to lookup.
+    ///
+    /// For the lookup to work, an equivalent key must hash
+    /// exactly as the value it's equivalent to.
+    ///
+    /// # Example
+    /// ```
+    /// use core::hash::{Hash, Hasher};
+    /// use interns::{Equivalent, Interner};
+    ///
+    /// /// Matches strings, ignoring ASCII case
+    /// struct Caseless<'a>(&'a str);
+    ///
+    /// impl Hash for Caseless<'_> {
+    ///     fn hash<H: Hasher>(&self, state: &mut H) {
+    ///         self.0.to_ascii_lowercase().hash(state);
+    ///     }
+    /// }
+    ///
+    /// impl Equivalent<str> for Caseless<'_> {
+    ///     fn equivalent(&self, key: &str) -> bool {
+    ///         self.0.eq_ignore_ascii_case(key)
+    ///     }
+    /// }
+    ///
+    /// let mut interner = Interner::<str>::new();
+    /// let select = interner.get_or_intern("select");
+    /// assert_eq!(interner.get_equivalent(&Caseless("SELECT")), Some(select));
+    /// ```
+    pub fn get_equivalent<Q>(&self, src: &Q) -> Option<B::Symbol>
+    where
+        Q: ?Sized + Hash + Equivalent<T>,
+    {
+        let hash = self.hasher.hash_one(src);
+        self.set
+            .find(hash, |&sym| {
                 /* SAFETY: If the symbol is on the table it must also be on the backend. */
-                src == unsafe { backend.get_unchecked(sym) }.borrow()
-            });
-
-        let k = match entry {
-            RawEntryMut::Occupied(occupied) => occupied.into_key(),
-            RawEntryMut::Vacant(vacant) => {
-                let sym = backend.intern(src);
-                vacant
-                    .insert_with_hasher(hash, sym, (), |sym| {
-                        /* SAFETY: We've interned the symbol on the call to `Backed::intern` above */
-                        let src = unsafe { backend.get_unchecked(*sym) };
-                        hasher.hash_one(src)
-                    })
-                    .0
+                src.equivalent(unsafe { self.backend.get_unchecked(sym) })
+            })
+            .copied()
+    }
+
+    /// Returns true if an [equivalent](Self::get_equivalent) value to `src` has been interned
+    pub fn contains_equivalent<Q>(&self, src: &Q) -> bool
+    where
+        Q: ?Sized + Hash + Equivalent<T>,
+    {
+        self.get_equivalent(src).is_some()
+    }
+
+    /// Returns the number of unique elements interned
+    pub fn len(&self) -> usize {
+        self.set.len()
+    }
+
+    /// Returns true if nothing has been interned yet
+    pub fn is_empty(&self) -> bool {
+        self.set.is_empty()
+    }
+
+    /// Inserts a symbol that's already stored on the backend into the set
+    ///
+    /// Returns false if `sym` doesn't resolve, or if its value was already on the set.
+    fn insert_symbol(&mut self, sym: B::Symbol) -> bool {
+        let Self { backend, set, hasher, .. } = self;
+        let Some(src) = backend.get(sym) else { return false };
+        let hash = hasher.hash_one(src);
+        let found = set.find(hash, |&s| {
+            /* SAFETY: If the symbol is on the table it must also be on the backend. */
+            src == unsafe { backend.get_unchecked(s) }
+        });
+        if found.is_some() {
+            return false
+        }
+        set.insert_unique(hash, sym, |sym| {
+            /* SAFETY: Every symbol on the table is on the backend */
+            let src = unsafe { backend.get_unchecked(*sym) };
+            hasher.hash_one(src)
+        });
+        self.touch();
+        self.debug_check_invariants(sym);
+        true
+    }
+
+    /// Returns the generation of this interner
+    ///
+    /// The generation changes every time a value is added to or removed from
+    /// the interner, or its symbols change (e.g. on [compact](Self::compact)).
+    /// Caches built from the interner can compare it to know if they're stale.
+    ///
+    /// # Example
+    /// ```
+    /// use interns::StringInterner;
+    ///
+    /// let mut interner = StringInterner::new();
+    /// let generation = interner.generation();
+    /// interner.get_or_intern("a");
+    /// assert_ne!(interner.generation(), generation);
+    ///
+    /// let generation = interner.generation();
+    /// interner.get_or_intern("a");
+    /// assert_eq!(interner.generation(), generation);
+    /// ```
+    pub fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    /// Marks the interner as changed, by advancing its [generation](Self::generation)
+    fn touch(&mut self) {
+        self.generation = self.generation.wrapping_add(1);
+    }
+
+    /// Like [touch](Self::touch), for changes that remove or move values
+    ///
+    /// These invalidate the [checkpoints](Self::checkpoint) taken before.
+    fn touch_removed(&mut self) {
+        self.touch();
+        self.removals = self.removals.wrapping_add(1);
+    }
+
+    /// Checks the internal consistency of this interner
+    ///
+    /// This verifies that:
+    /// - Every symbol appears once on the dedup set, and resolves on the backend
+    /// - Every symbol can be found from the hash of its value
+    /// - No value is stored under two different symbols
+    /// - The backend's own [invariants](Backend::check_invariants) hold
+    ///
+    /// This is useful to validate custom [Backend] implementations. With
+    /// the `debug-invariants` feature, the interner is checked after every
+    /// mutation, and panics if any check fails.
+    ///
+    /// # Example
+    /// ```
+    /// use interns::StringInterner;
+    ///
+    /// let interner = StringInterner::from_lines(["a", "b", "c"]);
+    /// assert!(interner.check_invariants().is_ok());
+    /// ```
+    pub fn check_invariants(&self) -> Result<(), InvariantError> {
+        self.backend.check_invariants()?;
+        let mut seen = std::collections::HashSet::with_capacity(self.len());
+        for (i, &sym) in self.set.iter().enumerate() {
+            if !seen.insert(sym) {
+                return Err(InvariantError::new(format!("symbol #{i} appears twice on the set")))
+            }
+            self.check_symbol(sym)
+                .map_err(|err| InvariantError::new(format!("symbol #{i} {err}")))?;
+        }
+        Ok(())
+    }
+
+    /// Checks the invariants that concern a single symbol of the set
+    fn check_symbol(&self, sym: B::Symbol) -> Result<(), &'static str> {
+        let value = self.backend.get(sym).ok_or("doesn't resolve")?;
+        let hash = self.hasher.hash_one(value);
+        if self.set.find(hash, |&s| s == sym).is_none() {
+            return Err("isn't reachable from its hash")
+        }
+        let found = self.set.find(hash, |&s| self.backend.get(s) == Some(value));
+        if found.is_some_and(|&s| s != sym) {
+            return Err("has a value that's interned twice")
+        }
+        Ok(())
+    }
+
+    /// Checks the invariants after `sym` is inserted, if the
+    /// `debug-invariants` feature is enabled
+    ///
+    /// To keep the overhead linear, only `sym` is checked, except when
+    /// the size of the set reaches a power of two, where the whole
+    /// interner is checked.
+    #[inline(always)]
+    #[track_caller]
+    fn debug_check_invariants(&self, sym: B::Symbol) {
+        #[cfg(feature = "debug-invariants")]
+        {
+            let result = if self.len().is_power_of_two() {
+                self.check_invariants()
+            } else {
+                self.check_symbol(sym)
+                    .map_err(|err| InvariantError::new(format!("new symbol {err}")))
+            };
+            if let Err(err) = result {
+                panic!("Interner invariant violated: {err}");
+            }
+        }
+        #[cfg(not(feature = "debug-invariants"))]
+        let _ = sym;
+    }
+
+    /// Updates the usage statistics after a request for `sym`
+    ///
+    /// `len` is the size of the requested value, and `new` is
+    /// true if it wasn't on the interner.
+    fn record_request(&mut self, sym: B::Symbol, len: usize, new: bool) {
+        self.usage.record(len);
+        self.trace_intern(len, new);
+        self.record_metrics(sym, new);
+    }
+
+    /// Emits a debug event for a request of a value of `len` bytes
+    ///
+    /// `new` is true if the value wasn't on the interner.
+    /// Only does something if the `tracing` feature is enabled.
+    #[inline(always)]
+    fn trace_intern(&self, len: usize, new: bool) {
+        #[cfg(feature = "tracing")]
+        tracing::debug!(new, len, entries = self.len(), "intern");
+        #[cfg(not(feature = "tracing"))]
+        let _ = (len, new);
+    }
+
+    /// Reports a request for `sym` to the [metrics](stats#metrics)
+    ///
+    /// `new` is true if the value wasn't on the interner.
+    /// Only does something if the `metrics` feature is enabled.
+    #[inline(always)]
+    fn record_metrics(&mut self, sym: B::Symbol, new: bool) {
+        #[cfg(feature = "metrics")]
+        {
+            if new {
+                /* SAFETY: The symbol has just been interned */
+                let bytes = size_of_val(unsafe { self.backend.get_unchecked(sym) });
+                self.usage.added(bytes);
             }
-        };
+            stats::record_metrics(new);
+        }
+        #[cfg(not(feature = "metrics"))]
+        let _ = (sym, new);
+    }
+
+    /// Reports the removal of `sym` to the [metrics](stats#metrics)
+    ///
+    /// The symbol must still be on the backend.
+    /// Only does something if the `metrics` feature is enabled.
+    #[inline(always)]
+    fn record_removal(&mut self, sym: B::Symbol) {
+        #[cfg(feature = "metrics")]
+        {
+            /* SAFETY: Removed values stay on the backend */
+            let bytes = size_of_val(unsafe { self.backend.get_unchecked(sym) });
+            self.usage.removed(bytes);
+        }
+        #[cfg(not(feature = "metrics"))]
+        let _ = sym;
+    }
+
+    /// Reports all the values of the interner to the [metrics](stats#metrics),
+    /// after they've been added by other means than interning them
+    ///
+    /// Only does something if the `metrics` feature is enabled.
+    #[inline(always)]
+    fn recount_metrics(&mut self) {
+        #[cfg(feature = "metrics")]
+        {
+            let bytes = self.iter().map(|(_, value)| size_of_val(value)).sum();
+            self.usage.recount(self.len(), bytes);
+        }
+    }
+
+    /// Returns an iterator over all the interned symbols and their values
+    ///
+    /// The iteration order is unspecified.
+    pub fn iter(&self) -> impl Iterator<Item = (B::Symbol, &T)> {
+        self.set.iter().map(|&sym| {
+            /* SAFETY: If the symbol is on the table it must also be on the backend. */
+            (sym, unsafe { self.backend.get_unchecked(sym) })
+        })
+    }
+
+    /// Returns all the interned symbols, sorted by the symbols themselves
+    fn ordered_symbols(&self) -> Vec<B::Symbol>
+    where
+        B::Symbol: Ord,
+    {
+        let mut syms = self.set.iter().copied().collect::<Vec<_>>();
+        syms.sort_unstable();
+        syms
+    }
+
+    /// Builds a map from each interned value to its symbol
+    ///
+    /// # Example
+    /// ```
+    /// use interns::StringInterner;
+    ///
+    /// let interner = StringInterner::from_lines(["a", "b"]);
+    /// let map = interner.to_lookup_map();
+    /// assert_eq!(map.get("a").copied(), interner.get("a"));
+    /// ```
+    pub fn to_lookup_map(&self) -> std::collections::HashMap<T::Owned, B::Symbol>
+    where
+        T: ToOwned,
+        T::Owned: Hash + Eq,
+    {
+        self.iter().map(|(sym, val)| (val.to_owned(), sym)).collect()
+    }
 
-        *k
+    /// Returns an owned copy of every interned value, ordered by symbol
+    ///
+    /// # Example
+    /// ```
+    /// use interns::Interner;
+    ///
+    /// let mut interner = Interner::<u32>::new();
+    /// for n in [3, 1, 3, 2] {
+    ///     interner.get_or_intern(&n);
+    /// }
+    /// assert_eq!(interner.to_vec(), [3, 1, 2]);
+    /// ```
+    pub fn to_vec(&self) -> Vec<T::Owned>
+    where
+        T: ToOwned,
+        B::Symbol: Ord,
+    {
+        self.ordered_symbols()
+            .into_iter()
+            .map(|sym| {
+                /* SAFETY: If the symbol is on the table it must also be on the backend. */
+                unsafe { self.backend.get_unchecked(sym) }.to_owned()
+            })
+            .collect()
     }
 
     /// Resolves the [symbol](Backend::Symbol) into a reference of T
@@ -237,6 +828,72 @@ where
     pub fn resolve(&self, sym: B::Symbol) -> Option<&T> {
         self.backend.get(sym)
     }
+
+    /// Wipes the memory of the backend, and removes all the interned values
+    ///
+    /// After this call, the interner is empty, and all the previous
+    /// symbols are invalid. With the `zeroize` feature, the [StringBackend]
+    /// also wipes its buffer when dropped, or when it needs to grow.
+    ///
+    /// The [StringBackend] is the only backend wiped on drop. A
+    /// [VecBackend](backend::VecBackend) can be wiped with this method
+    /// before dropping it, but its values don't need to be [Zeroize](zeroize::Zeroize)
+    /// unless this is called. The rest of the backends can't be wiped: the
+    /// [ArcBackend] and [BytesBackend](backend::BytesBackend)
+    /// share their values, and the arena backends don't own their arena.
+    ///
+    /// # Example
+    /// ```
+    /// use interns::StringInterner;
+    ///
+    /// let mut interner = StringInterner::new();
+    /// let token = interner.get_or_intern("Bearer abcd1234");
+    /// interner.zeroize();
+    /// assert!(interner.is_empty());
+    /// assert_eq!(interner.get("Bearer abcd1234"), None);
+    /// ```
+    #[cfg(feature = "zeroize")]
+    pub fn zeroize(&mut self)
+    where
+        B: zeroize::Zeroize,
+    {
+        self.backend.zeroize();
+        self.set.clear();
+        self.usage = Usage::new();
+        self.touch_removed();
+    }
+
+    /// Like [resolve](Self::resolve), but returns a [ResolveError]
+    /// if the symbol doesn't resolve
+    ///
+    /// # Example
+    /// ```
+    /// use interns::{Interner, ResolveError};
+    ///
+    /// let mut a = Interner::<u32>::new();
+    /// let mut b = Interner::<u32>::new();
+    /// b.get_or_intern(&1);
+    /// let sym = b.get_or_intern(&2);
+    ///
+    /// assert_eq!(a.try_resolve(sym), Err(ResolveError::OutOfRange { index: 1, len: 0 }));
+    /// ```
+    pub fn try_resolve(&self, sym: B::Symbol) -> Result<&T, ResolveError> {
+        self.backend.try_get(sym)
+    }
+}
+
+impl<T, H> Interner<T, ArcBackend<T>, H>
+where
+    T: Hash + Eq + PartialEq + ?Sized,
+    H: BuildHasher,
+{
+    /// Resolves the [symbol](Backend::Symbol) into an [Arc] holding the value
+    ///
+    /// The returned [Arc] shares the interner's storage, and remains
+    /// valid after the interner is dropped.
+    pub fn resolve_arc(&self, sym: Symbol<T, ArcBackend<T>>) -> Option<Arc<T>> {
+        self.backend.get_arc(sym).cloned()
+    }
 }
 
 impl<T,B> Default for Interner<T,B>
@@ -0,0 +1,468 @@
+//! Bounded interner with least-recently-used eviction
+//!
+//! An [LruInterner] holds a limited number of values. When it's
+//! full, interning a new value evicts the one that was used least recently.
+//!
+//! Evicted values free their slot, which can be reused by another value.
+//! Every slot has a *generation*, that increases each time it's freed. A
+//! [LruSymbol] remembers the generation it was produced in, so symbols of
+//! evicted values stop resolving, instead of resolving to the new occupant.
+//! A slot whose generation can't increase anymore is retired, instead of
+//! wrapping around to the generation of older symbols.
+//!
+//! # Example
+//! ```
+//! use interns::lru::LruInterner;
+//!
+//! let mut interner = LruInterner::<str>::new(2);
+//! let a = interner.get_or_intern("a");
+//! let b = interner.get_or_intern("b");
+//! interner.get_or_intern("a");
+//!
+//! /* "b" is the least recently used value */
+//! let c = interner.get_or_intern("c");
+//! assert_eq!(interner.resolve(a), Some("a"));
+//! assert_eq!(interner.resolve(b), None);
+//! assert_eq!(interner.resolve(c), Some("c"));
+//! ```
+//!
+//! # Expiration
+//! Values can also expire if they're not used for some [time](LruInterner::ttl).
+//! Expired values are evicted by [purge_expired](LruInterner::purge_expired),
+//! which is also called before interning a new value.
+//!
+//! ```
+//! use std::time::Duration;
+//! use interns::lru::LruInterner;
+//!
+//! let mut sessions = LruInterner::<str>::new(1000).ttl(Duration::from_millis(10));
+//! let token = sessions.get_or_intern("e0c9035898dd52fc65c41454cec9c4d2611bfb37");
+//! std::thread::sleep(Duration::from_millis(20));
+//! assert_eq!(sessions.purge_expired(), 1);
+//! assert_eq!(sessions.resolve(token), None);
+//! ```
+//!
+//! # Weight
+//! Besides the number of entries, the interner can be bounded by the total
+//! [weight](LruInterner::max_weight) of its values. A few huge strings can
+//! use more memory than thousands of small ones. By default, a value weighs
+//! its size in bytes, but a custom [Weigher] can be [plugged](LruInterner::weigher).
+
+use core::hash::{BuildHasher, Hash};
+use std::time::{Duration, Instant};
+
+use hashbrown::HashTable;
+
+use crate::hash::DefaultHashBuilder;
+use crate::ResolveError;
+
+/// Marks the end of the LRU list
+const NIL: u32 = u32::MAX;
+
+/// Symbol of a [LruInterner]
+#[derive(Clone, Copy, Debug, Hash, Eq, PartialEq, PartialOrd, Ord)]
+pub struct LruSymbol {
+    index: u32,
+    generation: u32,
+}
+
+impl LruSymbol {
+    /// Returns the index of the slot of this symbol
+    pub const fn index(&self) -> u32 {
+        self.index
+    }
+
+    /// Returns the generation of the slot this symbol was produced in
+    pub const fn generation(&self) -> u32 {
+        self.generation
+    }
+}
+
+struct Slot<T: ?Sized> {
+    value: Option<Box<T>>,
+    generation: u32,
+    /// Previous (more recently used) slot on the LRU list
+    prev: u32,
+    /// Next (less recently used) slot on the LRU list
+    next: u32,
+    /// Last time the value was used, if the interner has a ttl
+    last_used: Option<Instant>,
+    /// Weight of the value, as returned by the [Weigher]
+    weight: usize,
+}
+
+/// Computes the weight of the values of a [LruInterner]
+///
+/// The interner evicts values to keep the total weight under its
+/// [max_weight](LruInterner::max_weight). The weight of a value must
+/// not change while it's on the interner.
+///
+/// Any `Fn(&T) -> usize` can be used as a weigher.
+pub trait Weigher<T: ?Sized> {
+    /// Returns the weight of `value`
+    fn weigh(&self, value: &T) -> usize;
+}
+
+impl<T: ?Sized, F: Fn(&T) -> usize> Weigher<T> for F {
+    fn weigh(&self, value: &T) -> usize {
+        self(value)
+    }
+}
+
+/// Weighs values by their size in bytes
+///
+/// The size is the [size_of_val] of the value: the length of a [str]
+/// or slice, but not the heap memory of types like [String].
+#[derive(Clone, Copy, Debug, Default)]
+pub struct ByteSize;
+
+impl<T: ?Sized> Weigher<T> for ByteSize {
+    fn weigh(&self, value: &T) -> usize {
+        size_of_val(value)
+    }
+}
+
+/// Interner that evicts the least recently used values
+///
+/// See the [module-level documentation](self) for more details.
+pub struct LruInterner<T: ?Sized, H = DefaultHashBuilder, W = ByteSize> {
+    slots: Vec<Slot<T>>,
+    free: Vec<u32>,
+    table: HashTable<u32>,
+    hasher: H,
+    /// Most recently used slot
+    head: u32,
+    /// Least recently used slot
+    tail: u32,
+    len: usize,
+    weight: usize,
+    max_entries: usize,
+    max_weight: usize,
+    ttl: Option<Duration>,
+    weigher: W,
+}
+
+impl<T: ?Sized + Hash + Eq> LruInterner<T> {
+    /// Creates an interner that holds at most `max_entries` values
+    ///
+    /// # Panics
+    /// If `max_entries` is 0
+    pub fn new(max_entries: usize) -> Self {
+        Self::with_hasher(max_entries, DefaultHashBuilder::default())
+    }
+
+    /// Creates an interner limited only by the total weight of its values
+    ///
+    /// By default, the weight of a value is its [size in bytes](ByteSize).
+    ///
+    /// # Example
+    /// ```
+    /// use interns::lru::LruInterner;
+    ///
+    /// let mut interner = LruInterner::<str>::with_max_weight(16);
+    /// let small = interner.get_or_intern("small");
+    /// interner.get_or_intern("a much bigger string");
+    /// assert_eq!(interner.resolve(small), None);
+    /// ```
+    pub fn with_max_weight(max_weight: usize) -> Self {
+        Self::new(usize::MAX).max_weight(max_weight)
+    }
+}
+
+impl<T: ?Sized + Hash + Eq, H: BuildHasher> LruInterner<T, H> {
+    /// Creates an interner that holds at most `max_entries` values,
+    /// and uses the given [hasher](BuildHasher)
+    ///
+    /// # Panics
+    /// If `max_entries` is 0
+    pub fn with_hasher(max_entries: usize, hasher: H) -> Self {
+        assert!(max_entries > 0, "An LruInterner must hold at least one value");
+        Self {
+            slots: Vec::new(),
+            free: Vec::new(),
+            table: HashTable::new(),
+            hasher,
+            head: NIL,
+            tail: NIL,
+            len: 0,
+            weight: 0,
+            max_entries,
+            max_weight: usize::MAX,
+            ttl: None,
+            weigher: ByteSize,
+        }
+    }
+}
+
+impl<T, H, W> LruInterner<T, H, W>
+where
+    T: ?Sized + Hash + Eq,
+    H: BuildHasher,
+    W: Weigher<T>,
+{
+    /// Limits the total weight of the values to `max_weight`
+    ///
+    /// A value heavier than `max_weight` can still be interned,
+    /// but it evicts all the other values.
+    pub fn max_weight(mut self, max_weight: usize) -> Self {
+        self.max_weight = max_weight;
+        self
+    }
+
+    /// Weighs the values with `weigher`
+    ///
+    /// # Panics
+    /// If the interner is not empty
+    ///
+    /// # Example
+    /// ```
+    /// use interns::lru::LruInterner;
+    ///
+    /// /* Account for the String's heap memory */
+    /// let weigh = |s: &String| size_of::<String>() + s.capacity();
+    /// let mut interner = LruInterner::<String>::new(1000).weigher(weigh).max_weight(1024);
+    /// interner.get_or_intern(&String::from("hello"));
+    /// assert_eq!(interner.weight(), size_of::<String>() + 5);
+    /// ```
+    pub fn weigher<W2: Weigher<T>>(self, weigher: W2) -> LruInterner<T, H, W2> {
+        assert!(self.is_empty(), "Can't change the weigher of a non-empty LruInterner");
+        LruInterner {
+            slots: self.slots,
+            free: self.free,
+            table: self.table,
+            hasher: self.hasher,
+            head: self.head,
+            tail: self.tail,
+            len: self.len,
+            weight: self.weight,
+            max_entries: self.max_entries,
+            max_weight: self.max_weight,
+            ttl: self.ttl,
+            weigher,
+        }
+    }
+
+    /// Makes the values expire if they're not used for `ttl`
+    ///
+    /// See [purge_expired](Self::purge_expired)
+    pub fn ttl(mut self, ttl: Duration) -> Self {
+        self.ttl = Some(ttl);
+        self
+    }
+
+    /// Gets the symbol for `src`, interning it if it doesn't exist
+    ///
+    /// `src` becomes the most recently used value. If the interner
+    /// is over its limits, the least recently used values are evicted.
+    /// Before interning a new value, the expired values are
+    /// [purged](Self::purge_expired).
+    pub fn get_or_intern(&mut self, src: &T) -> LruSymbol
+    where
+        T: ToOwned,
+        T::Owned: Into<Box<T>>,
+    {
+        if let Some(sym) = self.get(src) {
+            self.touch(sym);
+            return sym;
+        }
+
+        /* The stored value is weighed, since its weight may not be the
+         * same as `src`'s (e.g. the clone of a `String` may have less capacity) */
+        let value: Box<T> = src.to_owned().into();
+        let weight = self.weigher.weigh(&value);
+
+        /* Make room before taking a slot, so the slots of
+         * the evicted values can be reused */
+        self.purge_expired();
+        while self.len > 0 && (self.len >= self.max_entries || self.weight.saturating_add(weight) > self.max_weight) {
+            self.remove_slot(self.tail);
+        }
+
+        let index = match self.free.pop() {
+            Some(index) => index,
+            None => {
+                let index = u32::try_from(self.slots.len())
+                    .ok()
+                    .filter(|&i| i != NIL)
+                    .expect("An LruInterner can't hold more than u32::MAX - 1 slots");
+                self.slots.push(Slot {
+                    value: None,
+                    generation: 0,
+                    prev: NIL,
+                    next: NIL,
+                    last_used: None,
+                    weight: 0,
+                });
+                index
+            }
+        };
+        let slot = &mut self.slots[index as usize];
+        slot.value = Some(value);
+        slot.weight = weight;
+        self.push_front(index);
+        self.len += 1;
+        self.weight = self.weight.saturating_add(weight);
+
+        let Self { slots, table, hasher, .. } = self;
+        let hash = hasher.hash_one(src);
+        table.insert_unique(hash, index, |&i| hasher.hash_one(value_of(slots, i)));
+
+        LruSymbol { index, generation: self.slots[index as usize].generation }
+    }
+
+    /// Gets the symbol for `src`, without interning it
+    ///
+    /// This doesn't count as a use of the value.
+    pub fn get(&self, src: &T) -> Option<LruSymbol> {
+        let hash = self.hasher.hash_one(src);
+        let &index = self.table.find(hash, |&i| value_of(&self.slots, i) == src)?;
+        Some(LruSymbol { index, generation: self.slots[index as usize].generation })
+    }
+
+    /// Resolves the symbol
+    ///
+    /// Returns None if the value of the symbol has been evicted.
+    /// This doesn't count as a use of the value, see [touch](Self::touch).
+    pub fn resolve(&self, sym: LruSymbol) -> Option<&T> {
+        self.try_resolve(sym).ok()
+    }
+
+    /// Like [resolve](Self::resolve), but returns a [ResolveError]
+    /// if the symbol doesn't resolve
+    ///
+    /// Symbols of evicted values fail with [ResolveError::StaleGeneration].
+    pub fn try_resolve(&self, sym: LruSymbol) -> Result<&T, ResolveError> {
+        let index = sym.index as usize;
+        let slot = self.slots.get(index).ok_or(ResolveError::OutOfRange { index, len: self.slots.len() })?;
+        match &slot.value {
+            Some(value) if slot.generation == sym.generation => Ok(value),
+            _ => Err(ResolveError::StaleGeneration {
+                generation: sym.generation,
+                current: slot.generation,
+            }),
+        }
+    }
+
+    /// Marks the value of `sym` as the most recently used
+    ///
+    /// Returns false if the symbol doesn't resolve.
+    pub fn touch(&mut self, sym: LruSymbol) -> bool {
+        if self.resolve(sym).is_none() {
+            return false;
+        }
+        self.unlink(sym.index);
+        self.push_front(sym.index);
+        true
+    }
+
+    /// Evicts the values that haven't been used for the [ttl](Self::ttl)
+    ///
+    /// Returns the number of evicted values. Until they are purged, expired
+    /// values still resolve. Does nothing if the interner doesn't have a ttl.
+    pub fn purge_expired(&mut self) -> usize {
+        let Some(ttl) = self.ttl else { return 0 };
+        let now = Instant::now();
+        let mut purged = 0;
+        /* The list is sorted by use, so the expired values are at the end */
+        while let Some(last_used) = self.slots.get(self.tail as usize).and_then(|slot| slot.last_used)
+            && now.saturating_duration_since(last_used) >= ttl
+        {
+            self.remove_slot(self.tail);
+            purged += 1;
+        }
+        purged
+    }
+
+    /// Removes the value of `sym`
+    ///
+    /// Returns false if the symbol doesn't resolve.
+    pub fn remove(&mut self, sym: LruSymbol) -> bool {
+        if self.resolve(sym).is_none() {
+            return false;
+        }
+        self.remove_slot(sym.index);
+        true
+    }
+
+    /// Returns the number of values on the interner
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns true if the interner is empty
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the total weight of the values
+    pub fn weight(&self) -> usize {
+        self.weight
+    }
+
+    /// Returns the maximum number of values the interner holds
+    pub fn max_entries(&self) -> usize {
+        self.max_entries
+    }
+
+    /// Iterates over the symbols, from the most to the least recently used
+    pub fn iter(&self) -> impl Iterator<Item = LruSymbol> + '_ {
+        let mut index = self.head;
+        core::iter::from_fn(move || {
+            let slot = self.slots.get(index as usize)?;
+            let sym = LruSymbol { index, generation: slot.generation };
+            index = slot.next;
+            Some(sym)
+        })
+    }
+
+    /// Removes the value of the slot, and frees it
+    fn remove_slot(&mut self, index: u32) {
+        self.unlink(index);
+        let Self { slots, table, hasher, .. } = self;
+        let value = value_of(slots, index);
+        let hash = hasher.hash_one(value);
+        if let Ok(entry) = table.find_entry(hash, |&i| i == index) {
+            entry.remove();
+        }
+        let slot = &mut slots[index as usize];
+        slot.value = None;
+        self.weight = self.weight.saturating_sub(slot.weight);
+        self.len -= 1;
+        /* A wrapped generation would make the oldest symbols of
+         * the slot resolve again, so the slot is never reused */
+        if let Some(generation) = slot.generation.checked_add(1) {
+            slot.generation = generation;
+            self.free.push(index);
+        }
+    }
+
+    fn push_front(&mut self, index: u32) {
+        let old_head = self.head;
+        let slot = &mut self.slots[index as usize];
+        slot.prev = NIL;
+        slot.next = old_head;
+        slot.last_used = self.ttl.map(|_| Instant::now());
+        match self.slots.get_mut(old_head as usize) {
+            Some(head) => head.prev = index,
+            None => self.tail = index,
+        }
+        self.head = index;
+    }
+
+    fn unlink(&mut self, index: u32) {
+        let Slot { prev, next, .. } = self.slots[index as usize];
+        match self.slots.get_mut(prev as usize) {
+            Some(prev) => prev.next = next,
+            None => self.head = next,
+        }
+        match self.slots.get_mut(next as usize) {
+            Some(next) => next.prev = prev,
+            None => self.tail = prev,
+        }
+    }
+}
+
+/// Returns the value of an occupied slot
+fn value_of<T: ?Sized>(slots: &[Slot<T>], index: u32) -> &T {
+    slots[index as usize].value.as_deref().expect("Slots on the table have a value")
+}
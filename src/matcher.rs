@@ -0,0 +1,206 @@
+//! Scanning text for interned strings
+//!
+//! A [Matcher] is an [Aho-Corasick](aho_corasick) automaton built from the
+//! strings of an interner. It finds all of them in a text in a single pass,
+//! and reports the matches as symbols of the interner. This is useful for
+//! keyword scanning, or classifying log lines by the words they contain.
+//!
+//! # Example
+//! ```
+//! use interns::StringInterner;
+//!
+//! let mut interner = StringInterner::new();
+//! let error = interner.get_or_intern("ERROR");
+//! let timeout = interner.get_or_intern("timeout");
+//!
+//! let matcher = interner.build_matcher().unwrap();
+//! let hits: Vec<_> = matcher.find_iter("ERROR: read timeout").map(|m| m.symbol).collect();
+//! assert_eq!(hits, [error, timeout]);
+//! ```
+//!
+//! # Updating
+//! The matcher is a snapshot of the interner. Strings interned after
+//! building it are not found until it's [refreshed](Matcher::refresh).
+//!
+//! ```
+//! use interns::StringInterner;
+//!
+//! let mut interner = StringInterner::from_lines(["foo"]);
+//! let mut matcher = interner.build_matcher().unwrap();
+//! let bar = interner.get_or_intern("bar");
+//! assert!(!matcher.is_match("bar"));
+//!
+//! assert!(matcher.refresh(&interner).unwrap());
+//! assert_eq!(matcher.find("a bar").map(|m| m.symbol), Some(bar));
+//! ```
+
+use core::hash::BuildHasher;
+use core::ops::Range;
+
+use aho_corasick::{AhoCorasick, Input};
+pub use aho_corasick::{AhoCorasickBuilder, BuildError, MatchError, MatchKind};
+
+use crate::backend::string::Symbol;
+use crate::{Interner, StringBackend};
+
+/// A match of an interned string in a text
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub struct SymbolMatch {
+    /// Symbol of the matched string
+    pub symbol: Symbol,
+    /// Byte offset where the match starts
+    pub start: usize,
+    /// Byte offset where the match ends
+    pub end: usize,
+}
+
+impl SymbolMatch {
+    /// Returns the byte range of the match
+    pub fn range(&self) -> Range<usize> {
+        self.start..self.end
+    }
+}
+
+/// Automaton that finds interned strings in a text
+///
+/// Returned by [Interner::build_matcher].
+/// See the [module-level documentation](self) for more details.
+#[derive(Clone, Debug)]
+pub struct Matcher {
+    automaton: AhoCorasick,
+    symbols: Vec<Symbol>,
+    builder: AhoCorasickBuilder,
+    /// Generation of the interner when the automaton was built
+    generation: u64,
+}
+
+impl Matcher {
+    /// Builds the automaton for the strings of `interner`, configured with `builder`
+    ///
+    /// Empty strings are not matched.
+    fn build<H: BuildHasher>(
+        interner: &Interner<str, StringBackend, H>,
+        builder: AhoCorasickBuilder,
+    ) -> Result<Self, BuildError> {
+        let (symbols, patterns): (Vec<_>, Vec<_>) = interner.iter().filter(|(_, s)| !s.is_empty()).unzip();
+        let automaton = builder.build(patterns)?;
+        Ok(Self { automaton, symbols, builder, generation: interner.generation() })
+    }
+
+    fn to_symbol_match(&self, m: aho_corasick::Match) -> SymbolMatch {
+        SymbolMatch {
+            symbol: self.symbols[m.pattern().as_usize()],
+            start: m.start(),
+            end: m.end(),
+        }
+    }
+
+    /// Returns the first match in `text`
+    pub fn find(&self, text: &str) -> Option<SymbolMatch> {
+        self.automaton.find(text).map(|m| self.to_symbol_match(m))
+    }
+
+    /// Returns true if any of the strings appears in `text`
+    pub fn is_match(&self, text: &str) -> bool {
+        self.automaton.is_match(text)
+    }
+
+    /// Iterates over the non-overlapping matches in `text`
+    ///
+    /// With the default [MatchKind::LeftmostLongest], the
+    /// longest string is chosen among the ones that start at
+    /// the same position.
+    pub fn find_iter<'a>(&'a self, text: &'a str) -> impl Iterator<Item = SymbolMatch> + 'a {
+        self.automaton.find_iter(text).map(|m| self.to_symbol_match(m))
+    }
+
+    /// Iterates over all the matches in `text`, including the overlapping ones
+    ///
+    /// # Errors
+    /// If the matcher was not built with [MatchKind::Standard], which is
+    /// the only kind that supports overlapping matches. The default one,
+    /// from [build_matcher](Interner::build_matcher), doesn't.
+    ///
+    /// # Example
+    /// ```
+    /// use interns::StringInterner;
+    /// use interns::matcher::{AhoCorasickBuilder, MatchKind};
+    ///
+    /// let interner = StringInterner::from_lines(["he", "she"]);
+    /// assert!(interner.build_matcher().unwrap().find_overlapping_iter("she").is_err());
+    ///
+    /// let mut builder = AhoCorasickBuilder::new();
+    /// builder.match_kind(MatchKind::Standard);
+    /// let matcher = interner.build_matcher_with(builder).unwrap();
+    /// assert_eq!(matcher.find_overlapping_iter("she").unwrap().count(), 2);
+    /// ```
+    pub fn find_overlapping_iter<'a>(
+        &'a self,
+        text: &'a str,
+    ) -> Result<impl Iterator<Item = SymbolMatch> + 'a, MatchError> {
+        let iter = self.automaton.try_find_overlapping_iter(Input::new(text))?;
+        Ok(iter.map(|m| self.to_symbol_match(m)))
+    }
+
+    /// Returns the number of strings the matcher looks for
+    pub fn len(&self) -> usize {
+        self.symbols.len()
+    }
+
+    /// Returns true if the matcher doesn't look for any string
+    pub fn is_empty(&self) -> bool {
+        self.symbols.is_empty()
+    }
+
+    /// Returns true if `interner` changed since the matcher was built
+    ///
+    /// This compares the [generation](Interner::generation) of the interner,
+    /// so removed values are noticed too.
+    pub fn is_stale<H: BuildHasher>(&self, interner: &Interner<str, StringBackend, H>) -> bool {
+        interner.generation() != self.generation
+    }
+
+    /// Rebuilds the automaton if the interner [changed](Self::is_stale)
+    ///
+    /// The automaton keeps its configuration. Returns true if it was rebuilt.
+    pub fn refresh<H: BuildHasher>(&mut self, interner: &Interner<str, StringBackend, H>) -> Result<bool, BuildError> {
+        if !self.is_stale(interner) {
+            return Ok(false)
+        }
+        *self = Self::build(interner, self.builder.clone())?;
+        Ok(true)
+    }
+}
+
+impl<H: BuildHasher> Interner<str, StringBackend, H> {
+    /// Builds a [Matcher] that finds the strings of this interner in a text
+    ///
+    /// The matcher reports the [leftmost-longest](MatchKind::LeftmostLongest)
+    /// matches. Empty strings are not matched.
+    ///
+    /// # Errors
+    /// If the automaton would be too big. See [BuildError].
+    pub fn build_matcher(&self) -> Result<Matcher, BuildError> {
+        let mut builder = AhoCorasick::builder();
+        builder.match_kind(MatchKind::LeftmostLongest);
+        self.build_matcher_with(builder)
+    }
+
+    /// Like [build_matcher](Self::build_matcher), but with
+    /// the configuration of `builder`
+    ///
+    /// # Example
+    /// ```
+    /// use interns::StringInterner;
+    /// use interns::matcher::AhoCorasickBuilder;
+    ///
+    /// let interner = StringInterner::from_lines(["select", "from"]);
+    /// let mut builder = AhoCorasickBuilder::new();
+    /// builder.ascii_case_insensitive(true);
+    /// let matcher = interner.build_matcher_with(builder).unwrap();
+    /// assert_eq!(matcher.find_iter("SELECT * FROM t").count(), 2);
+    /// ```
+    pub fn build_matcher_with(&self, builder: AhoCorasickBuilder) -> Result<Matcher, BuildError> {
+        Matcher::build(self, builder)
+    }
+}
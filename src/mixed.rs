@@ -0,0 +1,170 @@
+//! Interner for both strings and byte blobs
+//!
+//! A [MixedInterner] stores [str] and [`[u8]`](slice) values in the same
+//! arena. Each [MixedSymbol] is tagged with the [Kind] of value it was
+//! interned as, so string symbols always resolve to valid UTF-8.
+//!
+//! A string and a blob with the same bytes share their storage,
+//! but get different symbols.
+//!
+//! # Example
+//! ```
+//! use interns::mixed::{Kind, MixedInterner, MixedRef};
+//!
+//! let mut interner = MixedInterner::new();
+//! let name = interner.get_or_intern_str("content-type");
+//! let value = interner.get_or_intern_bytes(b"\x00\xff");
+//!
+//! assert_eq!(name.kind(), Kind::Str);
+//! assert_eq!(interner.resolve_str(name), Some("content-type"));
+//! assert_eq!(interner.resolve(value), Some(MixedRef::Bytes(b"\x00\xff")));
+//! assert_eq!(interner.resolve_str(value), None);
+//! ```
+
+use core::hash::BuildHasher;
+
+use crate::backend::{Backend, VecBackend};
+use crate::hash::DefaultHashBuilder;
+use crate::Interner;
+
+type Span = <VecBackend<u8> as Backend<[u8]>>::Symbol;
+
+/// Kind of a [MixedSymbol]
+#[derive(Clone, Copy, Debug, Hash, Eq, PartialEq, PartialOrd, Ord)]
+pub enum Kind {
+    /// An UTF-8 string
+    Str,
+    /// A blob of bytes
+    Bytes,
+}
+
+/// Symbol of a [MixedInterner], tagged with its [Kind]
+#[derive(Clone, Copy, Debug, Hash, Eq, PartialEq, PartialOrd, Ord)]
+pub struct MixedSymbol {
+    span: Span,
+    kind: Kind,
+}
+
+impl MixedSymbol {
+    /// Returns the kind of value this symbol was interned as
+    pub const fn kind(&self) -> Kind {
+        self.kind
+    }
+
+    /// Returns true if this symbol was interned as a string
+    pub const fn is_str(&self) -> bool {
+        matches!(self.kind, Kind::Str)
+    }
+}
+
+/// A resolved value of a [MixedInterner]
+#[derive(Clone, Copy, Debug, Hash, Eq, PartialEq)]
+pub enum MixedRef<'a> {
+    Str(&'a str),
+    Bytes(&'a [u8]),
+}
+
+impl<'a> MixedRef<'a> {
+    /// Returns the bytes of the value, regardless of its kind
+    pub const fn as_bytes(&self) -> &'a [u8] {
+        match self {
+            MixedRef::Str(s) => s.as_bytes(),
+            MixedRef::Bytes(b) => b,
+        }
+    }
+}
+
+/// Interner for strings and byte blobs, that share a single arena
+///
+/// See the [module-level documentation](self) for more details.
+pub struct MixedInterner<H: BuildHasher = DefaultHashBuilder> {
+    inner: Interner<[u8], VecBackend<u8>, H>,
+}
+
+impl MixedInterner {
+    /// Creates an empty interner
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl<H: BuildHasher + Default> Default for MixedInterner<H> {
+    fn default() -> Self {
+        Self { inner: Interner::new() }
+    }
+}
+
+impl<H: BuildHasher> MixedInterner<H> {
+    /// Creates an empty interner with the given [hasher](BuildHasher)
+    pub fn with_hasher(hasher: H) -> Self {
+        Self { inner: Interner::with_hasher(hasher) }
+    }
+
+    /// Gets the symbol for the string `src`, interning it if it doesn't exist
+    pub fn get_or_intern_str(&mut self, src: &str) -> MixedSymbol {
+        let span = self.inner.get_or_intern(src.as_bytes());
+        MixedSymbol { span, kind: Kind::Str }
+    }
+
+    /// Gets the symbol for the blob `src`, interning it if it doesn't exist
+    pub fn get_or_intern_bytes(&mut self, src: &[u8]) -> MixedSymbol {
+        let span = self.inner.get_or_intern(src);
+        MixedSymbol { span, kind: Kind::Bytes }
+    }
+
+    /// Gets the symbol for the string `src`, without interning it
+    ///
+    /// Returns None if no value with the same bytes has been interned.
+    pub fn get_str(&self, src: &str) -> Option<MixedSymbol> {
+        let span = self.inner.get(src.as_bytes())?;
+        Some(MixedSymbol { span, kind: Kind::Str })
+    }
+
+    /// Gets the symbol for the blob `src`, without interning it
+    ///
+    /// Returns None if no value with the same bytes has been interned.
+    pub fn get_bytes(&self, src: &[u8]) -> Option<MixedSymbol> {
+        let span = self.inner.get(src)?;
+        Some(MixedSymbol { span, kind: Kind::Bytes })
+    }
+
+    /// Resolves the symbol into a value of its [Kind]
+    ///
+    /// Returns None if the symbol doesn't resolve. A string symbol from
+    /// another interner may point to bytes that aren't valid UTF-8, so
+    /// the bytes of string symbols are always validated.
+    pub fn resolve(&self, sym: MixedSymbol) -> Option<MixedRef<'_>> {
+        let bytes = self.inner.resolve(sym.span)?;
+        Some(match sym.kind {
+            Kind::Str => MixedRef::Str(core::str::from_utf8(bytes).ok()?),
+            Kind::Bytes => MixedRef::Bytes(bytes),
+        })
+    }
+
+    /// Resolves a string symbol
+    ///
+    /// Returns None if the symbol is not a [Kind::Str] symbol.
+    pub fn resolve_str(&self, sym: MixedSymbol) -> Option<&str> {
+        match self.resolve(sym)? {
+            MixedRef::Str(s) => Some(s),
+            MixedRef::Bytes(_) => None,
+        }
+    }
+
+    /// Resolves the bytes of the symbol, regardless of its [Kind]
+    pub fn resolve_bytes(&self, sym: MixedSymbol) -> Option<&[u8]> {
+        self.inner.resolve(sym.span)
+    }
+
+    /// Returns the number of unique byte sequences interned
+    ///
+    /// A string and a blob with the same bytes count as one.
+    pub fn len(&self) -> usize {
+        self.inner.len()
+    }
+
+    /// Returns true if nothing has been interned yet
+    pub fn is_empty(&self) -> bool {
+        self.inner.is_empty()
+    }
+}
@@ -0,0 +1,135 @@
+//! Namespaced views over a string interner
+//!
+//! A [Namespace] prefixes every key with a fixed string (e.g. `"pkg::"`)
+//! before looking it up on the interner. This isolates the lookups of
+//! different namespaces (like the tenants of a service), while all of
+//! them share the storage of a single interner.
+//!
+//! The prefix is stored after its length, as `"{len}:{prefix}"`. Otherwise,
+//! the key `":x"` of the namespace `"a:"` would be the key `"x"` of the
+//! namespace `"a::"`.
+//!
+//! # Example
+//! ```
+//! use interns::StringInterner;
+//!
+//! let mut interner = StringInterner::new();
+//!
+//! let a = interner.namespace("a::").get_or_intern("main");
+//! let b = interner.namespace("b::").get_or_intern("main");
+//! assert_ne!(a, b);
+//!
+//! let ns = interner.namespace("a::");
+//! assert_eq!(ns.get("main"), Some(a));
+//! assert_eq!(ns.resolve(a), Some("main"));
+//! assert_eq!(ns.resolve(b), None);
+//!
+//! /* The prefixed strings are regular strings of the interner */
+//! assert_eq!(interner.resolve(b), Some("3:b::main"));
+//! ```
+
+use core::cell::Cell;
+use core::hash::BuildHasher;
+
+use crate::backend::Internable;
+use crate::hash::DefaultHashBuilder;
+use crate::{Backend, Interner, StringBackend};
+
+/// View over an [Interner] that prefixes its keys
+///
+/// See the [module-level documentation](self) for more details.
+pub struct Namespace<'a, B = StringBackend, H = DefaultHashBuilder>
+where
+    B: Backend<str>,
+    H: BuildHasher,
+{
+    interner: &'a mut Interner<str, B, H>,
+    prefix: &'a str,
+    /// The prefix, after its length
+    tag: String,
+    /// Buffer to build the prefixed keys, which always starts with `tag`
+    key: Cell<String>,
+}
+
+/// Calls `f` with `key` prefixed by `tag`, built on `buf`
+fn with_prefixed<R>(buf: &Cell<String>, tag: &str, key: &str, f: impl FnOnce(&str) -> R) -> R {
+    let mut s = buf.take();
+    s.truncate(tag.len());
+    s.push_str(key);
+    let result = f(&s);
+    buf.set(s);
+    result
+}
+
+impl<'a, B, H> Namespace<'a, B, H>
+where
+    B: Backend<str>,
+    H: BuildHasher,
+    str: Internable<str, B>,
+{
+    /// Creates a view of the keys of `interner` tagged with `prefix`
+    pub fn new(interner: &'a mut Interner<str, B, H>, prefix: &'a str) -> Self {
+        let tag = format!("{}:{prefix}", prefix.len());
+        Self {
+            interner,
+            prefix,
+            key: Cell::new(tag.clone()),
+            tag,
+        }
+    }
+
+    /// Gets the symbol of `key` on this namespace, interning it if it doesn't exist
+    pub fn get_or_intern(&mut self, key: &str) -> B::Symbol {
+        let Self { interner, tag, key: buf, .. } = self;
+        with_prefixed(buf, tag, key, |key| interner.get_or_intern(key))
+    }
+
+    /// Gets the symbol of `key` on this namespace, without interning it
+    pub fn get(&self, key: &str) -> Option<B::Symbol> {
+        with_prefixed(&self.key, &self.tag, key, |key| self.interner.get(key))
+    }
+
+    /// Returns true if `key` has been interned on this namespace
+    pub fn contains(&self, key: &str) -> bool {
+        self.get(key).is_some()
+    }
+
+    /// Resolves the symbol into its key, without the prefix
+    ///
+    /// Returns None if the symbol doesn't belong to this namespace.
+    pub fn resolve(&self, sym: B::Symbol) -> Option<&str> {
+        self.interner.resolve(sym)?.strip_prefix(&self.tag)
+    }
+
+    /// Returns an iterator over the symbols of this namespace, and their keys
+    ///
+    /// This goes through every string of the interner. The
+    /// iteration order is unspecified.
+    pub fn iter(&self) -> impl Iterator<Item = (B::Symbol, &str)> {
+        self.interner
+            .iter()
+            .filter_map(|(sym, s)| Some((sym, s.strip_prefix(&self.tag)?)))
+    }
+
+    /// Returns the prefix of this namespace
+    pub fn prefix(&self) -> &str {
+        self.prefix
+    }
+
+    /// Returns the underlying interner
+    pub fn interner(&self) -> &Interner<str, B, H> {
+        self.interner
+    }
+}
+
+impl<B, H> Interner<str, B, H>
+where
+    B: Backend<str>,
+    H: BuildHasher,
+    str: Internable<str, B>,
+{
+    /// Returns a [Namespace] that prefixes every key with `prefix`
+    pub fn namespace<'a>(&'a mut self, prefix: &'a str) -> Namespace<'a, B, H> {
+        Namespace::new(self, prefix)
+    }
+}
@@ -0,0 +1,243 @@
+//! Normalization of strings before interning
+//!
+//! A [NormalizedInterner] passes every string through a [Normalizer]
+//! before looking it up or interning it. Strings with the same
+//! normalization get the same symbol, and resolve to the normalized string.
+//!
+//! # Example
+//! ```
+//! use interns::normalize::{AsciiLowercase, NormalizedInterner, Trim};
+//!
+//! let mut interner = NormalizedInterner::new((Trim, AsciiLowercase));
+//! let a = interner.get_or_intern("  SELECT ");
+//! let b = interner.get_or_intern("select");
+//! assert_eq!(a, b);
+//! assert_eq!(interner.resolve(a), Some("select"));
+//! ```
+//!
+//! Any function from `&str` to [`Cow<str>`](Cow) can be used as a normalizer.
+//!
+//! ```
+//! use std::borrow::Cow;
+//! use interns::normalize::NormalizedInterner;
+//!
+//! fn clean_path(path: &str) -> Cow<'_, str> {
+//!     Cow::Borrowed(path.strip_suffix('/').unwrap_or(path))
+//! }
+//!
+//! let mut interner = NormalizedInterner::new(clean_path);
+//! assert_eq!(interner.get_or_intern("/usr/"), interner.get_or_intern("/usr"));
+//! ```
+//!
+//! # Case-insensitive lookups
+//! A regular [Interner] can also be searched ignoring case, with
+//! [get_ignore_case](Interner::get_ignore_case). The strings keep their
+//! original spelling, which is returned along with the symbol.
+
+use core::hash::BuildHasher;
+use std::borrow::Cow;
+
+use crate::backend::Internable;
+use crate::hash::DefaultHashBuilder;
+use crate::{Backend, Interner, StringBackend};
+
+/// Canonicalizes strings before they are interned
+pub trait Normalizer {
+    /// Returns the normalized form of `s`
+    ///
+    /// Return [Cow::Borrowed] when `s` is already normalized,
+    /// to avoid allocating.
+    fn normalize<'a>(&self, s: &'a str) -> Cow<'a, str>;
+}
+
+impl<F> Normalizer for F
+where
+    F: for<'a> Fn(&'a str) -> Cow<'a, str>,
+{
+    fn normalize<'a>(&self, s: &'a str) -> Cow<'a, str> {
+        self(s)
+    }
+}
+
+/// Applies both normalizers, in order
+impl<A: Normalizer, B: Normalizer> Normalizer for (A, B) {
+    fn normalize<'a>(&self, s: &'a str) -> Cow<'a, str> {
+        match self.0.normalize(s) {
+            Cow::Borrowed(s) => self.1.normalize(s),
+            Cow::Owned(s) => Cow::Owned(self.1.normalize(&s).into_owned()),
+        }
+    }
+}
+
+/// Removes leading and trailing whitespace
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Trim;
+
+impl Normalizer for Trim {
+    fn normalize<'a>(&self, s: &'a str) -> Cow<'a, str> {
+        Cow::Borrowed(s.trim())
+    }
+}
+
+/// Converts ASCII letters to lowercase
+#[derive(Clone, Copy, Debug, Default)]
+pub struct AsciiLowercase;
+
+impl Normalizer for AsciiLowercase {
+    fn normalize<'a>(&self, s: &'a str) -> Cow<'a, str> {
+        if s.bytes().any(|b| b.is_ascii_uppercase()) {
+            Cow::Owned(s.to_ascii_lowercase())
+        } else {
+            Cow::Borrowed(s)
+        }
+    }
+}
+
+/// Converts the string to lowercase, following the Unicode rules
+///
+/// See [str::to_lowercase]
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Lowercase;
+
+impl Normalizer for Lowercase {
+    fn normalize<'a>(&self, s: &'a str) -> Cow<'a, str> {
+        if s.chars().any(|c| c.is_uppercase()) {
+            Cow::Owned(s.to_lowercase())
+        } else {
+            Cow::Borrowed(s)
+        }
+    }
+}
+
+/// Normalizes the string to [NFC](crate::unicode)
+#[cfg(feature = "unicode")]
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Nfc;
+
+#[cfg(feature = "unicode")]
+impl Normalizer for Nfc {
+    fn normalize<'a>(&self, s: &'a str) -> Cow<'a, str> {
+        crate::unicode::nfc(s)
+    }
+}
+
+/// String interner that [normalizes](Normalizer) its input
+///
+/// See the [module-level documentation](self) for more details.
+pub struct NormalizedInterner<N, B = StringBackend, H = DefaultHashBuilder>
+where
+    B: Backend<str>,
+    H: BuildHasher,
+{
+    inner: Interner<str, B, H>,
+    normalizer: N,
+}
+
+impl<N: Normalizer> NormalizedInterner<N> {
+    /// Creates an empty interner that uses `normalizer`
+    pub fn new(normalizer: N) -> Self {
+        Self { inner: Interner::new(), normalizer }
+    }
+}
+
+impl<N, B, H> NormalizedInterner<N, B, H>
+where
+    N: Normalizer,
+    B: Backend<str>,
+    H: BuildHasher,
+    str: Internable<str, B>,
+{
+    /// Wraps `interner`, normalizing the strings interned from now on
+    ///
+    /// The strings already on `interner` are not normalized.
+    pub fn with_interner(interner: Interner<str, B, H>, normalizer: N) -> Self {
+        Self { inner: interner, normalizer }
+    }
+
+    /// Gets the symbol of the normalization of `src`, interning it if it doesn't exist
+    pub fn get_or_intern(&mut self, src: &str) -> B::Symbol {
+        let src = self.normalizer.normalize(src);
+        self.inner.get_or_intern(&*src)
+    }
+
+    /// Gets the symbol of the normalization of `src`, without interning it
+    pub fn get(&self, src: &str) -> Option<B::Symbol> {
+        self.inner.get(&*self.normalizer.normalize(src))
+    }
+
+    /// Returns true if the normalization of `src` has been interned
+    pub fn contains(&self, src: &str) -> bool {
+        self.get(src).is_some()
+    }
+
+    /// Resolves the symbol into the normalized string
+    pub fn resolve(&self, sym: B::Symbol) -> Option<&str> {
+        self.inner.resolve(sym)
+    }
+
+    /// Returns the number of unique normalized strings
+    pub fn len(&self) -> usize {
+        self.inner.len()
+    }
+
+    /// Returns true if nothing has been interned yet
+    pub fn is_empty(&self) -> bool {
+        self.inner.is_empty()
+    }
+
+    /// Returns the normalizer
+    pub fn normalizer(&self) -> &N {
+        &self.normalizer
+    }
+
+    /// Returns the underlying interner
+    pub fn interner(&self) -> &Interner<str, B, H> {
+        &self.inner
+    }
+
+    /// Returns the underlying interner
+    pub fn into_interner(self) -> Interner<str, B, H> {
+        self.inner
+    }
+}
+
+/// Returns true if `a` and `b` are equal after [lowercasing](char::to_lowercase) them
+fn eq_ignore_case(a: &str, b: &str) -> bool {
+    a.chars().flat_map(char::to_lowercase).eq(b.chars().flat_map(char::to_lowercase))
+}
+
+impl<B, H> Interner<str, B, H>
+where
+    B: Backend<str>,
+    H: BuildHasher,
+{
+    /// Looks for a string equal to `query`, ignoring case
+    ///
+    /// Returns the symbol of the string, and its stored spelling.
+    /// An exact match is preferred. Otherwise, this compares `query` against
+    /// every string, and any of the matching ones can be returned.
+    ///
+    /// # Example
+    /// ```
+    /// use interns::StringInterner;
+    ///
+    /// let mut idents = StringInterner::new();
+    /// let user_id = idents.get_or_intern("userId");
+    ///
+    /// let ident = "userID";
+    /// if let Some((sym, original)) = idents.get_ignore_case(ident)
+    ///     && original != ident
+    /// {
+    ///     assert_eq!(sym, user_id);
+    ///     println!("warning: `{ident}` differs only in case from `{original}`");
+    /// }
+    /// ```
+    pub fn get_ignore_case(&self, query: &str) -> Option<(B::Symbol, &str)> {
+        if let Some(sym) = self.get(query)
+            && let Some(s) = self.resolve(sym)
+        {
+            return Some((sym, s))
+        }
+        self.iter().find(|(_, s)| eq_ignore_case(s, query))
+    }
+}
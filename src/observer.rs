@@ -0,0 +1,183 @@
+//! Intern event hooks
+//!
+//! An [ObservedInterner] notifies an [Observer] each time a value is
+//! requested. This allows maintaining derived indexes, logging new
+//! vocabulary, or enforcing a policy on the values, without wrapping
+//! every call site.
+//!
+//! # Example
+//! ```
+//! use interns::observer::ObservedInterner;
+//!
+//! let mut vocabulary = Vec::new();
+//! let mut interner = ObservedInterner::<str, _>::new(|_, word: &str, was_new| {
+//!     if was_new {
+//!         vocabulary.push(word.to_owned());
+//!     }
+//! });
+//! for word in "to be or not to be".split(' ') {
+//!     interner.get_or_intern(word);
+//! }
+//! drop(interner);
+//! assert_eq!(vocabulary, ["to", "be", "or", "not"]);
+//! ```
+//!
+//! # Policies
+//! An observer can [reject](Observer::admit) new values. Values already on
+//! the interner are never rejected.
+//!
+//! ```
+//! use interns::observer::{Observer, ObservedInterner};
+//! use interns::Symbol;
+//!
+//! struct MaxLen(usize);
+//!
+//! impl Observer<str, Symbol<str>> for MaxLen {
+//!     fn admit(&mut self, value: &str) -> bool {
+//!         value.len() <= self.0
+//!     }
+//!
+//!     fn on_intern(&mut self, _sym: Symbol<str>, _value: &str, _was_new: bool) {}
+//! }
+//!
+//! let mut interner = ObservedInterner::<str, _>::new(MaxLen(5));
+//! assert!(interner.get_or_intern("short").is_some());
+//! assert!(interner.get_or_intern("too long").is_none());
+//! assert_eq!(interner.len(), 1);
+//! ```
+
+use core::borrow::Borrow;
+use core::hash::{BuildHasher, Hash};
+
+use crate::backend::{DefaultBackend, DefaultBackendBuilder, Internable};
+use crate::hash::DefaultHashBuilder;
+use crate::{Backend, Interner};
+
+/// Receives the intern events of an [ObservedInterner]
+///
+/// Any `FnMut(S, &T, bool)` can be used as an observer.
+pub trait Observer<T: ?Sized, S> {
+    /// Decides if `value` can be interned
+    ///
+    /// This is only called for values that are not on the interner yet.
+    /// By default, every value is admitted.
+    fn admit(&mut self, value: &T) -> bool {
+        let _ = value;
+        true
+    }
+
+    /// Called each time `value` is requested
+    ///
+    /// `was_new` is true if `value` wasn't on the interner before.
+    fn on_intern(&mut self, sym: S, value: &T, was_new: bool);
+}
+
+impl<T: ?Sized, S, F: FnMut(S, &T, bool)> Observer<T, S> for F {
+    fn on_intern(&mut self, sym: S, value: &T, was_new: bool) {
+        self(sym, value, was_new)
+    }
+}
+
+/// Interner that notifies an [Observer] of every request
+///
+/// See the [module-level documentation](self) for more details.
+pub struct ObservedInterner<T, O, B = DefaultBackend<T>, H = DefaultHashBuilder>
+where
+    T: Hash + Eq + ?Sized,
+    B: Backend<T>,
+    H: BuildHasher,
+{
+    inner: Interner<T, B, H>,
+    observer: O,
+}
+
+impl<T, O> ObservedInterner<T, O>
+where
+    T: Hash + Eq + ?Sized + DefaultBackendBuilder,
+    DefaultBackend<T>: Default,
+    O: Observer<T, <DefaultBackend<T> as Backend<T>>::Symbol>,
+{
+    /// Creates an empty interner, observed by `observer`
+    pub fn new(observer: O) -> Self {
+        Self::with_interner(Interner::new(), observer)
+    }
+}
+
+impl<T, O, B, H> ObservedInterner<T, O, B, H>
+where
+    T: Hash + Eq + ?Sized,
+    B: Backend<T>,
+    H: BuildHasher,
+    O: Observer<T, B::Symbol>,
+{
+    /// Wraps `interner`
+    ///
+    /// The observer isn't notified of the values already on `interner`.
+    pub fn with_interner(interner: Interner<T, B, H>, observer: O) -> Self {
+        Self { inner: interner, observer }
+    }
+
+    /// Gets the symbol for `src`, interning it if it doesn't exist,
+    /// and notifies the observer
+    ///
+    /// Returns None if `src` is new, and the observer doesn't [admit](Observer::admit) it.
+    pub fn get_or_intern(&mut self, src: &T) -> Option<B::Symbol>
+    where
+        T: Internable<T, B> + Borrow<T>,
+    {
+        let was_new = match self.inner.get(src) {
+            Some(_) => false,
+            None if self.observer.admit(src) => true,
+            None => return None,
+        };
+        let sym = self.inner.get_or_intern(src);
+        self.observer.on_intern(sym, src, was_new);
+        Some(sym)
+    }
+
+    /// Gets the symbol for `src`, without interning it
+    ///
+    /// The observer isn't notified.
+    pub fn get<Ref>(&self, src: &Ref) -> Option<B::Symbol>
+    where
+        Ref: ?Sized + Hash + Eq,
+        T: Borrow<Ref>,
+    {
+        self.inner.get(src)
+    }
+
+    /// Resolves the symbol
+    pub fn resolve(&self, sym: B::Symbol) -> Option<&T> {
+        self.inner.resolve(sym)
+    }
+
+    /// Returns the number of unique values
+    pub fn len(&self) -> usize {
+        self.inner.len()
+    }
+
+    /// Returns true if nothing has been interned yet
+    pub fn is_empty(&self) -> bool {
+        self.inner.is_empty()
+    }
+
+    /// Returns the observer
+    pub fn observer(&self) -> &O {
+        &self.observer
+    }
+
+    /// Returns a mutable reference to the observer
+    pub fn observer_mut(&mut self) -> &mut O {
+        &mut self.observer
+    }
+
+    /// Returns the underlying interner
+    pub fn interner(&self) -> &Interner<T, B, H> {
+        &self.inner
+    }
+
+    /// Returns the underlying interner and the observer
+    pub fn into_parts(self) -> (Interner<T, B, H>, O) {
+        (self.inner, self.observer)
+    }
+}
@@ -0,0 +1,203 @@
+//! Interning of filesystem paths
+//!
+//! The same file can be referred to by many different paths
+//! (`src/main.rs`, `./src/main.rs`, `src/../src/main.rs`...).
+//! A [PathInterner] normalizes paths before interning them, according
+//! to its [PathOptions], so that those paths get the same symbol.
+//!
+//! # Example
+//! ```
+//! use std::path::Path;
+//! use interns::path::PathInterner;
+//!
+//! let mut interner = PathInterner::new();
+//! let a = interner.get_or_intern("src/main.rs");
+//! let b = interner.get_or_intern("./src/lib/../main.rs");
+//! assert_eq!(a, b);
+//! assert_eq!(interner.resolve(a), Some(Path::new("src/main.rs")));
+//! ```
+
+use core::hash::BuildHasher;
+use std::borrow::Cow;
+use std::path::{Component, Path, PathBuf};
+
+use crate::hash::DefaultHashBuilder;
+use crate::{Interner, Symbol};
+use crate::backend::VecBackend;
+
+/// Symbol of a [PathInterner]
+pub type PathSymbol = Symbol<PathBuf>;
+
+/// Options of a [PathInterner]
+///
+/// By default, paths are [simplified](Self::simplify), but separators
+/// are not [unified](Self::unify_separators), and paths are not
+/// [canonicalized](Self::canonicalize).
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct PathOptions {
+    unify_separators: bool,
+    simplify: bool,
+    canonicalize: bool,
+}
+
+impl PathOptions {
+    /// Creates the default options
+    pub const fn new() -> Self {
+        Self {
+            unify_separators: false,
+            simplify: true,
+            canonicalize: false,
+        }
+    }
+
+    /// Replace backslashes with forward slashes
+    ///
+    /// This allows Windows-style paths to be interned on other platforms.
+    /// Note that on Unix, a backslash is a valid character of a file name.
+    /// Only paths that are valid UTF-8 are modified.
+    pub const fn unify_separators(mut self, unify: bool) -> Self {
+        self.unify_separators = unify;
+        self
+    }
+
+    /// Simplify the path lexically
+    ///
+    /// This removes `.` components, repeated and trailing separators,
+    /// and `..` components that follow a normal component. Symlinks are
+    /// not taken into account, so `a/../b` becomes `b` even if `a` is a symlink.
+    pub const fn simplify(mut self, simplify: bool) -> Self {
+        self.simplify = simplify;
+        self
+    }
+
+    /// Canonicalize the path on the filesystem
+    ///
+    /// See [std::fs::canonicalize]. Paths that can't be canonicalized
+    /// (e.g. because they don't exist) are interned as if this option
+    /// was disabled.
+    pub const fn canonicalize(mut self, canonicalize: bool) -> Self {
+        self.canonicalize = canonicalize;
+        self
+    }
+
+    /// Applies the options to `path`
+    pub fn normalize<'a>(&self, path: &'a Path) -> Cow<'a, Path> {
+        let mut path = Cow::Borrowed(path);
+        if self.unify_separators
+            && let Some(s) = path.to_str()
+            && s.contains('\\')
+        {
+            path = Cow::Owned(PathBuf::from(s.replace('\\', "/")));
+        }
+        if self.canonicalize
+            && let Ok(canonical) = std::fs::canonicalize(&path)
+        {
+            return Cow::Owned(canonical);
+        }
+        if self.simplify {
+            let simple = simplify(&path);
+            if simple.as_os_str() != path.as_os_str() {
+                path = Cow::Owned(simple);
+            }
+        }
+        path
+    }
+}
+
+impl Default for PathOptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Simplifies `path` lexically
+fn simplify(path: &Path) -> PathBuf {
+    let mut result = PathBuf::new();
+    /* Number of normal components at the end of result,
+     * that can be removed by a `..` */
+    let mut normal = 0;
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir if normal > 0 => {
+                result.pop();
+                normal -= 1;
+            }
+            /* `..` on the root is the root itself */
+            Component::ParentDir if result.has_root() => {}
+            Component::Normal(_) => {
+                result.push(component);
+                normal += 1;
+            }
+            Component::ParentDir | Component::Prefix(_) | Component::RootDir => result.push(component),
+        }
+    }
+    if result.as_os_str().is_empty() {
+        result.push(".");
+    }
+    result
+}
+
+/// Interner for filesystem paths
+///
+/// See the [module-level documentation](self) for more details.
+pub struct PathInterner<H: BuildHasher = DefaultHashBuilder> {
+    inner: Interner<PathBuf, VecBackend<PathBuf>, H>,
+    options: PathOptions,
+}
+
+impl PathInterner {
+    /// Creates an empty interner, with the default [PathOptions]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates an empty interner, with the given options
+    pub fn with_options(options: PathOptions) -> Self {
+        Self { inner: Interner::new(), options }
+    }
+}
+
+impl<H: BuildHasher + Default> Default for PathInterner<H> {
+    fn default() -> Self {
+        Self { inner: Interner::new(), options: PathOptions::new() }
+    }
+}
+
+impl<H: BuildHasher> PathInterner<H> {
+    /// Returns the options of this interner
+    pub fn options(&self) -> &PathOptions {
+        &self.options
+    }
+
+    /// Gets the symbol of the normalized `path`, interning it if it doesn't exist
+    pub fn get_or_intern(&mut self, path: impl AsRef<Path>) -> PathSymbol {
+        let path = self.options.normalize(path.as_ref());
+        self.inner.get_or_intern(&*path)
+    }
+
+    /// Gets the symbol of the normalized `path`, without interning it
+    pub fn get(&self, path: impl AsRef<Path>) -> Option<PathSymbol> {
+        self.inner.get(&*self.options.normalize(path.as_ref()))
+    }
+
+    /// Returns true if the normalized `path` has been interned
+    pub fn contains(&self, path: impl AsRef<Path>) -> bool {
+        self.get(path).is_some()
+    }
+
+    /// Resolves the symbol into the normalized path
+    pub fn resolve(&self, sym: PathSymbol) -> Option<&Path> {
+        self.inner.resolve(sym).map(PathBuf::as_path)
+    }
+
+    /// Returns the number of unique paths
+    pub fn len(&self) -> usize {
+        self.inner.len()
+    }
+
+    /// Returns true if nothing has been interned yet
+    pub fn is_empty(&self) -> bool {
+        self.inner.is_empty()
+    }
+}
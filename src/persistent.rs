@@ -0,0 +1,277 @@
+//! Persistent interner
+//!
+//! A [PersistentInterner] is immutable. [Interning](PersistentInterner::intern)
+//! a value returns a new version of the interner, that shares most of its
+//! structure with the old one. Keeping many versions around (e.g. for an
+//! undo history) is cheap, and cloning a version is O(1).
+//!
+//! The interner is append-only, so a symbol resolves to the same value on
+//! the version that produced it, and on every version derived from it.
+//!
+//! # Example
+//! ```
+//! use interns::persistent::PersistentInterner;
+//!
+//! let v0 = PersistentInterner::<str>::new();
+//! let (v1, a) = v0.intern("a");
+//! let (v2, b) = v1.intern("b");
+//!
+//! assert!(v0.is_empty());
+//! assert_eq!(v1.resolve(a), Some("a"));
+//! assert_eq!(v1.resolve(b), None);
+//! assert_eq!(v2.resolve(a), Some("a"));
+//! assert_eq!(v2.resolve(b), Some("b"));
+//! ```
+
+use core::borrow::Borrow;
+use core::hash::{BuildHasher, Hash};
+use std::sync::Arc;
+
+use crate::hash::DefaultHashBuilder;
+
+/// Bits of the key consumed on each level of a [Trie]
+const BITS: u32 = 4;
+/// Number of children of each [Node]
+const WIDTH: usize = 1 << BITS;
+const MASK: u64 = WIDTH as u64 - 1;
+
+enum Slot<V> {
+    Empty,
+    Leaf(u64, V),
+    Branch(Arc<Node<V>>),
+}
+
+impl<V: Clone> Clone for Slot<V> {
+    fn clone(&self) -> Self {
+        match self {
+            Slot::Empty => Slot::Empty,
+            Slot::Leaf(key, value) => Slot::Leaf(*key, value.clone()),
+            Slot::Branch(node) => Slot::Branch(Arc::clone(node)),
+        }
+    }
+}
+
+struct Node<V>([Slot<V>; WIDTH]);
+
+impl<V: Clone> Node<V> {
+    fn empty() -> Self {
+        Node(core::array::from_fn(|_| Slot::Empty))
+    }
+
+    /// Returns a copy of this node with `value` inserted, consuming
+    /// the key from `shift` onwards
+    fn insert(&self, key: u64, shift: u32, value: V) -> Self {
+        let mut node = Node(self.0.clone());
+        let i = ((key >> shift) & MASK) as usize;
+        node.0[i] = match &self.0[i] {
+            Slot::Empty => Slot::Leaf(key, value),
+            Slot::Leaf(k, _) if *k == key => Slot::Leaf(key, value),
+            Slot::Leaf(k, v) => {
+                /* Push the existing leaf one level down. Different
+                 * keys differ before we run out of bits. */
+                let mut child = Node::empty();
+                child.0[((k >> (shift + BITS)) & MASK) as usize] = Slot::Leaf(*k, v.clone());
+                Slot::Branch(Arc::new(child.insert(key, shift + BITS, value)))
+            }
+            Slot::Branch(child) => Slot::Branch(Arc::new(child.insert(key, shift + BITS, value))),
+        };
+        node
+    }
+}
+
+/// Persistent map from u64 keys to values
+///
+/// It's a trie, where each level is indexed by [BITS] bits of the key. Leaves
+/// are kept as high as possible, so the depth is logarithmic on the length.
+/// An insertion copies the nodes of the path to the key, and shares the rest.
+struct Trie<V> {
+    root: Arc<Node<V>>,
+}
+
+impl<V> Clone for Trie<V> {
+    fn clone(&self) -> Self {
+        Self { root: Arc::clone(&self.root) }
+    }
+}
+
+impl<V: Clone> Trie<V> {
+    fn new() -> Self {
+        Self { root: Arc::new(Node::empty()) }
+    }
+
+    fn get(&self, key: u64) -> Option<&V> {
+        let mut node = &*self.root;
+        let mut rest = key;
+        loop {
+            match &node.0[(rest & MASK) as usize] {
+                Slot::Empty => return None,
+                Slot::Leaf(k, value) => return (*k == key).then_some(value),
+                Slot::Branch(child) => {
+                    node = child;
+                    rest >>= BITS;
+                }
+            }
+        }
+    }
+
+    fn insert(&self, key: u64, value: V) -> Self {
+        Self { root: Arc::new(self.root.insert(key, 0, value)) }
+    }
+}
+
+/// Symbol of a [PersistentInterner]
+#[derive(Clone, Copy, Debug, Hash, Eq, PartialEq, PartialOrd, Ord)]
+pub struct PersistentSymbol(u32);
+
+impl PersistentSymbol {
+    /// Returns the position of the symbol, in the order the values were interned
+    pub fn index(self) -> usize {
+        self.0 as usize
+    }
+}
+
+/// Immutable interner with structural sharing between versions
+///
+/// See the [module-level documentation](self) for more details.
+pub struct PersistentInterner<T: ?Sized, H = DefaultHashBuilder> {
+    /// Values, by the index of their symbol
+    values: Trie<Arc<T>>,
+    /// Symbols, by the hash of their value
+    index: Trie<Arc<[u32]>>,
+    len: u32,
+    hasher: H,
+}
+
+impl<T: ?Sized, H: Clone> Clone for PersistentInterner<T, H> {
+    /// Clones this version of the interner
+    ///
+    /// This is O(1), since the clone shares all its structure.
+    fn clone(&self) -> Self {
+        Self {
+            values: self.values.clone(),
+            index: self.index.clone(),
+            len: self.len,
+            hasher: self.hasher.clone(),
+        }
+    }
+}
+
+impl<T: ?Sized + Hash + Eq> PersistentInterner<T> {
+    /// Creates an empty interner
+    pub fn new() -> Self {
+        Self::with_hasher(DefaultHashBuilder::default())
+    }
+}
+
+impl<T: ?Sized + Hash + Eq> Default for PersistentInterner<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: ?Sized + Hash + Eq, H: BuildHasher + Clone> PersistentInterner<T, H> {
+    /// Creates an empty interner that uses the given [hasher](BuildHasher)
+    pub fn with_hasher(hasher: H) -> Self {
+        Self {
+            values: Trie::new(),
+            index: Trie::new(),
+            len: 0,
+            hasher,
+        }
+    }
+
+    /// Returns a version of the interner that contains `src`, and its symbol
+    ///
+    /// If `src` is already interned, the returned version is a clone of this one.
+    ///
+    /// # Panics
+    /// If the interner already holds [u32::MAX] values
+    pub fn intern(&self, src: &T) -> (Self, PersistentSymbol)
+    where
+        T: ToOwned,
+        T::Owned: Into<Arc<T>>,
+    {
+        if let Some(sym) = self.get(src) {
+            return (self.clone(), sym)
+        }
+        assert!(self.len < u32::MAX, "PersistentInterner is full");
+        let sym = PersistentSymbol(self.len);
+        let hash = self.hasher.hash_one(src);
+        let bucket: Arc<[u32]> = match self.index.get(hash) {
+            Some(bucket) => bucket.iter().copied().chain([sym.0]).collect(),
+            None => Arc::new([sym.0]),
+        };
+        let next = Self {
+            values: self.values.insert(sym.0.into(), src.to_owned().into()),
+            index: self.index.insert(hash, bucket),
+            len: self.len + 1,
+            hasher: self.hasher.clone(),
+        };
+        (next, sym)
+    }
+
+    /// Interns `src`, replacing this version with the new one
+    ///
+    /// Other clones of this version are not affected.
+    pub fn get_or_intern(&mut self, src: &T) -> PersistentSymbol
+    where
+        T: ToOwned,
+        T::Owned: Into<Arc<T>>,
+    {
+        let (next, sym) = self.intern(src);
+        *self = next;
+        sym
+    }
+
+    /// Gets the symbol for `src`, if it's on this version
+    pub fn get<Ref>(&self, src: &Ref) -> Option<PersistentSymbol>
+    where
+        Ref: ?Sized + Hash + Eq,
+        T: Borrow<Ref>,
+    {
+        let hash = self.hasher.hash_one(src);
+        let bucket = self.index.get(hash)?;
+        bucket
+            .iter()
+            .map(|&i| PersistentSymbol(i))
+            .find(|&sym| self.resolve(sym).is_some_and(|value| value.borrow() == src))
+    }
+
+    /// Returns true if `src` is on this version
+    pub fn contains<Ref>(&self, src: &Ref) -> bool
+    where
+        Ref: ?Sized + Hash + Eq,
+        T: Borrow<Ref>,
+    {
+        self.get(src).is_some()
+    }
+
+    /// Resolves the symbol
+    ///
+    /// Returns None if the symbol was produced by a version
+    /// that this one doesn't derive from.
+    pub fn resolve(&self, sym: PersistentSymbol) -> Option<&T> {
+        if sym.0 >= self.len {
+            return None
+        }
+        self.values.get(sym.0.into()).map(|value| &**value)
+    }
+
+    /// Returns the number of values on this version
+    pub fn len(&self) -> usize {
+        self.len as usize
+    }
+
+    /// Returns true if this version is empty
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Iterates over the values, in the order they were interned
+    pub fn iter(&self) -> impl Iterator<Item = (PersistentSymbol, &T)> {
+        (0..self.len).filter_map(|i| {
+            let sym = PersistentSymbol(i);
+            Some((sym, self.resolve(sym)?))
+        })
+    }
+}
@@ -0,0 +1,58 @@
+//! C keywords, as of C11
+//!
+//! # Example
+//! ```
+//! use interns::presets::c;
+//!
+//! let mut interner = c::BUILDER.build();
+//! assert_eq!(interner.get_or_intern("typedef"), c::KW_TYPEDEF);
+//! assert_eq!(c::lookup("_Bool"), Some(c::KW__BOOL));
+//! ```
+
+super::preset! {
+    "the C keywords";
+    KW_AUTO = "auto",
+    KW_BREAK = "break",
+    KW_CASE = "case",
+    KW_CHAR = "char",
+    KW_CONST = "const",
+    KW_CONTINUE = "continue",
+    KW_DEFAULT = "default",
+    KW_DO = "do",
+    KW_DOUBLE = "double",
+    KW_ELSE = "else",
+    KW_ENUM = "enum",
+    KW_EXTERN = "extern",
+    KW_FLOAT = "float",
+    KW_FOR = "for",
+    KW_GOTO = "goto",
+    KW_IF = "if",
+    KW_INLINE = "inline",
+    KW_INT = "int",
+    KW_LONG = "long",
+    KW_REGISTER = "register",
+    KW_RESTRICT = "restrict",
+    KW_RETURN = "return",
+    KW_SHORT = "short",
+    KW_SIGNED = "signed",
+    KW_SIZEOF = "sizeof",
+    KW_STATIC = "static",
+    KW_STRUCT = "struct",
+    KW_SWITCH = "switch",
+    KW_TYPEDEF = "typedef",
+    KW_UNION = "union",
+    KW_UNSIGNED = "unsigned",
+    KW_VOID = "void",
+    KW_VOLATILE = "volatile",
+    KW_WHILE = "while",
+    KW__ALIGNAS = "_Alignas",
+    KW__ALIGNOF = "_Alignof",
+    KW__ATOMIC = "_Atomic",
+    KW__BOOL = "_Bool",
+    KW__COMPLEX = "_Complex",
+    KW__GENERIC = "_Generic",
+    KW__IMAGINARY = "_Imaginary",
+    KW__NORETURN = "_Noreturn",
+    KW__STATIC_ASSERT = "_Static_assert",
+    KW__THREAD_LOCAL = "_Thread_local",
+}
@@ -0,0 +1,180 @@
+//! HTTP header names
+//!
+//! The header names are interned in lowercase, as they're sent on
+//! HTTP/2 and HTTP/3. [canonical] returns their conventional casing
+//! (e.g. `Content-Type`), and [lookup_ignore_case] finds the symbol
+//! of a header name written with any casing.
+//!
+//! # Example
+//! ```
+//! use interns::presets::http;
+//!
+//! let mut interner = http::BUILDER.build();
+//! let sym = http::lookup_ignore_case("Content-Length")
+//!     .unwrap_or_else(|| interner.get_or_intern("content-length"));
+//! assert_eq!(sym, http::CONTENT_LENGTH);
+//! assert_eq!(http::canonical(sym), Some("Content-Length"));
+//! ```
+
+super::preset! {
+    "common HTTP header names";
+    ACCEPT = "accept",
+    ACCEPT_CHARSET = "accept-charset",
+    ACCEPT_ENCODING = "accept-encoding",
+    ACCEPT_LANGUAGE = "accept-language",
+    ACCEPT_RANGES = "accept-ranges",
+    ACCESS_CONTROL_ALLOW_CREDENTIALS = "access-control-allow-credentials",
+    ACCESS_CONTROL_ALLOW_HEADERS = "access-control-allow-headers",
+    ACCESS_CONTROL_ALLOW_METHODS = "access-control-allow-methods",
+    ACCESS_CONTROL_ALLOW_ORIGIN = "access-control-allow-origin",
+    ACCESS_CONTROL_EXPOSE_HEADERS = "access-control-expose-headers",
+    ACCESS_CONTROL_MAX_AGE = "access-control-max-age",
+    ACCESS_CONTROL_REQUEST_HEADERS = "access-control-request-headers",
+    ACCESS_CONTROL_REQUEST_METHOD = "access-control-request-method",
+    AGE = "age",
+    ALLOW = "allow",
+    ALT_SVC = "alt-svc",
+    AUTHORIZATION = "authorization",
+    CACHE_CONTROL = "cache-control",
+    CONNECTION = "connection",
+    CONTENT_DISPOSITION = "content-disposition",
+    CONTENT_ENCODING = "content-encoding",
+    CONTENT_LANGUAGE = "content-language",
+    CONTENT_LENGTH = "content-length",
+    CONTENT_LOCATION = "content-location",
+    CONTENT_RANGE = "content-range",
+    CONTENT_SECURITY_POLICY = "content-security-policy",
+    CONTENT_TYPE = "content-type",
+    COOKIE = "cookie",
+    DATE = "date",
+    ETAG = "etag",
+    EXPECT = "expect",
+    EXPIRES = "expires",
+    FORWARDED = "forwarded",
+    FROM = "from",
+    HOST = "host",
+    IF_MATCH = "if-match",
+    IF_MODIFIED_SINCE = "if-modified-since",
+    IF_NONE_MATCH = "if-none-match",
+    IF_RANGE = "if-range",
+    IF_UNMODIFIED_SINCE = "if-unmodified-since",
+    KEEP_ALIVE = "keep-alive",
+    LAST_MODIFIED = "last-modified",
+    LINK = "link",
+    LOCATION = "location",
+    MAX_FORWARDS = "max-forwards",
+    ORIGIN = "origin",
+    PRAGMA = "pragma",
+    PROXY_AUTHENTICATE = "proxy-authenticate",
+    PROXY_AUTHORIZATION = "proxy-authorization",
+    RANGE = "range",
+    REFERER = "referer",
+    REFERRER_POLICY = "referrer-policy",
+    RETRY_AFTER = "retry-after",
+    SEC_WEBSOCKET_ACCEPT = "sec-websocket-accept",
+    SEC_WEBSOCKET_EXTENSIONS = "sec-websocket-extensions",
+    SEC_WEBSOCKET_KEY = "sec-websocket-key",
+    SEC_WEBSOCKET_PROTOCOL = "sec-websocket-protocol",
+    SEC_WEBSOCKET_VERSION = "sec-websocket-version",
+    SERVER = "server",
+    SET_COOKIE = "set-cookie",
+    STRICT_TRANSPORT_SECURITY = "strict-transport-security",
+    TE = "te",
+    TRAILER = "trailer",
+    TRANSFER_ENCODING = "transfer-encoding",
+    UPGRADE = "upgrade",
+    UPGRADE_INSECURE_REQUESTS = "upgrade-insecure-requests",
+    USER_AGENT = "user-agent",
+    VARY = "vary",
+    VIA = "via",
+    WWW_AUTHENTICATE = "www-authenticate",
+    X_CONTENT_TYPE_OPTIONS = "x-content-type-options",
+    X_FRAME_OPTIONS = "x-frame-options",
+}
+
+/// Conventional casing of the header names, in the same order as [WORDS]
+const CANONICAL: [&str; WORDS.len()] = [
+    "Accept",
+    "Accept-Charset",
+    "Accept-Encoding",
+    "Accept-Language",
+    "Accept-Ranges",
+    "Access-Control-Allow-Credentials",
+    "Access-Control-Allow-Headers",
+    "Access-Control-Allow-Methods",
+    "Access-Control-Allow-Origin",
+    "Access-Control-Expose-Headers",
+    "Access-Control-Max-Age",
+    "Access-Control-Request-Headers",
+    "Access-Control-Request-Method",
+    "Age",
+    "Allow",
+    "Alt-Svc",
+    "Authorization",
+    "Cache-Control",
+    "Connection",
+    "Content-Disposition",
+    "Content-Encoding",
+    "Content-Language",
+    "Content-Length",
+    "Content-Location",
+    "Content-Range",
+    "Content-Security-Policy",
+    "Content-Type",
+    "Cookie",
+    "Date",
+    "ETag",
+    "Expect",
+    "Expires",
+    "Forwarded",
+    "From",
+    "Host",
+    "If-Match",
+    "If-Modified-Since",
+    "If-None-Match",
+    "If-Range",
+    "If-Unmodified-Since",
+    "Keep-Alive",
+    "Last-Modified",
+    "Link",
+    "Location",
+    "Max-Forwards",
+    "Origin",
+    "Pragma",
+    "Proxy-Authenticate",
+    "Proxy-Authorization",
+    "Range",
+    "Referer",
+    "Referrer-Policy",
+    "Retry-After",
+    "Sec-WebSocket-Accept",
+    "Sec-WebSocket-Extensions",
+    "Sec-WebSocket-Key",
+    "Sec-WebSocket-Protocol",
+    "Sec-WebSocket-Version",
+    "Server",
+    "Set-Cookie",
+    "Strict-Transport-Security",
+    "TE",
+    "Trailer",
+    "Transfer-Encoding",
+    "Upgrade",
+    "Upgrade-Insecure-Requests",
+    "User-Agent",
+    "Vary",
+    "Via",
+    "WWW-Authenticate",
+    "X-Content-Type-Options",
+    "X-Frame-Options",
+];
+
+/// Returns the conventional casing of a predefined header name
+pub fn canonical(sym: Symbol) -> Option<&'static str> {
+    super::index_of(sym, WORDS.len()).map(|i| CANONICAL[i])
+}
+
+/// Like [lookup], but ignores the ASCII casing of `name`
+pub fn lookup_ignore_case(name: &str) -> Option<Symbol> {
+    let i = WORDS.iter().position(|word| word.eq_ignore_ascii_case(name))?;
+    Some(BUILDER.symbol_at(i))
+}
@@ -0,0 +1,16 @@
+//! JSON literal names
+//!
+//! # Example
+//! ```
+//! use interns::presets::json;
+//!
+//! let mut interner = json::BUILDER.build();
+//! assert_eq!(interner.get_or_intern("null"), json::NULL);
+//! ```
+
+super::preset! {
+    "the JSON literal names";
+    TRUE = "true",
+    FALSE = "false",
+    NULL = "null",
+}
@@ -0,0 +1,128 @@
+//! Common MIME types
+//!
+//! # Example
+//! ```
+//! use interns::presets::mime;
+//!
+//! let mut interner = mime::BUILDER.build();
+//! let sym = mime::from_extension("json").unwrap();
+//! assert_eq!(sym, mime::APPLICATION_JSON);
+//! assert_eq!(interner.resolve(sym), Some("application/json"));
+//! assert_eq!(interner.get_or_intern("text/html"), mime::TEXT_HTML);
+//! ```
+
+super::preset! {
+    "common MIME types";
+    APPLICATION_GZIP = "application/gzip",
+    APPLICATION_JAVASCRIPT = "application/javascript",
+    APPLICATION_JSON = "application/json",
+    APPLICATION_LD_JSON = "application/ld+json",
+    APPLICATION_MSWORD = "application/msword",
+    APPLICATION_OCTET_STREAM = "application/octet-stream",
+    APPLICATION_OGG = "application/ogg",
+    APPLICATION_PDF = "application/pdf",
+    APPLICATION_RTF = "application/rtf",
+    APPLICATION_WASM = "application/wasm",
+    APPLICATION_X_7Z_COMPRESSED = "application/x-7z-compressed",
+    APPLICATION_X_BZIP2 = "application/x-bzip2",
+    APPLICATION_X_TAR = "application/x-tar",
+    APPLICATION_X_WWW_FORM_URLENCODED = "application/x-www-form-urlencoded",
+    APPLICATION_XHTML_XML = "application/xhtml+xml",
+    APPLICATION_XML = "application/xml",
+    APPLICATION_ZIP = "application/zip",
+    AUDIO_AAC = "audio/aac",
+    AUDIO_FLAC = "audio/flac",
+    AUDIO_MIDI = "audio/midi",
+    AUDIO_MPEG = "audio/mpeg",
+    AUDIO_OGG = "audio/ogg",
+    AUDIO_WAV = "audio/wav",
+    AUDIO_WEBM = "audio/webm",
+    FONT_OTF = "font/otf",
+    FONT_TTF = "font/ttf",
+    FONT_WOFF = "font/woff",
+    FONT_WOFF2 = "font/woff2",
+    IMAGE_AVIF = "image/avif",
+    IMAGE_BMP = "image/bmp",
+    IMAGE_GIF = "image/gif",
+    IMAGE_JPEG = "image/jpeg",
+    IMAGE_PNG = "image/png",
+    IMAGE_SVG_XML = "image/svg+xml",
+    IMAGE_TIFF = "image/tiff",
+    IMAGE_WEBP = "image/webp",
+    IMAGE_X_ICON = "image/x-icon",
+    MULTIPART_BYTERANGES = "multipart/byteranges",
+    MULTIPART_FORM_DATA = "multipart/form-data",
+    TEXT_CALENDAR = "text/calendar",
+    TEXT_CSS = "text/css",
+    TEXT_CSV = "text/csv",
+    TEXT_EVENT_STREAM = "text/event-stream",
+    TEXT_HTML = "text/html",
+    TEXT_JAVASCRIPT = "text/javascript",
+    TEXT_MARKDOWN = "text/markdown",
+    TEXT_PLAIN = "text/plain",
+    TEXT_XML = "text/xml",
+    VIDEO_MP4 = "video/mp4",
+    VIDEO_MPEG = "video/mpeg",
+    VIDEO_OGG = "video/ogg",
+    VIDEO_QUICKTIME = "video/quicktime",
+    VIDEO_WEBM = "video/webm",
+    VIDEO_X_MSVIDEO = "video/x-msvideo",
+}
+
+/// Returns the MIME type of files with the given extension
+///
+/// The extension is matched ignoring its ASCII casing, and without the leading dot.
+pub fn from_extension(ext: &str) -> Option<Symbol> {
+    let sym = match ext.to_ascii_lowercase().as_str() {
+        "7z" => APPLICATION_X_7Z_COMPRESSED,
+        "aac" => AUDIO_AAC,
+        "avi" => VIDEO_X_MSVIDEO,
+        "avif" => IMAGE_AVIF,
+        "bin" => APPLICATION_OCTET_STREAM,
+        "bmp" => IMAGE_BMP,
+        "bz2" => APPLICATION_X_BZIP2,
+        "css" => TEXT_CSS,
+        "csv" => TEXT_CSV,
+        "doc" => APPLICATION_MSWORD,
+        "flac" => AUDIO_FLAC,
+        "gif" => IMAGE_GIF,
+        "gz" => APPLICATION_GZIP,
+        "htm" | "html" => TEXT_HTML,
+        "ico" => IMAGE_X_ICON,
+        "ics" => TEXT_CALENDAR,
+        "jpeg" | "jpg" => IMAGE_JPEG,
+        "js" | "mjs" => TEXT_JAVASCRIPT,
+        "json" => APPLICATION_JSON,
+        "jsonld" => APPLICATION_LD_JSON,
+        "md" => TEXT_MARKDOWN,
+        "mid" | "midi" => AUDIO_MIDI,
+        "mov" => VIDEO_QUICKTIME,
+        "mp3" => AUDIO_MPEG,
+        "mp4" => VIDEO_MP4,
+        "mpeg" => VIDEO_MPEG,
+        "oga" | "opus" => AUDIO_OGG,
+        "ogg" => APPLICATION_OGG,
+        "ogv" => VIDEO_OGG,
+        "otf" => FONT_OTF,
+        "pdf" => APPLICATION_PDF,
+        "png" => IMAGE_PNG,
+        "rtf" => APPLICATION_RTF,
+        "svg" => IMAGE_SVG_XML,
+        "tar" => APPLICATION_X_TAR,
+        "tif" | "tiff" => IMAGE_TIFF,
+        "ttf" => FONT_TTF,
+        "txt" => TEXT_PLAIN,
+        "wasm" => APPLICATION_WASM,
+        "wav" => AUDIO_WAV,
+        "weba" => AUDIO_WEBM,
+        "webm" => VIDEO_WEBM,
+        "webp" => IMAGE_WEBP,
+        "woff" => FONT_WOFF,
+        "woff2" => FONT_WOFF2,
+        "xhtml" => APPLICATION_XHTML_XML,
+        "xml" => APPLICATION_XML,
+        "zip" => APPLICATION_ZIP,
+        _ => return None,
+    };
+    Some(sym)
+}
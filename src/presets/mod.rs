@@ -0,0 +1,103 @@
+//! Prefilled interners for common sets of strings
+//!
+//! Each module of this one defines, like the code generated by [codegen](crate::codegen):
+//!
+//! - A `BUILDER` constant, prefilled with all the strings of the preset
+//! - A [Symbol] constant for each string
+//! - A `lookup` function mapping a string to its predefined symbol
+//!
+//! These are only available with the `presets` feature.
+//!
+//! # Example
+//! ```
+//! use interns::presets::http;
+//!
+//! let mut interner = http::BUILDER.build();
+//! let sym = interner.get_or_intern("content-type");
+//! assert_eq!(sym, http::CONTENT_TYPE);
+//! ```
+//!
+//! There are presets for the keywords of some languages too.
+//!
+//! ```
+//! use interns::presets::rust;
+//!
+//! let mut interner = rust::BUILDER.build();
+//! let ident = interner.get_or_intern("main");
+//! assert_eq!(rust::lookup("main"), None);
+//! assert_ne!(ident, rust::KW_FN);
+//! ```
+
+use crate::backend::string::Symbol;
+
+pub mod http;
+pub mod mime;
+
+pub mod c;
+pub mod json;
+pub mod rust;
+pub mod sql;
+
+/// Returns the position of `word` on `words`
+///
+/// # Panics
+/// If `word` is not on `words`. Since this is called
+/// on constants, it fails at compile time.
+const fn position(words: &[&str], word: &str) -> usize {
+    let mut i = 0;
+    while i < words.len() {
+        if eq(words[i], word) {
+            return i;
+        }
+        i += 1;
+    }
+    panic!("Word not found on the preset");
+}
+
+const fn eq(a: &str, b: &str) -> bool {
+    let (a, b) = (a.as_bytes(), b.as_bytes());
+    if a.len() != b.len() {
+        return false;
+    }
+    let mut i = 0;
+    while i < a.len() {
+        if a[i] != b[i] {
+            return false;
+        }
+        i += 1;
+    }
+    true
+}
+
+/// Returns the position of `sym` on a preset of `n` words
+fn index_of(sym: Symbol, n: usize) -> Option<usize> {
+    let index = sym.offset as usize;
+    (!sym.is_inlined() && index < n).then_some(index)
+}
+
+/// Defines the items of a preset module
+macro_rules! preset {
+    ($what:literal; $( $name:ident = $word:literal ),* $(,)?) => {
+        use crate::backend::string::{StringInternerBuilder, Symbol};
+
+        const WORDS: &[&str] = &[$($word),*];
+
+        #[doc = concat!("Builder prefilled with ", $what)]
+        pub const BUILDER: StringInternerBuilder<{ WORDS.len() }> =
+            StringInternerBuilder::with_const_symbols([$($word),*]);
+
+        $(
+            #[doc = concat!("Symbol for `", $word, "`")]
+            pub const $name: Symbol = BUILDER.symbol_at(super::position(WORDS, $word));
+        )*
+
+        /// Returns the predefined symbol for `s`, if any
+        pub fn lookup(s: &str) -> Option<Symbol> {
+            match s {
+                $( $word => Some($name), )*
+                _ => None,
+            }
+        }
+    };
+}
+use preset;
@@ -0,0 +1,73 @@
+//! Rust keywords
+//!
+//! Includes the strict keywords of the 2021 edition, and the reserved ones.
+//!
+//! # Example
+//! ```
+//! use interns::presets::rust;
+//!
+//! let mut interner = rust::BUILDER.build();
+//! assert_eq!(interner.get_or_intern("fn"), rust::KW_FN);
+//! assert_eq!(rust::lookup("Self"), Some(rust::KW_SELF_TYPE));
+//! assert!(rust::is_reserved(rust::KW_YIELD));
+//! ```
+
+super::preset! {
+    "the Rust keywords";
+    KW_AS = "as",
+    KW_ASYNC = "async",
+    KW_AWAIT = "await",
+    KW_BREAK = "break",
+    KW_CONST = "const",
+    KW_CONTINUE = "continue",
+    KW_CRATE = "crate",
+    KW_DYN = "dyn",
+    KW_ELSE = "else",
+    KW_ENUM = "enum",
+    KW_EXTERN = "extern",
+    KW_FALSE = "false",
+    KW_FN = "fn",
+    KW_FOR = "for",
+    KW_IF = "if",
+    KW_IMPL = "impl",
+    KW_IN = "in",
+    KW_LET = "let",
+    KW_LOOP = "loop",
+    KW_MATCH = "match",
+    KW_MOD = "mod",
+    KW_MOVE = "move",
+    KW_MUT = "mut",
+    KW_PUB = "pub",
+    KW_REF = "ref",
+    KW_RETURN = "return",
+    KW_SELF = "self",
+    KW_SELF_TYPE = "Self",
+    KW_STATIC = "static",
+    KW_STRUCT = "struct",
+    KW_SUPER = "super",
+    KW_TRAIT = "trait",
+    KW_TRUE = "true",
+    KW_TYPE = "type",
+    KW_UNSAFE = "unsafe",
+    KW_USE = "use",
+    KW_WHERE = "where",
+    KW_WHILE = "while",
+    KW_ABSTRACT = "abstract",
+    KW_BECOME = "become",
+    KW_BOX = "box",
+    KW_DO = "do",
+    KW_FINAL = "final",
+    KW_MACRO = "macro",
+    KW_OVERRIDE = "override",
+    KW_PRIV = "priv",
+    KW_TRY = "try",
+    KW_TYPEOF = "typeof",
+    KW_UNSIZED = "unsized",
+    KW_VIRTUAL = "virtual",
+    KW_YIELD = "yield",
+}
+
+/// Returns true if `sym` is a keyword reserved for future use
+pub fn is_reserved(sym: Symbol) -> bool {
+    super::index_of(sym, WORDS.len()).is_some_and(|i| i >= WORDS.len() - 13)
+}
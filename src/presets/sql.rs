@@ -0,0 +1,95 @@
+//! Common SQL reserved words
+//!
+//! The words are interned in uppercase. Since SQL is case-insensitive,
+//! [lookup_ignore_case] finds them regardless of their casing.
+//!
+//! # Example
+//! ```
+//! use interns::presets::sql;
+//!
+//! let mut interner = sql::BUILDER.build();
+//! assert_eq!(interner.get_or_intern("SELECT"), sql::KW_SELECT);
+//! assert_eq!(sql::lookup_ignore_case("from"), Some(sql::KW_FROM));
+//! ```
+
+super::preset! {
+    "common SQL reserved words";
+    KW_ALL = "ALL",
+    KW_ALTER = "ALTER",
+    KW_AND = "AND",
+    KW_ANY = "ANY",
+    KW_AS = "AS",
+    KW_ASC = "ASC",
+    KW_BETWEEN = "BETWEEN",
+    KW_BY = "BY",
+    KW_CASE = "CASE",
+    KW_CAST = "CAST",
+    KW_CHECK = "CHECK",
+    KW_COLUMN = "COLUMN",
+    KW_CONSTRAINT = "CONSTRAINT",
+    KW_CREATE = "CREATE",
+    KW_CROSS = "CROSS",
+    KW_CURRENT_DATE = "CURRENT_DATE",
+    KW_CURRENT_TIME = "CURRENT_TIME",
+    KW_CURRENT_TIMESTAMP = "CURRENT_TIMESTAMP",
+    KW_DEFAULT = "DEFAULT",
+    KW_DELETE = "DELETE",
+    KW_DESC = "DESC",
+    KW_DISTINCT = "DISTINCT",
+    KW_DROP = "DROP",
+    KW_ELSE = "ELSE",
+    KW_END = "END",
+    KW_EXCEPT = "EXCEPT",
+    KW_EXISTS = "EXISTS",
+    KW_FALSE = "FALSE",
+    KW_FETCH = "FETCH",
+    KW_FOREIGN = "FOREIGN",
+    KW_FROM = "FROM",
+    KW_FULL = "FULL",
+    KW_GRANT = "GRANT",
+    KW_GROUP = "GROUP",
+    KW_HAVING = "HAVING",
+    KW_IN = "IN",
+    KW_INNER = "INNER",
+    KW_INSERT = "INSERT",
+    KW_INTERSECT = "INTERSECT",
+    KW_INTO = "INTO",
+    KW_IS = "IS",
+    KW_JOIN = "JOIN",
+    KW_LEFT = "LEFT",
+    KW_LIKE = "LIKE",
+    KW_NATURAL = "NATURAL",
+    KW_NOT = "NOT",
+    KW_NULL = "NULL",
+    KW_OFFSET = "OFFSET",
+    KW_ON = "ON",
+    KW_OR = "OR",
+    KW_ORDER = "ORDER",
+    KW_OUTER = "OUTER",
+    KW_PRIMARY = "PRIMARY",
+    KW_REFERENCES = "REFERENCES",
+    KW_REVOKE = "REVOKE",
+    KW_RIGHT = "RIGHT",
+    KW_ROLLBACK = "ROLLBACK",
+    KW_SELECT = "SELECT",
+    KW_SET = "SET",
+    KW_SOME = "SOME",
+    KW_TABLE = "TABLE",
+    KW_THEN = "THEN",
+    KW_TRUE = "TRUE",
+    KW_UNION = "UNION",
+    KW_UNIQUE = "UNIQUE",
+    KW_UPDATE = "UPDATE",
+    KW_USING = "USING",
+    KW_VALUES = "VALUES",
+    KW_VIEW = "VIEW",
+    KW_WHEN = "WHEN",
+    KW_WHERE = "WHERE",
+    KW_WITH = "WITH",
+}
+
+/// Like [lookup], but ignores the ASCII casing of `word`
+pub fn lookup_ignore_case(word: &str) -> Option<Symbol> {
+    let i = WORDS.iter().position(|w| w.eq_ignore_ascii_case(word))?;
+    Some(BUILDER.symbol_at(i))
+}
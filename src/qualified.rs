@@ -0,0 +1,189 @@
+//! Interning of qualified paths
+//!
+//! A qualified path, like `std::vec::Vec`, is a sequence of segments.
+//! A [PathSymbolInterner] stores each path as a pair of its parent path
+//! and its last segment, and the segments on a [StringInterner](crate::StringInterner).
+//! Paths that share a prefix share its entries, and the parent of a path
+//! is one lookup away.
+//!
+//! # Example
+//! ```
+//! use interns::qualified::PathSymbolInterner;
+//!
+//! let mut interner = PathSymbolInterner::new();
+//! let vec = interner.intern_path(["std", "vec", "Vec"]).unwrap();
+//! let new = interner.intern_child(Some(vec), "new");
+//!
+//! assert_eq!(interner.parent(new), Some(vec));
+//! assert_eq!(interner.segment(new), Some("new"));
+//! assert_eq!(interner.get_path(["std", "vec", "Vec", "new"]), Some(new));
+//! assert_eq!(interner.display(new).to_string(), "std::vec::Vec::new");
+//!
+//! /* "std" and "std::vec" are shared */
+//! assert_eq!(interner.len(), 4);
+//! ```
+
+use core::fmt;
+use core::hash::BuildHasher;
+
+use crate::backend::string::Symbol;
+use crate::composite::{PairInterner, PairSymbol};
+use crate::hash::DefaultHashBuilder;
+use crate::{Interner, StringBackend};
+
+/// Symbol of the paths of a [PathSymbolInterner]
+pub type QualifiedSymbol = PairSymbol;
+
+/// Interner for qualified paths
+///
+/// See the [module-level documentation](self) for more details.
+pub struct PathSymbolInterner<H: BuildHasher = DefaultHashBuilder> {
+    segments: Interner<str, StringBackend, H>,
+    paths: PairInterner<Option<QualifiedSymbol>, Symbol, H>,
+}
+
+impl PathSymbolInterner {
+    /// Creates an empty interner
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl<H: BuildHasher + Default> Default for PathSymbolInterner<H> {
+    fn default() -> Self {
+        Self { segments: Interner::with_hasher(H::default()), paths: Interner::with_hasher(H::default()) }
+    }
+}
+
+impl<H: BuildHasher> PathSymbolInterner<H> {
+    /// Gets the symbol of `segment` under `parent`, interning it if it doesn't exist
+    ///
+    /// The paths without a parent are the roots.
+    pub fn intern_child(&mut self, parent: Option<QualifiedSymbol>, segment: &str) -> QualifiedSymbol {
+        let segment = self.segments.get_or_intern(segment);
+        self.paths.get_or_intern_pair(parent, segment)
+    }
+
+    /// Gets the symbol of the path made of `segments`, interning it if it doesn't exist
+    ///
+    /// Every prefix of the path is interned too. Returns None if `segments` is empty.
+    pub fn intern_path<I>(&mut self, segments: I) -> Option<QualifiedSymbol>
+    where
+        I: IntoIterator,
+        I::Item: AsRef<str>,
+    {
+        segments
+            .into_iter()
+            .fold(None, |parent, segment| Some(self.intern_child(parent, segment.as_ref())))
+    }
+
+    /// Gets the symbol of `segment` under `parent`, without interning it
+    pub fn get_child(&self, parent: Option<QualifiedSymbol>, segment: &str) -> Option<QualifiedSymbol> {
+        let segment = self.segments.get(segment)?;
+        self.paths.get_pair(parent, segment)
+    }
+
+    /// Gets the symbol of the path made of `segments`, without interning it
+    ///
+    /// Returns None if `segments` is empty, or the path hasn't been interned.
+    pub fn get_path<I>(&self, segments: I) -> Option<QualifiedSymbol>
+    where
+        I: IntoIterator,
+        I::Item: AsRef<str>,
+    {
+        let mut parent = None;
+        for segment in segments {
+            parent = Some(self.get_child(parent, segment.as_ref())?);
+        }
+        parent
+    }
+
+    /// Returns the parent of the path
+    ///
+    /// Returns None if the path is a root, or the symbol doesn't resolve.
+    pub fn parent(&self, sym: QualifiedSymbol) -> Option<QualifiedSymbol> {
+        self.paths.resolve_pair(sym)?.0
+    }
+
+    /// Returns the last segment of the path
+    pub fn segment(&self, sym: QualifiedSymbol) -> Option<&str> {
+        let (_, segment) = self.paths.resolve_pair(sym)?;
+        self.segments.resolve(segment)
+    }
+
+    /// Iterates over the path and its ancestors, up to its root
+    pub fn ancestors(&self, sym: QualifiedSymbol) -> impl Iterator<Item = QualifiedSymbol> + '_ {
+        let first = self.paths.resolve(sym).map(|_| sym);
+        core::iter::successors(first, |&sym| self.parent(sym))
+    }
+
+    /// Returns the segments of the path, from its root
+    ///
+    /// Returns None if the symbol doesn't resolve.
+    pub fn segments(&self, sym: QualifiedSymbol) -> Option<Vec<&str>> {
+        let mut segments = self.ancestors(sym).map(|sym| self.segment(sym)).collect::<Option<Vec<_>>>()?;
+        if segments.is_empty() {
+            return None
+        }
+        segments.reverse();
+        Some(segments)
+    }
+
+    /// Returns true if `ancestor` is `sym`, or one of its ancestors
+    pub fn starts_with(&self, sym: QualifiedSymbol, ancestor: QualifiedSymbol) -> bool {
+        self.ancestors(sym).any(|sym| sym == ancestor)
+    }
+
+    /// Displays the path, with its segments separated by `::`
+    ///
+    /// See [display_with](Self::display_with)
+    pub fn display(&self, sym: QualifiedSymbol) -> PathDisplay<'_, H> {
+        self.display_with(sym, "::")
+    }
+
+    /// Displays the path, with its segments separated by `separator`
+    ///
+    /// A path that doesn't resolve is displayed as `<unknown>`.
+    pub fn display_with<'a>(&'a self, sym: QualifiedSymbol, separator: &'a str) -> PathDisplay<'a, H> {
+        PathDisplay { interner: self, sym, separator }
+    }
+
+    /// Returns the interner of the segments
+    pub fn segment_interner(&self) -> &Interner<str, StringBackend, H> {
+        &self.segments
+    }
+
+    /// Returns the number of unique paths, counting every prefix
+    pub fn len(&self) -> usize {
+        self.paths.len()
+    }
+
+    /// Returns true if nothing has been interned yet
+    pub fn is_empty(&self) -> bool {
+        self.paths.is_empty()
+    }
+}
+
+/// Displays a qualified path
+///
+/// Returned by [PathSymbolInterner::display]
+pub struct PathDisplay<'a, H: BuildHasher> {
+    interner: &'a PathSymbolInterner<H>,
+    sym: QualifiedSymbol,
+    separator: &'a str,
+}
+
+impl<H: BuildHasher> fmt::Display for PathDisplay<'_, H> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Some(segments) = self.interner.segments(self.sym) else {
+            return f.write_str("<unknown>")
+        };
+        for (i, segment) in segments.iter().enumerate() {
+            if i > 0 {
+                f.write_str(self.separator)?;
+            }
+            f.write_str(segment)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,205 @@
+//! Bulk interning from readers and iterators
+
+use core::hash::BuildHasher;
+use std::collections::HashMap;
+use std::io::{self, BufRead};
+
+use crate::backend::Internable;
+use crate::{Backend, Interner, PrefillError};
+
+impl<B, H> Interner<str, B, H>
+where
+    B: Backend<str> + Default,
+    H: BuildHasher + Default,
+    str: Internable<str, B>,
+{
+    /// Builds an interner with every line read from `reader`
+    ///
+    /// Line terminators (`\n` or `\r\n`) are not part of the interned
+    /// strings, and empty lines are skipped.
+    ///
+    /// Space is reserved upfront for the lines already in the reader's
+    /// buffer (see [BufRead::fill_buf]). For in-memory readers, like a
+    /// `&[u8]` or a [Cursor](io::Cursor), that's every line.
+    ///
+    /// # Example
+    /// ```
+    /// use interns::StringInterner;
+    ///
+    /// let words = "apple\nbanana\n\napple\r\ncherry\n";
+    /// let interner = StringInterner::from_reader(words.as_bytes()).unwrap();
+    /// assert_eq!(interner.len(), 3);
+    /// assert!(interner.contains("banana"));
+    /// ```
+    pub fn from_reader<R: BufRead>(mut reader: R) -> io::Result<Self> {
+        let buffered = reader.fill_buf()?;
+        let lines = buffered.iter().filter(|&&b| b == b'\n').count()
+            + usize::from(buffered.last().is_some_and(|&b| b != b'\n'));
+        let mut interner = Self::with_capacity(lines);
+        for_each_line(reader, |line| {
+            if !line.is_empty() {
+                interner.get_or_intern(line);
+            }
+        })?;
+        Ok(interner)
+    }
+
+    /// Builds an interner with every string yielded by `lines`
+    ///
+    /// Space for the elements is reserved upfront, based on the
+    /// iterator's [size_hint](Iterator::size_hint)
+    ///
+    /// # Example
+    /// ```
+    /// use interns::StringInterner;
+    ///
+    /// let interner = StringInterner::from_lines(["a", "b", "a"]);
+    /// assert_eq!(interner.len(), 2);
+    /// ```
+    pub fn from_lines<I>(lines: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: AsRef<str>,
+    {
+        let lines = lines.into_iter();
+        let mut interner = Self::with_capacity(lines.size_hint().0);
+        for line in lines {
+            interner.get_or_intern(line.as_ref());
+        }
+        interner
+    }
+}
+
+impl<B, H> Interner<str, B, H>
+where
+    B: Backend<str>,
+    H: BuildHasher,
+    str: Internable<str, B>,
+{
+    /// Interns every whitespace-separated word of `text`
+    ///
+    /// Returns the symbols of the words, in order.
+    /// Words are split like [str::split_whitespace].
+    ///
+    /// # Example
+    /// ```
+    /// use interns::StringInterner;
+    ///
+    /// let mut interner = StringInterner::new();
+    /// let words = interner.intern_split_whitespace("to be  or not to be");
+    /// assert_eq!(words.len(), 6);
+    /// assert_eq!(words[0], words[4]);
+    /// assert_eq!(interner.len(), 4);
+    /// ```
+    pub fn intern_split_whitespace(&mut self, text: &str) -> Vec<B::Symbol> {
+        text.split_whitespace().map(|word| self.get_or_intern(word)).collect()
+    }
+
+    /// Interns every substring of `text` separated by the
+    /// characters that match `sep`
+    ///
+    /// Returns the symbols of the substrings, in order.
+    /// Substrings are split like [str::split], so
+    /// consecutive separators produce empty strings.
+    ///
+    /// # Example
+    /// ```
+    /// use interns::StringInterner;
+    ///
+    /// let mut interner = StringInterner::new();
+    /// let fields = interner.intern_split("GET,/index,,GET", |c| c == ',');
+    /// assert_eq!(fields.len(), 4);
+    /// assert_eq!(interner.resolve(fields[2]), Some(""));
+    /// assert_eq!(fields[0], fields[3]);
+    /// ```
+    pub fn intern_split(&mut self, text: &str, sep: impl FnMut(char) -> bool) -> Vec<B::Symbol> {
+        text.split(sep).map(|field| self.get_or_intern(field)).collect()
+    }
+
+    /// Interns every line read from `reader`
+    ///
+    /// Returns the symbols of the lines, in order. Unlike [from_reader](Self::from_reader),
+    /// empty lines are interned too, so the n-th symbol corresponds to the n-th line.
+    /// Line terminators (`\n` or `\r\n`) are not part of the interned strings.
+    ///
+    /// The lines are read into a single buffer, which is reused for every line.
+    ///
+    /// # Example
+    /// ```
+    /// use interns::StringInterner;
+    ///
+    /// let log = "GET /\nPOST /login\nGET /\n";
+    /// let mut interner = StringInterner::new();
+    /// let lines = interner.intern_lines(log.as_bytes()).unwrap();
+    /// assert_eq!(lines.len(), 3);
+    /// assert_eq!(lines[0], lines[2]);
+    /// assert_eq!(interner.len(), 2);
+    /// ```
+    pub fn intern_lines<R: BufRead>(&mut self, reader: R) -> io::Result<Vec<B::Symbol>> {
+        let mut syms = Vec::new();
+        for_each_line(reader, |line| syms.push(self.get_or_intern(line)))?;
+        Ok(syms)
+    }
+
+    /// Interns every string of `strings`, and returns their symbols, in order
+    ///
+    /// This is meant to reserve well-known symbols at startup, when the
+    /// list is only known at runtime (e.g. it comes from a configuration
+    /// file). For lists known at compile time, see
+    /// [StringInternerBuilder](crate::backend::string::StringInternerBuilder).
+    ///
+    /// The interner doesn't need to be empty. Strings that were already
+    /// interned keep their symbol.
+    ///
+    /// # Errors
+    /// - [PrefillError::Duplicate] if a string appears more than once on the list.
+    ///   Nothing is interned in that case.
+    /// - [PrefillError::Intern] if a string can't be interned. The
+    ///   strings before it stay on the interner.
+    ///
+    /// # Example
+    /// ```
+    /// use interns::{PrefillError, StringInterner};
+    ///
+    /// let mut interner = StringInterner::new();
+    /// let config = ["GET", "POST", "PUT"];
+    /// let methods = interner.prefill(&config).unwrap();
+    /// assert_eq!(interner.get("POST"), Some(methods[1]));
+    ///
+    /// let err = interner.prefill(&["HEAD", "GET", "HEAD"]).unwrap_err();
+    /// assert!(matches!(err, PrefillError::Duplicate { first: 0, index: 2, .. }));
+    /// assert!(!interner.contains("HEAD"));
+    /// ```
+    pub fn prefill(&mut self, strings: &[&str]) -> Result<Vec<B::Symbol>, PrefillError> {
+        let mut seen = HashMap::with_capacity(strings.len());
+        for (index, string) in strings.iter().enumerate() {
+            if let Some(&first) = seen.get(string) {
+                return Err(PrefillError::Duplicate { string: string.to_string(), first, index })
+            }
+            seen.insert(string, index);
+        }
+        let mut syms = Vec::with_capacity(strings.len());
+        for string in strings {
+            syms.push(self.try_get_or_intern(*string)?);
+        }
+        Ok(syms)
+    }
+}
+
+/// Calls `f` with every line of `reader`, without the line terminator
+fn for_each_line<R: BufRead>(mut reader: R, mut f: impl FnMut(&str)) -> io::Result<()> {
+    let mut line = String::new();
+    loop {
+        line.clear();
+        if reader.read_line(&mut line)? == 0 {
+            return Ok(())
+        }
+        f(trim_newline(&line));
+    }
+}
+
+/// Removes a trailing `\n` or `\r\n`
+pub(crate) fn trim_newline(line: &str) -> &str {
+    let line = line.strip_suffix('\n').unwrap_or(line);
+    line.strip_suffix('\r').unwrap_or(line)
+}
@@ -0,0 +1,245 @@
+//! Symbol remapping
+//!
+//! This module also has helpers for code that consumes symbols
+//! from several independent interners, like [cross_eq] and [translate].
+
+use std::collections::HashMap;
+use std::hash::{BuildHasher, Hash};
+
+use crate::backend::Internable;
+use crate::{Backend, Interner};
+
+/// Maps symbols from an old assignment to a new one
+///
+/// This is returned by operations that change the symbols
+/// of interned values, or move them to another interner.
+/// The new symbols can be of a different type `N`, if they
+/// belong to an interner with another backend.
+#[derive(Clone, Debug)]
+pub struct RemapTable<S, N = S> {
+    map: HashMap<S, N>,
+}
+
+impl<S, N> Default for RemapTable<S, N> {
+    fn default() -> Self {
+        Self { map: HashMap::new() }
+    }
+}
+
+impl<S: Copy + Hash + Eq, N: Copy> RemapTable<S, N> {
+    pub(crate) fn with_capacity(capacity: usize) -> Self {
+        Self { map: HashMap::with_capacity(capacity) }
+    }
+
+    pub(crate) fn insert(&mut self, old: S, new: N) {
+        self.map.insert(old, new);
+    }
+
+    /// Returns the new symbol for `old`
+    pub fn get(&self, old: S) -> Option<N> {
+        self.map.get(&old).copied()
+    }
+
+    /// Returns the number of symbols in the table
+    pub fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    /// Returns true if the table is empty
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+
+    /// Iterates over the (old, new) pairs of the table
+    pub fn iter(&self) -> impl Iterator<Item = (S, N)> + '_ {
+        self.map.iter().map(|(old, new)| (*old, *new))
+    }
+}
+
+impl<S: Copy + Hash + Eq> RemapTable<S> {
+    /// Returns true if every symbol is mapped to itself
+    pub fn is_identity(&self) -> bool {
+        self.map.iter().all(|(old, new)| old == new)
+    }
+}
+
+/// Returns true if `a_sym` on `a` resolves to the same value as `b_sym` on `b`
+///
+/// Returns false if any of the symbols can't be resolved.
+///
+/// # Example
+/// ```
+/// use interns::StringInterner;
+/// use interns::remap::cross_eq;
+///
+/// let mut old = StringInterner::new();
+/// let mut new = StringInterner::new();
+/// new.get_or_intern("padding");
+/// let a = old.get_or_intern("main");
+/// let b = new.get_or_intern("main");
+///
+/// assert_ne!(a, b);
+/// assert!(cross_eq(a, &old, b, &new));
+/// ```
+pub fn cross_eq<T, BA, HA, BB, HB>(
+    a_sym: BA::Symbol,
+    a: &Interner<T, BA, HA>,
+    b_sym: BB::Symbol,
+    b: &Interner<T, BB, HB>,
+) -> bool
+where
+    T: Hash + Eq + ?Sized,
+    BA: Backend<T>,
+    BB: Backend<T>,
+    HA: BuildHasher,
+    HB: BuildHasher,
+{
+    match (a.resolve(a_sym), b.resolve(b_sym)) {
+        (Some(x), Some(y)) => x == y,
+        _ => false,
+    }
+}
+
+/// Returns true if both sequences of symbols resolve to the same values
+///
+/// This is the [cross_eq] of every pair of symbols. The sequences
+/// must have the same length.
+///
+/// # Example
+/// ```
+/// use interns::StringInterner;
+/// use interns::remap::cross_eq_all;
+///
+/// let mut old = StringInterner::new();
+/// let mut new = StringInterner::new();
+/// let a: Vec<_> = ["let", "x", "=", "1"].iter().map(|s| old.get_or_intern(s)).collect();
+/// let b: Vec<_> = ["let", "x", "=", "1"].iter().rev().map(|s| new.get_or_intern(s)).rev().collect();
+///
+/// assert!(cross_eq_all(&a, &old, &b, &new));
+/// assert!(!cross_eq_all(&a[1..], &old, &b[..3], &new));
+/// ```
+pub fn cross_eq_all<T, BA, HA, BB, HB>(
+    a_syms: &[BA::Symbol],
+    a: &Interner<T, BA, HA>,
+    b_syms: &[BB::Symbol],
+    b: &Interner<T, BB, HB>,
+) -> bool
+where
+    T: Hash + Eq + ?Sized,
+    BA: Backend<T>,
+    BB: Backend<T>,
+    HA: BuildHasher,
+    HB: BuildHasher,
+{
+    a_syms.len() == b_syms.len()
+        && a_syms.iter().zip(b_syms).all(|(&x, &y)| cross_eq(x, a, y, b))
+}
+
+/// Resolves `sym` on `from`, and interns its value on `to`
+///
+/// Returns None if `sym` can't be resolved. To translate many
+/// symbols, a [Translator] avoids resolving the same ones again.
+///
+/// # Example
+/// ```
+/// use interns::StringInterner;
+/// use interns::remap::translate;
+///
+/// let mut from = StringInterner::new();
+/// let mut to = StringInterner::new();
+/// let sym = from.get_or_intern("token");
+///
+/// let new = translate(sym, &from, &mut to).unwrap();
+/// assert_eq!(to.resolve(new), Some("token"));
+/// ```
+pub fn translate<T, BA, HA, BB, HB>(
+    sym: BA::Symbol,
+    from: &Interner<T, BA, HA>,
+    to: &mut Interner<T, BB, HB>,
+) -> Option<BB::Symbol>
+where
+    T: Hash + Eq + ?Sized + Internable<T, BB>,
+    BA: Backend<T>,
+    BB: Backend<T>,
+    HA: BuildHasher,
+    HB: BuildHasher,
+{
+    from.resolve(sym).map(|value| to.get_or_intern(value))
+}
+
+/// Memoizing [translate] for streams of symbols
+///
+/// # Example
+/// ```
+/// use interns::StringInterner;
+/// use interns::remap::Translator;
+///
+/// let mut lexer = StringInterner::new();
+/// let tokens: Vec<_> = "a = b + a".split(' ').map(|t| lexer.get_or_intern(t)).collect();
+///
+/// let mut parser = StringInterner::new();
+/// let mut translator = Translator::new(&lexer, &mut parser);
+/// let translated = translator.translate_all(tokens.iter().copied()).unwrap();
+/// assert_eq!(translator.remap().len(), 4);
+///
+/// assert_eq!(translated[0], translated[4]);
+/// assert_eq!(parser.resolve(translated[2]), Some("b"));
+/// ```
+pub struct Translator<'a, T, BA, HA, BB, HB>
+where
+    T: Hash + Eq + ?Sized,
+    BA: Backend<T>,
+    BB: Backend<T>,
+    HA: BuildHasher,
+    HB: BuildHasher,
+{
+    from: &'a Interner<T, BA, HA>,
+    to: &'a mut Interner<T, BB, HB>,
+    remap: RemapTable<BA::Symbol, BB::Symbol>,
+}
+
+impl<'a, T, BA, HA, BB, HB> Translator<'a, T, BA, HA, BB, HB>
+where
+    T: Hash + Eq + ?Sized + Internable<T, BB>,
+    BA: Backend<T>,
+    BB: Backend<T>,
+    HA: BuildHasher,
+    HB: BuildHasher,
+{
+    /// Creates a translator of symbols of `from` into symbols of `to`
+    pub fn new(from: &'a Interner<T, BA, HA>, to: &'a mut Interner<T, BB, HB>) -> Self {
+        Self { from, to, remap: RemapTable::default() }
+    }
+
+    /// Translates `sym`
+    ///
+    /// Returns None if `sym` can't be resolved on the source interner.
+    pub fn translate(&mut self, sym: BA::Symbol) -> Option<BB::Symbol> {
+        if let Some(new) = self.remap.get(sym) {
+            return Some(new)
+        }
+        let new = translate(sym, self.from, self.to)?;
+        self.remap.insert(sym, new);
+        Some(new)
+    }
+
+    /// Translates every symbol of `syms`
+    ///
+    /// Returns None if any of them can't be resolved on the source interner.
+    pub fn translate_all<I>(&mut self, syms: I) -> Option<Vec<BB::Symbol>>
+    where
+        I: IntoIterator<Item = BA::Symbol>,
+    {
+        syms.into_iter().map(|sym| self.translate(sym)).collect()
+    }
+
+    /// Returns the translations made so far
+    pub fn remap(&self) -> &RemapTable<BA::Symbol, BB::Symbol> {
+        &self.remap
+    }
+
+    /// Returns the translations made so far, consuming the translator
+    pub fn into_remap(self) -> RemapTable<BA::Symbol, BB::Symbol> {
+        self.remap
+    }
+}
@@ -0,0 +1,246 @@
+//! Abstraction over everything that can resolve symbols
+//!
+//! The [Resolve] trait is implemented by the [Interner], and by the other
+//! interner types of this crate. It's also implemented by references and
+//! smart pointers to them, including the guards of [Mutex](std::sync::Mutex)
+//! and [RwLock](std::sync::RwLock).
+//! This allows library code to accept "anything that can resolve symbols".
+//!
+//! # Example
+//! ```
+//! use std::sync::{Arc, RwLock};
+//! use interns::{Resolve, StringInterner};
+//! use interns::backend::string::Symbol;
+//!
+//! fn describe(names: &dyn Resolve<str, Symbol = Symbol>, sym: Symbol) -> String {
+//!     format!("`{}`", names.resolve(sym).unwrap_or("?"))
+//! }
+//!
+//! let mut interner = StringInterner::new();
+//! let main = interner.get_or_intern("main");
+//! assert_eq!(describe(&interner, main), "`main`");
+//!
+//! let shared = Arc::new(RwLock::new(interner));
+//! assert_eq!(describe(&shared.read().unwrap(), main), "`main`");
+//!
+//! let frozen = Arc::into_inner(shared).unwrap().into_inner().unwrap().freeze();
+//! assert_eq!(describe(&frozen, main), "`main`");
+//! ```
+//!
+//! A [MappedResolver] transforms the values as they are resolved,
+//! without modifying the stored ones.
+
+use core::hash::{BuildHasher, Hash};
+use std::borrow::Cow;
+use std::rc::Rc;
+use std::sync::{Arc, MutexGuard, RwLockReadGuard, RwLockWriteGuard};
+
+use crate::frozen::{FrozenInterner, MphInterner};
+use crate::layered::{LayeredInterner, LayeredSymbol};
+use crate::persistent::{PersistentInterner, PersistentSymbol};
+use crate::backend::BackendSymbol;
+use crate::{Backend, Interner};
+
+/// Something that can resolve symbols into values of `T`, and look them up
+///
+/// This trait is object safe. See the [module-level documentation](self).
+pub trait Resolve<T: ?Sized> {
+    /// Type of the symbols
+    type Symbol: BackendSymbol;
+
+    /// Resolves the symbol
+    fn resolve(&self, sym: Self::Symbol) -> Option<&T>;
+
+    /// Gets the symbol for `value`, if it's known
+    fn get(&self, value: &T) -> Option<Self::Symbol>;
+
+    /// Returns true if `value` is known
+    fn contains(&self, value: &T) -> bool {
+        self.get(value).is_some()
+    }
+}
+
+impl<T, B, H> Resolve<T> for Interner<T, B, H>
+where
+    T: Hash + Eq + ?Sized,
+    B: Backend<T>,
+    H: BuildHasher,
+{
+    type Symbol = B::Symbol;
+
+    fn resolve(&self, sym: B::Symbol) -> Option<&T> {
+        Interner::resolve(self, sym)
+    }
+
+    fn get(&self, value: &T) -> Option<B::Symbol> {
+        Interner::get(self, value)
+    }
+}
+
+impl<T, B> Resolve<T> for FrozenInterner<T, B>
+where
+    T: Ord + ?Sized,
+    B: Backend<T>,
+{
+    type Symbol = B::Symbol;
+
+    fn resolve(&self, sym: B::Symbol) -> Option<&T> {
+        FrozenInterner::resolve(self, sym)
+    }
+
+    fn get(&self, value: &T) -> Option<B::Symbol> {
+        FrozenInterner::get(self, value)
+    }
+}
+
+impl<T, B, H> Resolve<T> for MphInterner<T, B, H>
+where
+    T: Hash + Eq + ?Sized,
+    B: Backend<T>,
+    H: BuildHasher,
+{
+    type Symbol = B::Symbol;
+
+    fn resolve(&self, sym: B::Symbol) -> Option<&T> {
+        MphInterner::resolve(self, sym)
+    }
+
+    fn get(&self, value: &T) -> Option<B::Symbol> {
+        MphInterner::get(self, value)
+    }
+}
+
+impl<T, B, H> Resolve<T> for LayeredInterner<T, B, H>
+where
+    T: Hash + Eq + ?Sized,
+    B: Backend<T>,
+    H: BuildHasher,
+{
+    type Symbol = LayeredSymbol<B::Symbol>;
+
+    fn resolve(&self, sym: Self::Symbol) -> Option<&T> {
+        LayeredInterner::resolve(self, sym)
+    }
+
+    fn get(&self, value: &T) -> Option<Self::Symbol> {
+        LayeredInterner::get(self, value)
+    }
+}
+
+impl<T, H> Resolve<T> for PersistentInterner<T, H>
+where
+    T: Hash + Eq + ?Sized,
+    H: BuildHasher + Clone,
+{
+    type Symbol = PersistentSymbol;
+
+    fn resolve(&self, sym: PersistentSymbol) -> Option<&T> {
+        PersistentInterner::resolve(self, sym)
+    }
+
+    fn get(&self, value: &T) -> Option<PersistentSymbol> {
+        PersistentInterner::get(self, value)
+    }
+}
+
+/// Implements [Resolve] for a pointer-like type, by dereferencing it
+macro_rules! impl_deref {
+    ($($ty:ty),* $(,)?) => {
+        $(
+            impl<T: ?Sized, R: Resolve<T> + ?Sized> Resolve<T> for $ty {
+                type Symbol = R::Symbol;
+
+                fn resolve(&self, sym: R::Symbol) -> Option<&T> {
+                    (**self).resolve(sym)
+                }
+
+                fn get(&self, value: &T) -> Option<R::Symbol> {
+                    (**self).get(value)
+                }
+            }
+        )*
+    };
+}
+
+impl_deref! {
+    &R,
+    &mut R,
+    Box<R>,
+    Rc<R>,
+    Arc<R>,
+    MutexGuard<'_, R>,
+    RwLockReadGuard<'_, R>,
+    RwLockWriteGuard<'_, R>,
+}
+
+/// Adapter that transforms the values of a [Resolve] implementation
+///
+/// The transformation is applied on every [resolve](Self::resolve), so it
+/// can be used to present the values differently (e.g. demangled, without
+/// a prefix, or with secrets redacted) without changing what's stored.
+///
+/// # Example
+/// ```
+/// use std::borrow::Cow;
+/// use interns::StringInterner;
+/// use interns::resolve::MappedResolver;
+///
+/// let mut interner = StringInterner::new();
+/// let user = interner.get_or_intern("user");
+/// let token = interner.get_or_intern("secret:abcd1234");
+///
+/// let redacted = MappedResolver::new(&interner, |s: &str| match s.starts_with("secret:") {
+///     true => Cow::Borrowed("secret:***"),
+///     false => Cow::Borrowed(s),
+/// });
+/// assert_eq!(redacted.resolve(user).as_deref(), Some("user"));
+/// assert_eq!(redacted.resolve(token).as_deref(), Some("secret:***"));
+/// assert_eq!(interner.resolve(token), Some("secret:abcd1234"));
+/// ```
+pub struct MappedResolver<R, F> {
+    inner: R,
+    map: F,
+}
+
+impl<R, F> MappedResolver<R, F> {
+    /// Wraps `inner`, transforming its values with `map`
+    pub fn new<T>(inner: R, map: F) -> Self
+    where
+        R: Resolve<T>,
+        T: ToOwned + ?Sized,
+        F: Fn(&T) -> Cow<'_, T>,
+    {
+        Self { inner, map }
+    }
+
+    /// Resolves the symbol, and transforms its value
+    pub fn resolve<T>(&self, sym: R::Symbol) -> Option<Cow<'_, T>>
+    where
+        R: Resolve<T>,
+        T: ToOwned + ?Sized,
+        F: Fn(&T) -> Cow<'_, T>,
+    {
+        self.inner.resolve(sym).map(&self.map)
+    }
+
+    /// Gets the symbol for `value`
+    ///
+    /// The lookup is done with the stored values, not the transformed ones.
+    pub fn get<T>(&self, value: &T) -> Option<R::Symbol>
+    where
+        R: Resolve<T>,
+        T: ?Sized,
+    {
+        self.inner.get(value)
+    }
+
+    /// Returns the wrapped resolver
+    pub fn inner(&self) -> &R {
+        &self.inner
+    }
+
+    /// Returns the wrapped resolver
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+}
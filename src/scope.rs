@@ -0,0 +1,165 @@
+//! Scoped "current interner"
+//!
+//! Some code needs to resolve or intern symbols, but can't receive the
+//! interner as a parameter (e.g. [serde](crate::serde_symbol) field adapters).
+//!
+//! [with_resolver] and [with_interner] make an interner available to
+//! such code, for the duration of a closure, on the current thread.
+//!
+//! # Example
+//! ```
+//! use interns::{scope, StringInterner};
+//!
+//! let mut interner = StringInterner::new();
+//! let sym = interner.get_or_intern("hello");
+//!
+//! let len = scope::with_resolver(&interner, || {
+//!     scope::resolve(sym, |s| s.map(str::len))
+//! });
+//! assert_eq!(len, Some(5));
+//! ```
+//!
+//! Inside a scope, [Symbol]s also implement [Display](fmt::Display), which
+//! allows formatting them deep inside error-reporting code.
+//!
+//! ```
+//! use interns::{scope, StringInterner};
+//!
+//! let mut interner = StringInterner::new();
+//! let sym = interner.get_or_intern("foo");
+//!
+//! let msg = scope::with_resolver(&interner, || format!("undefined variable `{sym}`"));
+//! assert_eq!(msg, "undefined variable `foo`");
+//! ```
+//!
+//! The scope is set with a closure instead of returning a guard. A guard
+//! could be leaked with [mem::forget](core::mem::forget), leaving a
+//! dangling reference to the interner behind.
+
+use core::cell::Cell;
+use core::fmt;
+use core::hash::BuildHasher;
+
+use crate::backend::string::Symbol;
+use crate::{Interner, StringBackend};
+
+/// Type-erased interface to the current interner
+trait Scoped {
+    fn resolve(&self, sym: Symbol) -> Option<&str>;
+    fn get(&self, s: &str) -> Option<Symbol>;
+    fn intern(&mut self, s: &str) -> Symbol;
+}
+
+impl<H: BuildHasher> Scoped for Interner<str, StringBackend, H> {
+    fn resolve(&self, sym: Symbol) -> Option<&str> {
+        Interner::resolve(self, sym)
+    }
+
+    fn get(&self, s: &str) -> Option<Symbol> {
+        Interner::get(self, s)
+    }
+
+    fn intern(&mut self, s: &str) -> Symbol {
+        Interner::get_or_intern(self, s)
+    }
+}
+
+#[derive(Clone, Copy)]
+struct Current {
+    ptr: *mut dyn Scoped,
+    mutable: bool,
+}
+
+thread_local! {
+    static CURRENT: Cell<Option<Current>> = const { Cell::new(None) };
+}
+
+/// Restores the previous scope when dropped, even on panic
+struct Restore(Option<Current>);
+
+impl Drop for Restore {
+    fn drop(&mut self) {
+        CURRENT.set(self.0);
+    }
+}
+
+fn enter<R>(current: Current, f: impl FnOnce() -> R) -> R {
+    let _restore = Restore(CURRENT.replace(Some(current)));
+    f()
+}
+
+/// Runs `f` with `interner` as the current resolver
+///
+/// Inside `f`, symbols can be [resolved](resolve), but not [interned](intern).
+pub fn with_resolver<H, R>(interner: &Interner<str, StringBackend, H>, f: impl FnOnce() -> R) -> R
+where
+    H: BuildHasher + 'static,
+{
+    let ptr = interner as *const dyn Scoped as *mut dyn Scoped;
+    enter(Current { ptr, mutable: false }, f)
+}
+
+/// Runs `f` with `interner` as the current interner
+///
+/// Inside `f`, symbols can be both [resolved](resolve) and [interned](intern).
+pub fn with_interner<H, R>(interner: &mut Interner<str, StringBackend, H>, f: impl FnOnce() -> R) -> R
+where
+    H: BuildHasher + 'static,
+{
+    let ptr = interner as *mut dyn Scoped;
+    enter(Current { ptr, mutable: true }, f)
+}
+
+/// Returns true if there's an interner in scope
+pub fn is_set() -> bool {
+    CURRENT.get().is_some()
+}
+
+/// Resolves `sym` with the current interner, and passes the result to `f`
+///
+/// `f` receives None if there's no interner in scope,
+/// or the symbol can't be resolved. Calls to [intern]
+/// from inside `f` return None.
+pub fn resolve<R>(sym: Symbol, f: impl FnOnce(Option<&str>) -> R) -> R {
+    /* Make the scope read-only while `f` runs, so that it can't
+     * call `intern` while we hold a reference into the interner */
+    let current = CURRENT.get();
+    let _restore = Restore(CURRENT.replace(current.map(|c| Current { mutable: false, ..c })));
+    /* SAFETY: The pointer is valid during the call to `with_resolver`
+     * or `with_interner`, which outlives this function */
+    let s = current.and_then(|c| unsafe { (*c.ptr).resolve(sym) });
+    f(s)
+}
+
+/// Gets the symbol of `s` on the current interner, without interning it
+pub fn get(s: &str) -> Option<Symbol> {
+    let current = CURRENT.get()?;
+    /* SAFETY: The pointer is valid during the call to `with_resolver`
+     * or `with_interner`, which outlives this function */
+    unsafe { (*current.ptr).get(s) }
+}
+
+/// Interns `s` on the current interner
+///
+/// Returns None if there's no interner in scope, or it was set
+/// with [with_resolver].
+pub fn intern(s: &str) -> Option<Symbol> {
+    let current = CURRENT.get().filter(|c| c.mutable)?;
+    /* SAFETY: The pointer comes from a mutable reference, valid during
+     * the call to `with_interner`. No other reference to the interner is
+     * alive, since `resolve` makes the scope read-only while it holds one. */
+    Some(unsafe { (*current.ptr).intern(s) })
+}
+
+/// Formats the symbol's value, resolved with the current interner
+///
+/// If there's no interner in scope, or the symbol doesn't resolve,
+/// it's formatted as `<symbol offset:len>`.
+impl fmt::Display for Symbol {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        resolve(*self, |s| match s {
+            Some(s) => fmt::Display::fmt(s, f),
+            None => write!(f, "<symbol {}:{}>", self.offset, self.len),
+        })
+    }
+}
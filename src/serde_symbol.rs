@@ -0,0 +1,79 @@
+//! Serde adapter to serialize symbols as their strings
+//!
+//! Use it with `#[serde(with = "interns::serde_symbol")]` on fields of
+//! type [Symbol]. The interner used to resolve and intern the symbols
+//! is taken from the current [scope].
+//!
+//! - Serialization requires [with_resolver](crate::scope::with_resolver)
+//!   or [with_interner](crate::scope::with_interner).
+//! - Deserialization with [with_interner](crate::scope::with_interner) interns
+//!   the strings. With [with_resolver](crate::scope::with_resolver), it only
+//!   accepts strings that are already interned.
+//!
+//! # Example
+//! ```
+//! use interns::{scope, StringInterner};
+//! use interns::backend::string::Symbol;
+//! use serde::{Serialize, Deserialize};
+//!
+//! #[derive(Serialize, Deserialize)]
+//! struct Field {
+//!     #[serde(with = "interns::serde_symbol")]
+//!     name: Symbol,
+//!     size: u32,
+//! }
+//!
+//! let mut interner = StringInterner::new();
+//! let field = Field { name: interner.get_or_intern("width"), size: 4 };
+//!
+//! let json = scope::with_resolver(&interner, || serde_json::to_string(&field)).unwrap();
+//! assert_eq!(json, r#"{"name":"width","size":4}"#);
+//!
+//! let mut other = StringInterner::new();
+//! let back: Field = scope::with_interner(&mut other, || serde_json::from_str(&json)).unwrap();
+//! assert_eq!(other.resolve(back.name), Some("width"));
+//! ```
+
+use core::fmt;
+
+use serde::de::{self, Visitor};
+use serde::ser::Error as _;
+use serde::{Deserializer, Serializer};
+
+use crate::backend::string::Symbol;
+use crate::scope;
+
+/// Serializes `sym` as the string it resolves to
+pub fn serialize<S: Serializer>(sym: &Symbol, serializer: S) -> Result<S::Ok, S::Error> {
+    if !scope::is_set() {
+        return Err(S::Error::custom("no interner in scope"))
+    }
+    scope::resolve(*sym, |s| match s {
+        Some(s) => serializer.serialize_str(s),
+        None => Err(S::Error::custom(format_args!("unknown symbol {sym:?}"))),
+    })
+}
+
+/// Deserializes a string, and interns it on the current scope
+pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Symbol, D::Error> {
+    deserializer.deserialize_str(SymbolVisitor)
+}
+
+struct SymbolVisitor;
+
+impl Visitor<'_> for SymbolVisitor {
+    type Value = Symbol;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("a string")
+    }
+
+    fn visit_str<E: de::Error>(self, s: &str) -> Result<Symbol, E> {
+        if !scope::is_set() {
+            return Err(E::custom("no interner in scope"))
+        }
+        scope::intern(s)
+            .or_else(|| scope::get(s))
+            .ok_or_else(|| E::custom(format_args!("string {s:?} is not interned")))
+    }
+}
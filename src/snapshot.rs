@@ -0,0 +1,587 @@
+//! Snapshots of a [StringInterner](crate::StringInterner)
+//!
+//! A [Snapshot] is an owned copy of all the values of an interner, along with
+//! their symbols and positions. An interner restored from a snapshot assigns
+//! the exact same symbols as the original one.
+//!
+//! Snapshots can be [saved](Snapshot::save) into a compact binary format,
+//! which embeds a [fingerprint](Snapshot::fingerprint) of its contents.
+//! Loading a snapshot verifies that fingerprint.
+//!
+//! Long-running processes can avoid rewriting the whole table by saving
+//! [deltas](Interner::save_delta), which only contain the values interned
+//! since a [Checkpoint]. Deltas can be appended to the same file, and
+//! [applied](Interner::apply_delta) in order on top of the base snapshot.
+
+use core::fmt;
+use core::hash::BuildHasher;
+use std::io::{self, Read, Write};
+
+use crate::backend::string::Symbol;
+use crate::{Backend, Interner, RemapTable, StringBackend};
+
+/// An entry of a [Snapshot]
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Entry {
+    /// Symbol of the value
+    pub symbol: Symbol,
+    /// Position of the value inside the backend's buffer
+    pub offset: usize,
+    /// The interned value
+    pub value: String,
+}
+
+/// A copy of the contents of an interner
+///
+/// The entries are sorted by their offset.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Snapshot {
+    entries: Vec<Entry>,
+}
+
+impl Snapshot {
+    /// Returns the entries of this snapshot, sorted by offset
+    pub fn entries(&self) -> &[Entry] {
+        &self.entries
+    }
+
+    /// Returns the number of entries
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns true if the snapshot has no entries
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Computes the fingerprint of this snapshot
+    ///
+    /// Two snapshots have the same fingerprint if they have the same
+    /// values, assigned to the same symbols. This matches the
+    /// [fingerprint](Interner::fingerprint) of the interner it was taken from.
+    pub fn fingerprint(&self) -> u64 {
+        fingerprint(self.entries.iter().map(|e| (e.symbol, e.value.as_str())))
+    }
+
+    /// Returns true if all the symbols of this snapshot resolve to
+    /// the same values on `interner`
+    ///
+    /// The interner may contain more values than the snapshot. This is
+    /// useful to check that symbols stored externally (e.g. in an index file)
+    /// are still valid for an interner.
+    ///
+    /// # Example
+    /// ```
+    /// use interns::StringInterner;
+    ///
+    /// let mut interner = StringInterner::new();
+    /// interner.get_or_intern("hello");
+    /// let snapshot = interner.snapshot();
+    ///
+    /// interner.get_or_intern("world");
+    /// assert!(snapshot.is_compatible(&interner));
+    ///
+    /// let other = StringInterner::from_lines(["world", "hello"]);
+    /// assert!(!snapshot.is_compatible(&other));
+    /// ```
+    pub fn is_compatible<H: BuildHasher>(&self, interner: &Interner<str, StringBackend, H>) -> bool {
+        if interner.len() == self.len() {
+            return interner.fingerprint() == self.fingerprint()
+        }
+        interner.len() > self.len()
+            && self.entries.iter().all(|e| interner.get(e.value.as_str()) == Some(e.symbol))
+    }
+
+    /// Writes this snapshot in binary format
+    ///
+    /// # Example
+    /// ```
+    /// use interns::StringInterner;
+    /// use interns::snapshot::Snapshot;
+    ///
+    /// let interner = StringInterner::from_lines(["a", "b"]);
+    /// let mut buf = Vec::new();
+    /// interner.snapshot().save(&mut buf).unwrap();
+    ///
+    /// let snapshot = Snapshot::load(&buf[..]).unwrap();
+    /// assert_eq!(snapshot.fingerprint(), interner.fingerprint());
+    /// ```
+    pub fn save<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        writer.write_all(MAGIC)?;
+        writer.write_all(&[VERSION])?;
+        write_entries(&mut writer, &self.entries)
+    }
+
+    /// Reads a snapshot written by [save](Self::save)
+    ///
+    /// Fails with [InvalidData](io::ErrorKind::InvalidData) if the
+    /// data is malformed, or its fingerprint doesn't match.
+    pub fn load<R: Read>(mut reader: R) -> io::Result<Self> {
+        let mut magic = [0; MAGIC.len() + 1];
+        reader.read_exact(&mut magic)?;
+        if &magic[..MAGIC.len()] != MAGIC || magic[MAGIC.len()] != VERSION {
+            return Err(invalid_data("not a snapshot"))
+        }
+        read_entries(&mut reader).map(|entries| Snapshot { entries })
+    }
+}
+
+#[cfg(feature = "zstd")]
+impl Snapshot {
+    /// Writes this snapshot in binary format, compressed with zstd
+    ///
+    /// The data is compressed as it's written, without buffering the
+    /// whole snapshot. `level` is the zstd compression level. 0 means
+    /// zstd's default level.
+    ///
+    /// # Example
+    /// ```
+    /// use interns::StringInterner;
+    /// use interns::snapshot::Snapshot;
+    ///
+    /// let interner = StringInterner::from_lines((0..1000).map(|i| format!("identifier_{i}")));
+    /// let mut buf = Vec::new();
+    /// interner.snapshot().save_compressed(&mut buf, 0).unwrap();
+    ///
+    /// let snapshot = Snapshot::load_compressed(&buf[..]).unwrap();
+    /// assert_eq!(snapshot.fingerprint(), interner.fingerprint());
+    /// ```
+    pub fn save_compressed<W: Write>(&self, writer: W, level: i32) -> io::Result<()> {
+        let mut encoder = zstd::Encoder::new(writer, level)?;
+        self.save(&mut encoder)?;
+        encoder.finish()?;
+        Ok(())
+    }
+
+    /// Reads a snapshot written by [save_compressed](Self::save_compressed)
+    pub fn load_compressed<R: Read>(reader: R) -> io::Result<Self> {
+        Self::load(zstd::Decoder::new(reader)?)
+    }
+}
+
+const MAGIC: &[u8] = b"INTERNS";
+const DELTA_MAGIC: &[u8] = b"INTERND";
+const VERSION: u8 = 1;
+
+/// A position on the history of an interner
+///
+/// Returned by [Interner::checkpoint], and used to save [deltas](Interner::save_delta)
+/// with the values interned after it. A checkpoint is no longer valid once values
+/// are [removed](Interner::remove) from the interner, or [compacted](Interner::compact).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Checkpoint {
+    /// Length of the backend's buffer
+    offset: usize,
+    /// Number of interned values
+    len: usize,
+    /// Number of removals of the interner
+    removals: u64,
+}
+
+impl Checkpoint {
+    /// Returns the number of values interned at this checkpoint
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns true if this checkpoint precedes any interned value
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+}
+
+/// Writes the fingerprint, count and `entries`
+fn write_entries<W: Write>(writer: &mut W, entries: &[Entry]) -> io::Result<()> {
+    let fingerprint = fingerprint(entries.iter().map(|e| (e.symbol, e.value.as_str())));
+    writer.write_all(&fingerprint.to_le_bytes())?;
+    writer.write_all(&(entries.len() as u64).to_le_bytes())?;
+    for Entry { symbol, offset, value } in entries {
+        writer.write_all(&symbol.as_u64().to_le_bytes())?;
+        writer.write_all(&(*offset as u64).to_le_bytes())?;
+        writer.write_all(&(value.len() as u64).to_le_bytes())?;
+        writer.write_all(value.as_bytes())?;
+    }
+    Ok(())
+}
+
+/// Reads the entries written by [write_entries], and verifies their fingerprint
+fn read_entries<R: Read>(reader: &mut R) -> io::Result<Vec<Entry>> {
+    let fingerprint = read_u64(reader)?;
+    let len = read_u64(reader)?;
+
+    let mut entries = Vec::new();
+    for _ in 0..len {
+        let symbol = Symbol::from_u64(read_u64(reader)?);
+        let offset = read_usize(reader)?;
+        let len = read_usize(reader)?;
+        let mut value = Vec::new();
+        reader.by_ref().take(len as u64).read_to_end(&mut value)?;
+        if value.len() != len {
+            return Err(io::ErrorKind::UnexpectedEof.into())
+        }
+        let value = String::from_utf8(value).map_err(invalid_data)?;
+        entries.push(Entry { symbol, offset, value });
+    }
+
+    if self::fingerprint(entries.iter().map(|e| (e.symbol, e.value.as_str()))) != fingerprint {
+        return Err(invalid_data("fingerprint mismatch"))
+    }
+    Ok(entries)
+}
+
+fn invalid_data<E>(err: E) -> io::Error
+where
+    E: Into<Box<dyn std::error::Error + Send + Sync>>,
+{
+    io::Error::new(io::ErrorKind::InvalidData, err)
+}
+
+fn read_u64<R: Read>(reader: &mut R) -> io::Result<u64> {
+    let mut buf = [0; 8];
+    reader.read_exact(&mut buf)?;
+    Ok(u64::from_le_bytes(buf))
+}
+
+fn read_usize<R: Read>(reader: &mut R) -> io::Result<usize> {
+    usize::try_from(read_u64(reader)?).map_err(invalid_data)
+}
+
+/// 64-bit FNV-1a over the symbols and values, in order
+fn fingerprint<'a>(entries: impl Iterator<Item = (Symbol, &'a str)>) -> u64 {
+    const OFFSET: u64 = 0xcbf29ce484222325;
+    const PRIME: u64 = 0x100000001b3;
+
+    let mut hash = OFFSET;
+    let mut write = |bytes: &[u8]| {
+        for b in bytes {
+            hash ^= *b as u64;
+            hash = hash.wrapping_mul(PRIME);
+        }
+    };
+    for (sym, value) in entries {
+        write(&sym.as_u64().to_le_bytes());
+        write(&(value.len() as u64).to_le_bytes());
+        write(value.as_bytes());
+    }
+    hash
+}
+
+/// Error returned when restoring an invalid [Snapshot]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SnapshotError {
+    /// An entry overlaps with the previous one
+    Overlap { offset: usize },
+    /// The symbol of an entry doesn't match its position
+    InvalidSymbol(Symbol),
+    /// The same value appears more than once
+    Duplicate(String),
+    /// The gap before an entry is bigger than the values of the snapshot
+    Gap { offset: usize },
+}
+
+impl fmt::Display for SnapshotError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SnapshotError::Overlap { offset } => write!(f, "overlapping entry at offset {offset}"),
+            SnapshotError::InvalidSymbol(sym) => write!(f, "invalid symbol {sym:?}"),
+            SnapshotError::Duplicate(value) => write!(f, "duplicate value {value:?}"),
+            SnapshotError::Gap { offset } => write!(f, "gap too big before the entry at offset {offset}"),
+        }
+    }
+}
+
+impl std::error::Error for SnapshotError {}
+
+impl<H: BuildHasher> Interner<str, StringBackend, H> {
+    /// Takes a [Snapshot] of the contents of this interner
+    ///
+    /// # Example
+    /// ```
+    /// use interns::StringInterner;
+    ///
+    /// let mut interner = StringInterner::new();
+    /// let hello = interner.get_or_intern("hello");
+    /// let snapshot = interner.snapshot();
+    ///
+    /// let restored = StringInterner::from_snapshot(&snapshot).unwrap();
+    /// assert_eq!(restored.resolve(hello), Some("hello"));
+    /// ```
+    pub fn snapshot(&self) -> Snapshot {
+        let entries = self.to_entries(self.sorted_entries());
+        Snapshot { entries }
+    }
+
+    fn to_entries(&self, entries: Vec<(Symbol, &str)>) -> Vec<Entry> {
+        entries
+            .into_iter()
+            .map(|(symbol, value)| Entry {
+                symbol,
+                offset: self.offset_of(symbol),
+                value: value.to_owned(),
+            })
+            .collect()
+    }
+
+    /// Returns a [Checkpoint] of the current state of this interner
+    pub fn checkpoint(&self) -> Checkpoint {
+        Checkpoint {
+            offset: self.backend.buf_len(),
+            len: self.len(),
+            removals: self.removals,
+        }
+    }
+
+    /// Writes the values interned since `since`
+    ///
+    /// Returns the current [Checkpoint], to be used on the next call.
+    ///
+    /// Fails with [InvalidInput](io::ErrorKind::InvalidInput) if values were
+    /// removed since `since`, since a delta can't represent the removals.
+    ///
+    /// # Example
+    /// ```
+    /// use interns::StringInterner;
+    ///
+    /// let mut interner = StringInterner::from_lines(["a", "b"]);
+    /// let mut file = Vec::new();
+    /// interner.snapshot().save(&mut file).unwrap();
+    /// let base_len = file.len();
+    ///
+    /// let mut checkpoint = interner.checkpoint();
+    /// for word in ["c", "d"] {
+    ///     interner.get_or_intern(word);
+    ///     checkpoint = interner.save_delta(checkpoint, &mut file).unwrap();
+    /// }
+    ///
+    /// let snapshot = interns::snapshot::Snapshot::load(&file[..base_len]).unwrap();
+    /// let mut restored = StringInterner::from_snapshot(&snapshot).unwrap();
+    /// let mut deltas = &file[base_len..];
+    /// while !deltas.is_empty() {
+    ///     restored.apply_delta(&mut deltas).unwrap();
+    /// }
+    /// assert_eq!(restored.fingerprint(), interner.fingerprint());
+    /// ```
+    pub fn save_delta<W: Write>(&self, since: Checkpoint, mut writer: W) -> io::Result<Checkpoint> {
+        let current = self.checkpoint();
+        if since.len > current.len || since.offset > current.offset {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, "checkpoint is ahead of the interner"))
+        }
+        if since.removals != current.removals {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, "values were removed since the checkpoint"))
+        }
+
+        /* Without removals, the buffer is append-only, so the new values are
+         * the ones at or past the checkpoint's offset. An empty string interned
+         * right before the checkpoint shares its offset, which is why we only
+         * keep the last entries. */
+        let mut tail = self.entries_from(since.offset);
+        let new = current.len - since.len;
+        if tail.len() < new {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, "checkpoint doesn't belong to this interner"))
+        }
+        let entries = self.to_entries(tail.split_off(tail.len() - new));
+
+        writer.write_all(DELTA_MAGIC)?;
+        writer.write_all(&[VERSION])?;
+        writer.write_all(&(since.offset as u64).to_le_bytes())?;
+        writer.write_all(&(since.len as u64).to_le_bytes())?;
+        write_entries(&mut writer, &entries)?;
+        Ok(current)
+    }
+
+    /// Reads a delta written by [save_delta](Self::save_delta), and interns its values
+    ///
+    /// The delta must have been saved from the current [checkpoint](Self::checkpoint)
+    /// of this interner, so deltas must be applied in the same order they were saved.
+    /// Returns the new checkpoint.
+    ///
+    /// Fails with [InvalidData](io::ErrorKind::InvalidData) if the data is malformed,
+    /// or if it doesn't follow this interner's checkpoint. On error, the
+    /// interner is left unchanged.
+    pub fn apply_delta<R: Read>(&mut self, mut reader: R) -> io::Result<Checkpoint> {
+        let mut magic = [0; DELTA_MAGIC.len() + 1];
+        reader.read_exact(&mut magic)?;
+        if &magic[..DELTA_MAGIC.len()] != DELTA_MAGIC || magic[DELTA_MAGIC.len()] != VERSION {
+            return Err(invalid_data("not a delta"))
+        }
+        let since = Checkpoint {
+            offset: read_usize(&mut reader)?,
+            len: read_usize(&mut reader)?,
+            removals: self.removals,
+        };
+        if since != self.checkpoint() {
+            return Err(invalid_data("delta doesn't follow the current checkpoint"))
+        }
+        let entries = read_entries(&mut reader)?;
+        self.restore(&Snapshot { entries }).map_err(invalid_data)?;
+        Ok(self.checkpoint())
+    }
+
+    /// Computes a fingerprint of the contents of this interner
+    ///
+    /// See [Snapshot::fingerprint]
+    pub fn fingerprint(&self) -> u64 {
+        fingerprint(self.sorted_entries().into_iter())
+    }
+
+    /// Returns all the entries, sorted by offset
+    fn sorted_entries(&self) -> Vec<(Symbol, &str)> {
+        let mut entries = self.iter().collect::<Vec<_>>();
+        entries.sort_unstable_by_key(|(sym, value)| (self.offset_of(*sym), value.len()));
+        entries
+    }
+
+    /// Returns the entries at or past `offset`, sorted by offset
+    ///
+    /// A [validating](StringBackend::validating) backend finds them by
+    /// position. Otherwise, every entry is scanned.
+    fn entries_from(&self, offset: usize) -> Vec<(Symbol, &str)> {
+        if let Some(symbols) = self.backend.symbols_from(offset) {
+            /* Removed values are still on the backend, but not on the set */
+            return symbols
+                .into_iter()
+                .filter_map(|sym| Some((sym, self.backend.get(sym)?)))
+                .filter(|&(sym, value)| self.get(value) == Some(sym))
+                .collect()
+        }
+        let mut entries = self
+            .iter()
+            .filter(|(sym, _)| self.offset_of(*sym) >= offset)
+            .collect::<Vec<_>>();
+        entries.sort_unstable_by_key(|(sym, value)| (self.offset_of(*sym), value.len()));
+        entries
+    }
+
+    /// Returns the offset of a symbol of the set
+    fn offset_of(&self, sym: Symbol) -> usize {
+        self.backend.offset_of(sym).expect("Every symbol on the set is on the backend")
+    }
+
+    /// Builds an interner from a [Snapshot]
+    ///
+    /// The values [removed](Self::remove) from the original interner leave gaps
+    /// on the buffer, which are restored too. Snapshots where the gaps are bigger
+    /// than the rest of the values are rejected with [SnapshotError::Gap]. Such
+    /// interners should be [compacted](Self::compact) before taking the snapshot.
+    pub fn from_snapshot(snapshot: &Snapshot) -> Result<Self, SnapshotError>
+    where
+        H: Default,
+    {
+        let mut interner = Self::new();
+        interner.restore(snapshot)?;
+        Ok(interner)
+    }
+
+    /// Interns all the values of `snapshot` into this interner
+    ///
+    /// Values that are already interned keep their current symbol. The
+    /// returned [RemapTable] maps the symbols of the snapshot to their
+    /// symbols on this interner.
+    ///
+    /// # Example
+    /// ```
+    /// use interns::StringInterner;
+    ///
+    /// let yesterday = StringInterner::from_lines(["a", "b"]);
+    /// let old_b = yesterday.get("b").unwrap();
+    /// let snapshot = yesterday.snapshot();
+    ///
+    /// let mut today = StringInterner::from_lines(["c", "b"]);
+    /// let remap = today.absorb_snapshot(snapshot);
+    ///
+    /// assert_eq!(remap.get(old_b), today.get("b"));
+    /// assert_eq!(today.len(), 3);
+    /// ```
+    pub fn absorb_snapshot(&mut self, snapshot: Snapshot) -> RemapTable<Symbol> {
+        let mut remap = RemapTable::with_capacity(snapshot.len());
+        for Entry { symbol, value, .. } in snapshot.entries {
+            let new = self.get_or_intern(value.as_str());
+            remap.insert(symbol, new);
+        }
+        remap
+    }
+
+    /// Restores the entries of `snapshot`
+    ///
+    /// On error, the interner is rolled back to its previous state.
+    fn restore(&mut self, snapshot: &Snapshot) -> Result<(), SnapshotError> {
+        let mark = self.backend.mark();
+        let mut restored = 0;
+        let result = self.restore_entries(&snapshot.entries, &mut restored);
+        if result.is_err() {
+            for Entry { symbol, value, .. } in &snapshot.entries[..restored] {
+                let hash = self.hasher.hash_one(value.as_str());
+                if let Ok(entry) = self.set.find_entry(hash, |sym| sym == symbol) {
+                    entry.remove();
+                }
+            }
+            self.backend.rollback(mark);
+        }
+        self.recount_metrics();
+        result
+    }
+
+    /// Restores `entries`, counting on `restored` the ones inserted on the set
+    fn restore_entries(&mut self, entries: &[Entry], restored: &mut usize) -> Result<(), SnapshotError> {
+        /* Gaps between the entries are left by removed values, and are padded.
+         * The offsets come from untrusted input, so the total padding is capped
+         * by the size of the values, instead of allocating whatever it asks for. */
+        let mut budget = self.backend.buf_len();
+        for Entry { symbol, offset, value } in entries {
+            let Some(gap) = offset.checked_sub(self.backend.buf_len()) else {
+                return Err(SnapshotError::Overlap { offset: *offset })
+            };
+            budget = budget
+                .saturating_add(value.len())
+                .checked_sub(gap)
+                .ok_or(SnapshotError::Gap { offset: *offset })?;
+            if !self.backend.push_at(*offset, *symbol, value) {
+                return Err(SnapshotError::InvalidSymbol(*symbol))
+            }
+            if !self.insert_symbol(*symbol) {
+                return Err(SnapshotError::Duplicate(value.clone()))
+            }
+            *restored += 1;
+        }
+        Ok(())
+    }
+
+    /// Writes the contents of this interner as JSON
+    ///
+    /// The output is an object with an `entries` array, where each
+    /// element has the `symbol`, `offset` and `value` of an interned string.
+    ///
+    /// # Example
+    /// ```
+    /// use interns::StringInterner;
+    ///
+    /// let mut interner = StringInterner::new();
+    /// interner.get_or_intern("hi");
+    ///
+    /// let mut json = Vec::new();
+    /// interner.dump_json(&mut json).unwrap();
+    /// assert_eq!(
+    ///     String::from_utf8(json).unwrap(),
+    ///     r#"{"entries":[{"symbol":{"offset":0,"len":2},"offset":0,"value":"hi"}]}"#
+    /// );
+    /// ```
+    #[cfg(feature = "json")]
+    pub fn dump_json<W: io::Write>(&self, writer: W) -> io::Result<()> {
+        serde_json::to_writer(writer, &self.snapshot())?;
+        Ok(())
+    }
+
+    /// Builds an interner from the JSON written by [dump_json](Self::dump_json)
+    #[cfg(feature = "json")]
+    pub fn from_json<R: io::Read>(reader: R) -> io::Result<Self>
+    where
+        H: Default,
+    {
+        let snapshot: Snapshot = serde_json::from_reader(reader)?;
+        Self::from_snapshot(&snapshot)
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))
+    }
+}
@@ -0,0 +1,114 @@
+//! Symbols sorted by their values
+//!
+//! [Interner::sorted_symbols] returns the symbols in the order of their
+//! values, to produce deterministic output without collecting and sorting
+//! owned copies of the values.
+//!
+//! When the order is needed many times, a [SortIndex] caches it, and
+//! only sorts again when the interner changes.
+//!
+//! # Example
+//! ```
+//! use interns::StringInterner;
+//!
+//! let mut interner = StringInterner::new();
+//! for word in ["pear", "apple", "fig"] {
+//!     interner.get_or_intern(word);
+//! }
+//!
+//! let sorted: Vec<_> = interner
+//!     .sorted_symbols()
+//!     .into_iter()
+//!     .map(|sym| interner.resolve(sym).unwrap())
+//!     .collect();
+//! assert_eq!(sorted, ["apple", "fig", "pear"]);
+//! ```
+
+use core::hash::{BuildHasher, Hash};
+
+use crate::{Backend, Interner};
+
+impl<T, B, H> Interner<T, B, H>
+where
+    T: Hash + Eq + Ord + ?Sized,
+    B: Backend<T>,
+    H: BuildHasher,
+{
+    /// Returns the symbols, sorted by their values
+    pub fn sorted_symbols(&self) -> Vec<B::Symbol> {
+        let mut entries: Vec<_> = self.iter().collect();
+        entries.sort_unstable_by_key(|&(_, value)| value);
+        entries.into_iter().map(|(sym, _)| sym).collect()
+    }
+
+    /// Builds a [SortIndex] for this interner
+    pub fn sort_index(&self) -> SortIndex<B::Symbol> {
+        SortIndex {
+            symbols: self.sorted_symbols(),
+            generation: self.generation(),
+        }
+    }
+}
+
+/// Cached order of the symbols of an interner, by their values
+///
+/// See the [module-level documentation](self) for more details.
+#[derive(Clone, Debug)]
+pub struct SortIndex<S> {
+    symbols: Vec<S>,
+    /// Generation of the interner when the index was sorted
+    generation: u64,
+}
+
+impl<S: Copy> SortIndex<S> {
+    /// Returns the sorted symbols
+    ///
+    /// This doesn't reflect the changes to the interner after
+    /// the index was built or [refreshed](Self::refresh).
+    pub fn symbols(&self) -> &[S] {
+        &self.symbols
+    }
+
+    /// Returns true if `interner` has changed since the index was built
+    ///
+    /// This compares the [generation](Interner::generation) of the interner,
+    /// so values that were removed or compacted away are noticed too.
+    pub fn is_stale<T, B, H>(&self, interner: &Interner<T, B, H>) -> bool
+    where
+        T: Hash + Eq + ?Sized,
+        B: Backend<T, Symbol = S>,
+        H: BuildHasher,
+    {
+        interner.generation() != self.generation
+    }
+
+    /// Sorts the symbols of `interner` again, if it has changed
+    ///
+    /// Returns true if the index was updated.
+    ///
+    /// # Example
+    /// ```
+    /// use interns::StringInterner;
+    ///
+    /// let mut interner = StringInterner::new();
+    /// let b = interner.get_or_intern("b");
+    /// let mut index = interner.sort_index();
+    /// assert!(!index.refresh(&interner));
+    ///
+    /// let a = interner.get_or_intern("a");
+    /// assert!(index.refresh(&interner));
+    /// assert_eq!(index.symbols(), [a, b]);
+    /// ```
+    pub fn refresh<T, B, H>(&mut self, interner: &Interner<T, B, H>) -> bool
+    where
+        T: Hash + Eq + Ord + ?Sized,
+        B: Backend<T, Symbol = S>,
+        H: BuildHasher,
+    {
+        if !self.is_stale(interner) {
+            return false
+        }
+        *self = interner.sort_index();
+        true
+    }
+}
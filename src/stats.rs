@@ -0,0 +1,487 @@
+//! Statistics about the usage of an [Interner]
+//!
+//! # Metrics
+//! With the `metrics` feature, interners report their activity to the
+//! `metrics` crate. The metrics aggregate all the interners
+//! of the program.
+//!
+//! | Name | Type | Description |
+//! |------|------|-------------|
+//! | `interns_requests_total` | counter | Interning requests, labeled with `result="hit"` or `result="miss"` |
+//! | `interns_entries` | gauge | Unique values stored by the live interners |
+//! | `interns_arena_bytes` | gauge | Size of the unique values stored, in bytes |
+//! | `interns_hit_ratio` | gauge | Fraction of the requests that found an existing value |
+//!
+//! The gauges go down when values are [removed](Interner::remove), and
+//! when an interner is dropped, its values are taken out of them.
+//!
+//! `describe_metrics` registers their descriptions on the installed recorder.
+
+use core::fmt;
+use core::hash::{BuildHasher, Hash};
+use std::collections::BTreeMap;
+
+use crate::{Backend, Interner, StringBackend};
+
+/// Number of interning requests, and their size
+#[derive(Debug, Default)]
+pub(crate) struct Usage {
+    requests: usize,
+    bytes: usize,
+    #[cfg(feature = "metrics")]
+    gauges: metrics_impl::Gauges,
+}
+
+impl Usage {
+    pub(crate) const fn new() -> Self {
+        Self {
+            requests: 0,
+            bytes: 0,
+            #[cfg(feature = "metrics")]
+            gauges: metrics_impl::Gauges::new(),
+        }
+    }
+
+    pub(crate) fn record(&mut self, bytes: usize) {
+        self.requests = self.requests.saturating_add(1);
+        self.bytes = self.bytes.saturating_add(bytes);
+    }
+
+    /// Adds a value of `bytes` bytes to the [metrics](self#metrics)
+    #[cfg(feature = "metrics")]
+    pub(crate) fn added(&mut self, bytes: usize) {
+        self.gauges.add(bytes);
+    }
+
+    /// Removes a value of `bytes` bytes from the [metrics](self#metrics)
+    pub(crate) fn removed(&mut self, bytes: usize) {
+        #[cfg(feature = "metrics")]
+        self.gauges.remove(bytes);
+        #[cfg(not(feature = "metrics"))]
+        let _ = bytes;
+    }
+
+    /// Replaces the values reported to the [metrics](self#metrics)
+    /// with `entries` values of `bytes` bytes
+    #[cfg(feature = "metrics")]
+    pub(crate) fn recount(&mut self, entries: usize, bytes: usize) {
+        self.gauges.set(entries, bytes);
+    }
+}
+
+/// Estimation of the memory saved by interning
+///
+/// Returned by [Interner::savings]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct SavingsReport {
+    /// Number of calls to [get_or_intern](Interner::get_or_intern)
+    /// and [try_get_or_intern](Interner::try_get_or_intern)
+    pub requests: usize,
+    /// Total size of the values of those calls
+    ///
+    /// This is what would have been allocated without interning.
+    pub requested_bytes: usize,
+    /// Number of unique values on the interner
+    pub unique: usize,
+    /// Total size of the unique values
+    pub stored_bytes: usize,
+    /// Size of the table used to deduplicate the values
+    pub table_bytes: usize,
+}
+
+impl SavingsReport {
+    /// Returns the memory used by the interner: the
+    /// stored values, and the deduplication table
+    pub fn interned_bytes(&self) -> usize {
+        self.stored_bytes.saturating_add(self.table_bytes)
+    }
+
+    /// Returns the bytes saved by interning
+    ///
+    /// It's negative if interning used more memory than it saved.
+    pub fn saved_bytes(&self) -> i128 {
+        self.requested_bytes as i128 - self.interned_bytes() as i128
+    }
+
+    /// Returns how many times smaller the interned
+    /// values are, compared to the requested ones
+    pub fn ratio(&self) -> f64 {
+        self.requested_bytes as f64 / self.interned_bytes().max(1) as f64
+    }
+}
+
+impl fmt::Display for SavingsReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} requests ({} bytes) for {} unique values ({} bytes + {} bytes of table): {} bytes saved",
+            self.requests,
+            self.requested_bytes,
+            self.unique,
+            self.stored_bytes,
+            self.table_bytes,
+            self.saved_bytes(),
+        )
+    }
+}
+
+impl<T, B, H> Interner<T, B, H>
+where
+    T: Hash + Eq + ?Sized,
+    B: Backend<T>,
+    H: BuildHasher,
+{
+    /// Estimates the memory saved by interning
+    ///
+    /// The size of the values is their [size_of_val]. This is their
+    /// length in bytes for [str] and slices, but it doesn't include the
+    /// heap memory of types like [String]. Since the requests are measured
+    /// by the value passed to [get_or_intern](Self::get_or_intern), an
+    /// `Interner<String>` queried with [str]s does account for it.
+    ///
+    /// The memory used by the backend besides the values (e.g. unused
+    /// capacity, or bookkeeping) is not taken into account.
+    ///
+    /// # Example
+    /// ```
+    /// use interns::StringInterner;
+    ///
+    /// let mut interner = StringInterner::new();
+    /// for _ in 0..1000 {
+    ///     interner.get_or_intern("a somewhat long string");
+    /// }
+    /// let report = interner.savings();
+    /// assert_eq!(report.requests, 1000);
+    /// assert_eq!(report.requested_bytes, 22_000);
+    /// assert_eq!(report.stored_bytes, 22);
+    /// assert!(report.saved_bytes() > 20_000);
+    /// ```
+    pub fn savings(&self) -> SavingsReport {
+        let stored_bytes = self
+            .set
+            .iter()
+            /* SAFETY: Every symbol on the table is on the backend */
+            .map(|&sym| size_of_val(unsafe { self.backend.get_unchecked(sym) }))
+            .sum();
+        SavingsReport {
+            requests: self.usage.requests,
+            requested_bytes: self.usage.bytes,
+            unique: self.len(),
+            stored_bytes,
+            /* Each bucket holds a symbol, and a control byte */
+            table_bytes: self.set.capacity() * (size_of::<B::Symbol>() + 1),
+        }
+    }
+}
+
+/// Distribution of the sizes of the values of an [Interner]
+///
+/// Returned by [Interner::length_stats]. The size of a value is its
+/// [size_of_val], which for a [str] or slice is its length.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct LengthStats {
+    /// Number of values
+    pub count: usize,
+    /// Total size of the values
+    pub total_bytes: usize,
+    /// Size of the smallest value, or 0 if there are none
+    pub min_len: usize,
+    /// Size of the biggest value, or 0 if there are none
+    pub max_len: usize,
+    /// Number of values of each size
+    pub histogram: BTreeMap<usize, usize>,
+}
+
+impl LengthStats {
+    /// Returns the mean size of the values, or 0 if there are none
+    pub fn mean_len(&self) -> f64 {
+        if self.count == 0 {
+            return 0.0
+        }
+        self.total_bytes as f64 / self.count as f64
+    }
+
+    /// Returns how many values are at most `len` bytes long
+    pub fn count_up_to(&self, len: usize) -> usize {
+        self.histogram.range(..=len).map(|(_, n)| n).sum()
+    }
+}
+
+impl<T, B, H> Interner<T, B, H>
+where
+    T: Hash + Eq + ?Sized,
+    B: Backend<T>,
+    H: BuildHasher,
+{
+    /// Returns how many values there are of each size
+    ///
+    /// # Example
+    /// ```
+    /// use interns::StringInterner;
+    ///
+    /// let interner = StringInterner::from_lines(["a", "b", "abc"]);
+    /// let histogram = interner.length_histogram();
+    /// assert_eq!(histogram.get(&1), Some(&2));
+    /// assert_eq!(histogram.get(&3), Some(&1));
+    /// ```
+    pub fn length_histogram(&self) -> BTreeMap<usize, usize> {
+        let mut histogram = BTreeMap::new();
+        for (_, value) in self.iter() {
+            *histogram.entry(size_of_val(value)).or_default() += 1;
+        }
+        histogram
+    }
+
+    /// Returns statistics about the sizes of the values
+    ///
+    /// This can guide decisions like the inline capacity of small
+    /// string optimizations, or the choice of a backend.
+    ///
+    /// # Example
+    /// ```
+    /// use interns::StringInterner;
+    ///
+    /// let interner = StringInterner::from_lines(["id", "name", "description"]);
+    /// let stats = interner.length_stats();
+    /// assert_eq!(stats.max_len, 11);
+    /// assert_eq!(stats.mean_len(), 17.0 / 3.0);
+    /// assert_eq!(stats.count_up_to(4), 2);
+    /// ```
+    pub fn length_stats(&self) -> LengthStats {
+        let histogram = self.length_histogram();
+        LengthStats {
+            count: self.len(),
+            total_bytes: histogram.iter().map(|(len, n)| len * n).sum(),
+            min_len: histogram.keys().next().copied().unwrap_or(0),
+            max_len: histogram.keys().next_back().copied().unwrap_or(0),
+            histogram,
+        }
+    }
+}
+
+/// Statistics about the table used to deduplicate the values of an [Interner]
+///
+/// Returned by [Interner::table_stats]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct TableStats {
+    /// Number of values on the table
+    pub len: usize,
+    /// Number of values the table can hold without growing
+    pub capacity: usize,
+    /// Size of the table, in bytes
+    pub table_bytes: usize,
+    /// Statistics about the lookups on the table
+    ///
+    /// They are only computed with debug assertions, since
+    /// it takes hashing and looking up every value.
+    pub probes: Option<ProbeStats>,
+}
+
+impl TableStats {
+    /// Returns the fraction of the capacity that is in use
+    pub fn load_factor(&self) -> f64 {
+        if self.capacity == 0 {
+            return 0.0
+        }
+        self.len as f64 / self.capacity as f64
+    }
+}
+
+/// Statistics about the lookups on the deduplication table
+///
+/// A lookup compares the value with every entry whose hash looks
+/// similar, until it finds it. With a good [hasher](BuildHasher),
+/// this is close to one comparison per lookup.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct ProbeStats {
+    /// Total number of comparisons to look up every value
+    pub total_comparisons: usize,
+    /// Most comparisons done to look up a single value
+    pub max_comparisons: usize,
+    /// Number of different hashes of the values
+    pub distinct_hashes: usize,
+    /// Most values that share the same hash
+    pub max_same_hash: usize,
+}
+
+impl ProbeStats {
+    /// Returns the mean number of comparisons to look up a value
+    pub fn mean_comparisons(&self, len: usize) -> f64 {
+        if len == 0 {
+            return 0.0
+        }
+        self.total_comparisons as f64 / len as f64
+    }
+}
+
+impl<T, B, H> Interner<T, B, H>
+where
+    T: Hash + Eq + ?Sized,
+    B: Backend<T>,
+    H: BuildHasher,
+{
+    /// Returns statistics about the deduplication table
+    ///
+    /// This helps diagnose a [hasher](BuildHasher) that performs
+    /// badly on the interned values. The [probes](TableStats::probes)
+    /// are only computed with debug assertions.
+    ///
+    /// # Example
+    /// ```
+    /// use std::hash::BuildHasherDefault;
+    /// use interns::{Interner, StringBackend};
+    ///
+    /// /* A hasher that maps everything to the same hash */
+    /// #[derive(Default)]
+    /// struct Constant;
+    ///
+    /// impl std::hash::Hasher for Constant {
+    ///     fn finish(&self) -> u64 { 0 }
+    ///     fn write(&mut self, _: &[u8]) {}
+    /// }
+    ///
+    /// let mut interner = Interner::<str, StringBackend, _>::with_hasher(BuildHasherDefault::<Constant>::default());
+    /// for s in ["a", "b", "c", "d"] {
+    ///     interner.get_or_intern(s);
+    /// }
+    /// let stats = interner.table_stats();
+    /// assert_eq!(stats.len, 4);
+    /// assert!(stats.load_factor() <= 1.0);
+    /// if let Some(probes) = stats.probes {
+    ///     assert_eq!(probes.distinct_hashes, 1);
+    ///     assert_eq!(probes.max_same_hash, 4);
+    /// }
+    /// ```
+    pub fn table_stats(&self) -> TableStats {
+        TableStats {
+            len: self.set.len(),
+            capacity: self.set.capacity(),
+            /* Each bucket holds a symbol, and a control byte */
+            table_bytes: self.set.capacity() * (size_of::<B::Symbol>() + 1),
+            probes: cfg!(debug_assertions).then(|| self.probe_stats()),
+        }
+    }
+
+    fn probe_stats(&self) -> ProbeStats {
+        let mut stats = ProbeStats::default();
+        let mut hashes = std::collections::HashMap::<u64, usize>::with_capacity(self.set.len());
+        for &sym in &self.set {
+            /* SAFETY: Every symbol on the table is on the backend */
+            let hash = self.hasher.hash_one(unsafe { self.backend.get_unchecked(sym) });
+            let mut comparisons = 0;
+            self.set.find(hash, |&other| {
+                comparisons += 1;
+                other == sym
+            });
+            stats.total_comparisons += comparisons;
+            stats.max_comparisons = stats.max_comparisons.max(comparisons);
+
+            let same = hashes.entry(hash).or_default();
+            *same += 1;
+            stats.max_same_hash = stats.max_same_hash.max(*same);
+        }
+        stats.distinct_hashes = hashes.len();
+        stats
+    }
+}
+
+impl<H: BuildHasher> Interner<str, StringBackend, H> {
+    /// Returns the number of strings with an [indexed](crate::backend::string::Symbol) symbol
+    ///
+    /// The strings of [prefilled](crate::backend::string::StringInternerBuilder)
+    /// interners are indexed, and so are the ones that don't fit in an inlined
+    /// symbol (e.g. past the first 4 GiB of the buffer). The rest are inlined.
+    pub fn indexed_count(&self) -> usize {
+        self.set.iter().filter(|sym| !sym.is_inlined()).count()
+    }
+}
+
+#[cfg(feature = "metrics")]
+mod metrics_impl {
+    use core::sync::atomic::{AtomicU64, Ordering};
+
+    use ::metrics::{Unit, counter, describe_counter, describe_gauge, gauge};
+
+    const REQUESTS: &str = "interns_requests_total";
+    const ENTRIES: &str = "interns_entries";
+    const ARENA_BYTES: &str = "interns_arena_bytes";
+    const HIT_RATIO: &str = "interns_hit_ratio";
+
+    static HITS: AtomicU64 = AtomicU64::new(0);
+    static TOTAL: AtomicU64 = AtomicU64::new(0);
+
+    /// Registers the descriptions of the [metrics](super#metrics)
+    pub fn describe_metrics() {
+        describe_counter!(REQUESTS, Unit::Count, "Interning requests");
+        describe_gauge!(ENTRIES, Unit::Count, "Unique values stored");
+        describe_gauge!(ARENA_BYTES, Unit::Bytes, "Size of the unique values stored");
+        describe_gauge!(HIT_RATIO, "Fraction of the requests that found an existing value");
+    }
+
+    /// Records a request for a value
+    ///
+    /// `new` is true if the value wasn't on the interner.
+    pub(crate) fn record_metrics(new: bool) {
+        let total = TOTAL.fetch_add(1, Ordering::Relaxed) + 1;
+        let hits = if new {
+            counter!(REQUESTS, "result" => "miss").increment(1);
+            HITS.load(Ordering::Relaxed)
+        } else {
+            counter!(REQUESTS, "result" => "hit").increment(1);
+            HITS.fetch_add(1, Ordering::Relaxed) + 1
+        };
+        gauge!(HIT_RATIO).set(hits as f64 / total as f64);
+    }
+
+    /// Values of an interner, added to the gauges
+    ///
+    /// They're taken out of the gauges when the interner is dropped.
+    #[derive(Debug, Default)]
+    pub(crate) struct Gauges {
+        entries: usize,
+        bytes: usize,
+    }
+
+    impl Gauges {
+        pub(crate) const fn new() -> Self {
+            Self { entries: 0, bytes: 0 }
+        }
+
+        pub(crate) fn add(&mut self, bytes: usize) {
+            self.set(self.entries.saturating_add(1), self.bytes.saturating_add(bytes));
+        }
+
+        pub(crate) fn remove(&mut self, bytes: usize) {
+            self.set(self.entries.saturating_sub(1), self.bytes.saturating_sub(bytes));
+        }
+
+        /// Updates the gauges, so that this interner has
+        /// `entries` values of `bytes` bytes
+        pub(crate) fn set(&mut self, entries: usize, bytes: usize) {
+            update(ENTRIES, self.entries, entries);
+            update(ARENA_BYTES, self.bytes, bytes);
+            self.entries = entries;
+            self.bytes = bytes;
+        }
+    }
+
+    impl Drop for Gauges {
+        fn drop(&mut self) {
+            self.set(0, 0);
+        }
+    }
+
+    /// Moves the gauge `name` by the difference between `old` and `new`
+    fn update(name: &'static str, old: usize, new: usize) {
+        if new > old {
+            gauge!(name).increment((new - old) as f64);
+        } else if new < old {
+            gauge!(name).decrement((old - new) as f64);
+        }
+    }
+}
+
+#[cfg(feature = "metrics")]
+pub use metrics_impl::describe_metrics;
+#[cfg(feature = "metrics")]
+pub(crate) use metrics_impl::record_metrics;
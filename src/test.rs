@@ -163,3 +163,3303 @@ fn string_interner_prefill() {
     assert_eq!(interner.get_or_intern("you"), BUILDER.symbol_at(3));
     assert_eq!(interner.get_or_intern("?"), BUILDER.symbol_at(4));
 }
+
+#[cfg(feature = "lasso")]
+#[test]
+fn lasso_compat() {
+    use crate::backend::string::Symbol;
+
+    fn count_words(interner: &mut impl lasso::Interner<Symbol>, text: &str) -> usize {
+        text.split_whitespace()
+            .map(|w| interner.get_or_intern(w))
+            .collect::<std::collections::HashSet<_>>()
+            .len()
+    }
+
+    let mut interner = StringInterner::new();
+    assert_eq!(count_words(&mut interner, "a b a c b"), 3);
+
+    let reader: &dyn lasso::Reader<Symbol> = &interner;
+    let b = reader.get("b").unwrap();
+    assert!(reader.contains_key(&b));
+    assert_eq!(reader.resolve(&b), "b");
+    assert_eq!(reader.len(), 3);
+    assert!(!reader.contains("d"));
+
+    use crate::compat::lasso::lasso_error;
+    use lasso::LassoErrorKind;
+    let backend = InternError::Backend("disk full".into());
+    for (err, kind) in [
+        (InternError::Full, LassoErrorKind::KeySpaceExhaustion),
+        (InternError::CapacityOverflow, LassoErrorKind::MemoryLimitReached),
+        (backend, LassoErrorKind::MemoryLimitReached),
+    ] {
+        assert_eq!(lasso_error(err).kind(), kind);
+    }
+    /* The errors are cached, so they stay the same */
+    assert_eq!(lasso_error(InternError::Full), lasso_error(InternError::Full));
+}
+
+#[cfg(feature = "string-interner")]
+#[test]
+fn string_interner_interop() {
+    use crate::backend::string::Symbol;
+    use string_interner::symbol::{SymbolU16, SymbolU32, SymbolUsize};
+
+    let mut interner = StringInterner::new();
+    let hello = interner.get_or_intern("hello");
+    let world = interner.get_or_intern("world");
+
+    let foreign: SymbolUsize = world.encode_foreign().unwrap();
+    assert_eq!(Symbol::decode_foreign(foreign), world);
+
+    /* The offset of "hello" is 0, so it fits into 16 bits... */
+    let small: SymbolU16 = hello.encode_foreign().unwrap();
+    assert_eq!(Symbol::decode_foreign(small), hello);
+    /* ... but a symbol with a non-zero offset doesn't */
+    assert!(world.encode_foreign::<SymbolU32>().is_err());
+    assert!(world.encode_foreign::<SymbolU16>().is_err());
+
+    /* The conversion traits use the same encoding */
+    let converted = SymbolUsize::try_from(world).unwrap();
+    assert_eq!(converted, foreign);
+    assert_eq!(Symbol::from(converted), world);
+    assert_eq!(Symbol::from(SymbolU32::try_from(hello).unwrap()), hello);
+    assert_eq!(SymbolU16::try_from(world), Err(crate::compat::SymbolOutOfRange));
+
+    fn roundtrip<S: string_interner::Symbol>(sym: S) -> S {
+        S::try_from_usize(sym.to_usize()).unwrap()
+    }
+    assert_eq!(roundtrip(hello), hello);
+}
+
+#[cfg(feature = "string_cache")]
+#[test]
+fn string_cache_atoms() {
+    use string_cache::DefaultAtom;
+
+    let mut interner = StringInterner::new();
+    let atoms = ["div", "span", "div", "p"].map(DefaultAtom::from);
+
+    let syms = atoms.each_ref().map(|a| interner.get_or_intern_atom(a));
+    assert_eq!(syms[0], syms[2]);
+    assert_ne!(syms[0], syms[1]);
+    assert_eq!(interner.len(), 3);
+
+    assert_eq!(interner.get_atom(&DefaultAtom::from("p")), Some(syms[3]));
+    assert_eq!(interner.get_atom(&DefaultAtom::from("a")), None);
+
+    let back: DefaultAtom = interner.resolve_atom(syms[1]).unwrap();
+    assert_eq!(back, atoms[1]);
+}
+
+#[cfg(all(feature = "smol_str", feature = "compact_str"))]
+#[test]
+fn owned_small_strings() {
+    let mut interner = StringInterner::new();
+    let short = interner.get_or_intern("id");
+    let long = interner.get_or_intern("a_rather_long_identifier_name_that_spills");
+
+    let smol = interner.resolve_smol(short).unwrap();
+    assert!(!smol.is_heap_allocated());
+    assert_eq!(smol.clone(), "id");
+
+    let compact = interner.resolve_compact(long).unwrap();
+    assert_eq!(compact, "a_rather_long_identifier_name_that_spills");
+
+    let bogus = crate::backend::string::Symbol::new_indexed(7);
+    assert!(interner.resolve_smol(bogus).is_none());
+}
+
+#[test]
+fn arc_backend() {
+    use crate::backend::ArcBackend;
+
+    let mut interner = Interner::<str, ArcBackend<str>>::new();
+    let a = interner.get_or_intern("hello");
+    let b = interner.get_or_intern(&String::from("hello"));
+    assert_eq!(a, b);
+
+    let shared = interner.resolve_arc(a).unwrap();
+    assert_eq!(std::sync::Arc::strong_count(&shared), 2);
+
+    let handle = std::thread::spawn(move || shared.len());
+    drop(interner);
+    assert_eq!(handle.join().unwrap(), 5);
+
+    let mut slices = Interner::<[u8], ArcBackend<[u8]>>::new();
+    let s = slices.get_or_intern(&[1, 2, 3][..]);
+    assert_eq!(slices.resolve_arc(s).as_deref(), Some(&[1, 2, 3][..]));
+}
+
+#[cfg(feature = "ffi")]
+#[test]
+fn ffi() {
+    use crate::ffi::*;
+
+    let interner = interner_new();
+    let mut sym = InternerSymbol { offset: 0, len: 0 };
+    let mut len = 0;
+    unsafe {
+        assert!(interner_intern(interner, "hello".as_ptr(), 5, &mut sym));
+        assert!(!interner_intern(interner, [0xff, 0xfe].as_ptr(), 2, &mut sym));
+
+        let ptr = interner_resolve(interner, sym, &mut len);
+        assert_eq!(std::slice::from_raw_parts(ptr, len), b"hello");
+
+        /* Fabricated symbols must not crash */
+        let bogus = InternerSymbol { offset: 1000, len: 5 };
+        assert!(interner_resolve(interner, bogus, &mut len).is_null());
+
+        assert_eq!(interner_len(interner), 1);
+        interner_free(interner);
+    }
+}
+
+#[test]
+fn u64_encoding() {
+    use crate::backend::string::Symbol;
+
+    let mut interner = StringInterner::new();
+    interner.get_or_intern("padding");
+    let sym = interner.get_or_intern("hello");
+
+    let encoded = sym.as_u64();
+    assert_eq!(encoded >> 32, 7);
+    assert_eq!(Symbol::from_u64(encoded), sym);
+
+    let indexed = Symbol::new_indexed(3);
+    assert_eq!(Symbol::from_u64(indexed.as_u64()), indexed);
+}
+
+#[cfg(feature = "wasm")]
+#[test]
+fn wasm_interner() {
+    use crate::wasm::JsInterner;
+
+    let mut interner = JsInterner::new();
+    let a = interner.intern("hello");
+    let b = interner.intern("world");
+    assert_eq!(interner.intern("hello"), a);
+    assert_eq!(interner.get("world"), Some(b));
+    assert_eq!(interner.resolve(b).as_deref(), Some("world"));
+    assert_eq!(interner.len(), 2);
+}
+
+#[test]
+fn snapshot_roundtrip() {
+    use crate::backend::string::StringInternerBuilder;
+
+    const BUILDER: StringInternerBuilder<2> = StringInternerBuilder::with_const_symbols(["fn", "let"]);
+    let mut interner = BUILDER.build();
+    let words = ["", "hello", "world", "fn", "ñandú"].map(|w| interner.get_or_intern(w));
+
+    let snapshot = interner.snapshot();
+    assert_eq!(snapshot.len(), 6);
+    assert!(snapshot.entries().is_sorted_by_key(|e| e.offset));
+
+    let restored = StringInterner::from_snapshot(&snapshot).unwrap();
+    for (sym, value) in interner.iter() {
+        assert_eq!(restored.get(value), Some(sym));
+    }
+    assert_eq!(restored.resolve(words[4]), Some("ñandú"));
+    assert_eq!(restored.snapshot(), snapshot);
+
+    /* Removed values leave gaps, which are restored */
+    interner.remove("hello");
+    let restored = StringInterner::from_snapshot(&interner.snapshot()).unwrap();
+    assert_eq!(restored.resolve(words[2]), Some("world"));
+    assert_eq!(restored.get("hello"), None);
+
+    let mut sparse = StringInterner::new();
+    sparse.get_or_intern("a very long value that gets removed");
+    sparse.remove("a very long value that gets removed");
+    sparse.get_or_intern("x");
+    let Err(err) = StringInterner::from_snapshot(&sparse.snapshot()) else { panic!() };
+    assert!(matches!(err, crate::snapshot::SnapshotError::Gap { .. }));
+}
+
+#[cfg(feature = "json")]
+#[test]
+fn json_roundtrip() {
+    let mut interner = StringInterner::new();
+    let a = interner.get_or_intern("quote \" and \\ backslash");
+    let b = interner.get_or_intern("second");
+
+    let mut json = Vec::new();
+    interner.dump_json(&mut json).unwrap();
+
+    let restored = StringInterner::from_json(&json[..]).unwrap();
+    assert_eq!(restored.resolve(a), interner.resolve(a));
+    assert_eq!(restored.get("second"), Some(b));
+
+    let invalid = r#"{"entries":[{"symbol":{"offset":0,"len":2},"offset":3,"value":"hi"}]}"#;
+    let Err(err) = StringInterner::from_json(invalid.as_bytes()) else { panic!() };
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+
+    /* A huge gap is rejected, instead of allocating the padding */
+    let huge = r#"{"entries":[{"symbol":{"offset":0,"len":4294967295},"offset":1099511627776,"value":"hi"}]}"#;
+    let Err(err) = StringInterner::from_json(huge.as_bytes()) else { panic!() };
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+}
+
+#[test]
+fn from_reader() {
+    let dict = "zebra\nant\r\n\nant\nbee";
+    let interner = StringInterner::from_reader(std::io::Cursor::new(dict)).unwrap();
+    assert_eq!(interner.len(), 3);
+    for word in ["zebra", "ant", "bee"] {
+        assert!(interner.contains(word));
+    }
+    assert!(!interner.contains(""));
+    /* Reserved for the 5 buffered lines, not just the 3 unique ones */
+    assert!(interner.set.capacity() >= 5);
+
+    let lines = dict.lines().map(String::from).collect::<Vec<_>>();
+    let interner = StringInterner::from_lines(&lines);
+    assert_eq!(interner.len(), 4);
+    assert!(interner.set.capacity() >= lines.len());
+}
+
+#[test]
+fn codegen() {
+    use crate::codegen::{generate, Options};
+
+    let mut out = Vec::new();
+    let options = Options { const_prefix: "KW_".into(), ..Default::default() };
+    generate(&mut out, &["fn", "let", "fn", "self", "Self", "a-b"], &options).unwrap();
+    let src = String::from_utf8(out).unwrap();
+
+    assert!(src.contains("StringInternerBuilder<5>"));
+    assert!(src.contains("pub const KW_FN: ::interns::backend::string::Symbol = BUILDER.symbol_at(0);"));
+    assert!(src.contains("pub const KW_SELF: ::interns::backend::string::Symbol = BUILDER.symbol_at(2);"));
+    assert!(src.contains("pub const KW_SELF_1: ::interns::backend::string::Symbol = BUILDER.symbol_at(3);"));
+    assert!(src.contains("pub const KW_A_B: ::interns::backend::string::Symbol = BUILDER.symbol_at(4);"));
+    assert!(src.contains("\"let\" => ::core::option::Option::Some(KW_LET),"));
+}
+
+#[test]
+fn codegen_fixture() {
+    use crate::codegen::generate;
+
+    /* The fixture is compiled by the doctest of `generate` */
+    let words = ["fn", "let", "self", "Self", "->", "+", "", "ñ", "a-b", "a_b", "builder", "1st"];
+    let mut out = Vec::new();
+    generate(&mut out, &words, &Default::default()).unwrap();
+    assert_eq!(String::from_utf8(out).unwrap(), include_str!("codegen/keywords.rs"));
+}
+
+#[test]
+fn snapshot_fingerprint() {
+    use crate::snapshot::Snapshot;
+
+    let mut interner = StringInterner::from_lines(["one", "two", "three"]);
+    let snapshot = interner.snapshot();
+    assert_eq!(snapshot.fingerprint(), interner.fingerprint());
+    assert!(snapshot.is_compatible(&interner));
+
+    let mut buf = Vec::new();
+    snapshot.save(&mut buf).unwrap();
+    let loaded = Snapshot::load(&buf[..]).unwrap();
+    assert_eq!(loaded, snapshot);
+
+    /* Corrupt one byte of the last value */
+    let last = buf.len() - 1;
+    buf[last] = b'X';
+    let err = Snapshot::load(&buf[..]).unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+
+    /* The same strings in a different order get different symbols */
+    let reordered = StringInterner::from_lines(["two", "one", "three"]);
+    assert_ne!(reordered.fingerprint(), interner.fingerprint());
+    assert!(!snapshot.is_compatible(&reordered));
+
+    interner.get_or_intern("four");
+    assert!(snapshot.is_compatible(&interner));
+    assert!(!interner.snapshot().is_compatible(&StringInterner::from_snapshot(&snapshot).unwrap()));
+}
+
+#[cfg(feature = "shm")]
+#[test]
+fn shared_memory() {
+    use crate::backend::shm::{ShmBackend, ShmReader};
+
+    let path = std::env::temp_dir().join(format!("interns-test-{}", std::process::id()));
+    let writer = ShmBackend::create(&path, 4, 16).unwrap();
+    let mut writer = Interner::<str, ShmBackend>::with_backend(writer);
+
+    let reader = unsafe { ShmReader::open(&path).unwrap() };
+    let mut reader = Interner::<str, ShmReader>::with_backend(reader);
+    assert_eq!(reader.refresh(), 0);
+
+    let a = writer.get_or_intern("alpha");
+    let b = writer.get_or_intern("beta");
+    assert_eq!(writer.get_or_intern("alpha"), a);
+
+    /* Not indexed yet, but resolvable */
+    assert_eq!(reader.get("beta"), None);
+    assert_eq!(reader.resolve(b), Some("beta"));
+
+    assert_eq!(reader.refresh(), 2);
+    assert_eq!(reader.get("alpha"), Some(a));
+
+    let c = writer.get_or_intern("gamma");
+    assert_eq!(reader.refresh(), 1);
+    assert_eq!(reader.get("gamma"), Some(c));
+
+    let full = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        writer.get_or_intern("does not fit");
+    }));
+    assert!(full.is_err());
+
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[cfg(feature = "shm")]
+#[test]
+fn shared_memory_untrusted() {
+    use std::io::{Seek, SeekFrom, Write};
+
+    use crate::backend::shm::{ShmBackend, ShmReader};
+
+    let path = std::env::temp_dir().join(format!("interns-test-untrusted-{}", std::process::id()));
+    let err = ShmBackend::create(&path, usize::MAX / 8, 64).err().unwrap();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+
+    let writer = ShmBackend::create(&path, 2, 8).unwrap();
+    let mut writer = Interner::<str, ShmBackend>::with_backend(writer);
+    let ab = writer.get_or_intern("ab");
+
+    let mut file = std::fs::OpenOptions::new().write(true).open(&path).unwrap();
+    let mut patch = |offset, bytes: &[u8]| {
+        file.seek(SeekFrom::Start(offset)).unwrap();
+        file.write_all(bytes).unwrap();
+        file.flush().unwrap();
+    };
+
+    /* The data and the entry count are written by another process */
+    patch(64, &[0xff]);
+    patch(24, &1000u64.to_le_bytes());
+    let reader = unsafe { ShmReader::open(&path).unwrap() };
+    assert_eq!(reader.published(), 2);
+    let reader = Interner::<str, ShmReader>::with_backend(reader);
+    assert_eq!(reader.resolve(ab), None);
+
+    /* A header whose size overflows */
+    patch(8, &(u64::MAX / 8).to_le_bytes());
+    let err = unsafe { ShmReader::open(&path) }.err().unwrap();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+
+    drop(writer);
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[cfg(feature = "bytes")]
+#[test]
+fn bytes_backend() {
+    use crate::backend::BytesBackend;
+
+    let mut interner = Interner::<[u8], BytesBackend>::with_backend(BytesBackend::with_chunk_size(8));
+    let a = interner.get_or_intern(&b"abc"[..]);
+    let b = interner.get_or_intern(&b"defg"[..]);
+    let big = interner.get_or_intern(&b"larger than a chunk"[..]);
+    assert_eq!(interner.get_or_intern(&b"abc"[..]), a);
+
+    let a_bytes = interner.resolve_bytes(a).unwrap();
+    let b_bytes = interner.resolve_bytes(b).unwrap();
+    assert_eq!(a_bytes, &b"abc"[..]);
+    assert_eq!(b_bytes, &b"defg"[..]);
+    /* Both values live on the same chunk */
+    assert_eq!(a_bytes.as_ptr().wrapping_add(3), b_bytes.as_ptr());
+    assert_eq!(interner.resolve(big), Some(&b"larger than a chunk"[..]));
+
+    drop(interner);
+    assert_eq!(a_bytes, &b"abc"[..]);
+}
+
+#[test]
+fn scoped_interner() {
+    use crate::scope;
+
+    let mut interner = StringInterner::new();
+    let a = interner.get_or_intern("a");
+
+    assert!(!scope::is_set());
+    assert_eq!(scope::intern("b"), None);
+
+    scope::with_resolver(&interner, || {
+        assert!(scope::is_set());
+        assert_eq!(scope::resolve(a, |s| s.map(String::from)).as_deref(), Some("a"));
+        assert_eq!(scope::get("a"), Some(a));
+        /* Read-only scope */
+        assert_eq!(scope::intern("b"), None);
+    });
+
+    let b = scope::with_interner(&mut interner, || {
+        let b = scope::intern("b").unwrap();
+        /* Nested scopes restore the previous one */
+        let other = StringInterner::new();
+        scope::with_resolver(&other, || assert_eq!(scope::get("b"), None));
+        scope::resolve(b, |s| {
+            assert_eq!(s, Some("b"));
+            assert_eq!(scope::intern("c"), None);
+        });
+        b
+    });
+    assert!(!scope::is_set());
+    assert_eq!(interner.get("b"), Some(b));
+
+    let panicked = std::panic::catch_unwind(|| {
+        scope::with_resolver(&StringInterner::new(), || panic!())
+    });
+    assert!(panicked.is_err());
+    assert!(!scope::is_set());
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn serde_symbol() {
+    use crate::backend::string::Symbol;
+    use crate::scope;
+
+    #[derive(serde::Serialize, serde::Deserialize, Debug, PartialEq)]
+    struct Item {
+        #[serde(with = "crate::serde_symbol")]
+        name: Symbol,
+    }
+
+    let mut interner = StringInterner::new();
+    let item = Item { name: interner.get_or_intern("item") };
+
+    assert!(serde_json::to_string(&item).is_err());
+    let json = scope::with_resolver(&interner, || serde_json::to_string(&item)).unwrap();
+    assert_eq!(json, r#"{"name":"item"}"#);
+
+    let back: Item = scope::with_resolver(&interner, || serde_json::from_str(&json)).unwrap();
+    assert_eq!(back, item);
+
+    let unknown = r#"{"name":"other"}"#;
+    assert!(scope::with_resolver(&interner, || serde_json::from_str::<Item>(unknown)).is_err());
+    let other: Item = scope::with_interner(&mut interner, || serde_json::from_str(unknown)).unwrap();
+    assert_eq!(interner.resolve(other.name), Some("other"));
+}
+
+#[test]
+fn absorb_snapshot() {
+    let mut old = StringInterner::new();
+    let old_syms = ["x", "y", "z"].map(|s| old.get_or_intern(s));
+
+    let mut live = StringInterner::new();
+    let live_z = live.get_or_intern("z");
+    let live_w = live.get_or_intern("w");
+
+    let remap = live.absorb_snapshot(old.snapshot());
+    assert_eq!(remap.len(), 3);
+    assert!(!remap.is_identity());
+    assert_eq!(remap.get(old_syms[2]), Some(live_z));
+    for (old_sym, new_sym) in remap.iter() {
+        assert_eq!(old.resolve(old_sym), live.resolve(new_sym));
+    }
+    assert_eq!(live.len(), 4);
+    assert_eq!(live.get("w"), Some(live_w));
+
+    /* Absorbing into an identical interner is the identity */
+    let mut copy = StringInterner::from_snapshot(&old.snapshot()).unwrap();
+    assert!(copy.absorb_snapshot(old.snapshot()).is_identity());
+}
+
+#[test]
+fn deterministic_hashing() {
+    use crate::hash::FixedState;
+    use std::hash::BuildHasher;
+
+    let state = FixedState::new();
+    /* These values must never change between versions */
+    assert_eq!(state.hash_one("hello"), 0x1f51bcc5dda20a69);
+    assert_eq!(state.hash_one(42u32), 0x6239e9d8fc75eb42);
+    assert_ne!(state.hash_one("hello"), state.hash_one("hello\0"));
+    assert_ne!(state.hash_one(1u32), FixedState::with_seed(1).hash_one(1u32));
+    assert_eq!(state.hash_one(7usize), state.hash_one(7u64));
+
+    let words = (0..1000).map(|i| format!("w{}", i % 300)).collect::<Vec<_>>();
+    let build = || {
+        let mut interner = DeterministicInterner::<str>::new();
+        for w in &words {
+            interner.get_or_intern(w.as_str());
+        }
+        interner
+    };
+    let (a, b) = (build(), build());
+    assert!(a.iter().eq(b.iter()));
+    assert_eq!(a.fingerprint(), b.fingerprint());
+}
+
+#[test]
+fn reverse_index() {
+    let words = ["delta", "alpha", "charlie", "alpha", "bravo"];
+    let interner = StringInterner::from_lines(words);
+
+    assert_eq!(interner.to_vec(), ["delta", "alpha", "charlie", "bravo"]);
+
+    let map = interner.to_lookup_map();
+    assert_eq!(map.len(), 4);
+    for (value, sym) in &map {
+        assert_eq!(interner.resolve(*sym), Some(value.as_str()));
+    }
+
+    let mut slices = Interner::<[u8]>::new();
+    slices.get_or_intern(&[2, 2][..]);
+    slices.get_or_intern(&[1][..]);
+    assert_eq!(slices.to_vec(), [vec![2, 2], vec![1]]);
+}
+
+#[cfg(feature = "zstd")]
+#[test]
+fn compressed_snapshot() {
+    use crate::snapshot::Snapshot;
+
+    let interner = StringInterner::from_lines((0..5000).map(|i| format!("std::module_{}::Item{}", i % 50, i)));
+    let snapshot = interner.snapshot();
+
+    let mut plain = Vec::new();
+    snapshot.save(&mut plain).unwrap();
+    let mut compressed = Vec::new();
+    snapshot.save_compressed(&mut compressed, 3).unwrap();
+    assert!(compressed.len() * 4 < plain.len());
+
+    assert_eq!(Snapshot::load_compressed(&compressed[..]).unwrap(), snapshot);
+    assert!(Snapshot::load_compressed(&plain[..]).is_err());
+}
+
+#[test]
+fn flatbuffer_export() {
+    let interner = StringInterner::from_lines(["", "a", "abc", "abcd", "ñ"]);
+    let mut buf = Vec::new();
+    let order = interner.write_flatbuffer(&mut buf).unwrap();
+
+    /* Minimal FlatBuffers reader */
+    let u32_at = |pos: usize| u32::from_le_bytes(buf[pos..pos + 4].try_into().unwrap()) as usize;
+    let u16_at = |pos: usize| u16::from_le_bytes(buf[pos..pos + 2].try_into().unwrap()) as usize;
+
+    let table = u32_at(0);
+    let vtable = table - i32::from_le_bytes(buf[table..table + 4].try_into().unwrap()) as usize;
+    assert!(u16_at(vtable) >= 6);
+    let field = table + u16_at(vtable + 4);
+    let vector = field + u32_at(field);
+    assert_eq!(u32_at(vector), order.len());
+
+    for (i, sym) in order.iter().enumerate() {
+        let slot = vector + 4 + i * 4;
+        let string = slot + u32_at(slot);
+        assert_eq!(string % 4, 0);
+        let len = u32_at(string);
+        let bytes = &buf[string + 4..string + 4 + len];
+        assert_eq!(buf[string + 4 + len], 0);
+        assert_eq!(interner.resolve(*sym), std::str::from_utf8(bytes).ok());
+    }
+    assert_eq!(buf.len() % 4, 0);
+}
+
+#[test]
+#[cfg(feature = "sqlite")]
+fn sqlite_backend() {
+    use crate::backend::sqlite::{SqliteBackend, SqliteLruInterner, Symbol};
+
+    let path = std::env::temp_dir().join(format!("interns-test-{}.db", std::process::id()));
+    let _ = std::fs::remove_file(&path);
+
+    let mut interner = Interner::<str, SqliteBackend>::open_sqlite(&path).unwrap();
+    let a = interner.get_or_intern("a");
+    let b = interner.get_or_intern("b");
+    assert_eq!(interner.get_or_intern("a"), a);
+    assert_ne!(a, b);
+    drop(interner);
+
+    /* Other rows can reference the symbols */
+    let backend = SqliteBackend::open(&path).unwrap();
+    let id: i64 = backend
+        .connection()
+        .query_row("SELECT id FROM interns WHERE value = 'b'", (), |row| row.get(0))
+        .unwrap();
+    assert_eq!(Symbol::from_id(id), b);
+
+    let mut interner = Interner::<str, SqliteBackend>::from_sqlite(backend);
+    assert_eq!(interner.len(), 2);
+    assert_eq!(interner.get("a"), Some(a));
+    assert_eq!(interner.resolve(b), Some("b"));
+    let c = interner.get_or_intern("c");
+    assert!(c != a && c != b);
+    assert_eq!(interner.resolve(Symbol::from_id(-1)), None);
+    drop(interner);
+
+    /* Only the recently used values are kept in memory */
+    let mut lru = SqliteLruInterner::open(&path, 2).unwrap();
+    assert_eq!(lru.cached(), 0);
+    assert_eq!(lru.get("a").unwrap(), Some(a));
+    assert_eq!(lru.get_or_intern("c").unwrap(), c);
+    let d = lru.get_or_intern("d").unwrap();
+    assert_eq!(lru.cached(), 2);
+    for (sym, value) in [(a, "a"), (b, "b"), (c, "c"), (d, "d"), (a, "a")] {
+        assert_eq!(lru.resolve(sym).unwrap(), Some(value));
+        assert_eq!(lru.get_or_intern(value).unwrap(), sym);
+    }
+    assert_eq!(lru.cached(), 2);
+    assert_eq!(lru.get("e").unwrap(), None);
+    assert_eq!(lru.resolve(Symbol::from_id(-1)).unwrap(), None);
+    drop(lru);
+
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn delta_snapshots() {
+    use crate::snapshot::Snapshot;
+
+    let mut interner = StringInterner::from_lines(["a", ""]);
+    let mut file = Vec::new();
+    interner.snapshot().save(&mut file).unwrap();
+    let base_len = file.len();
+
+    /* The empty string shares its offset with the next value */
+    let mut checkpoint = interner.checkpoint();
+    assert_eq!(checkpoint.len(), 2);
+    checkpoint = interner.save_delta(checkpoint, &mut file).unwrap();
+    for word in ["b", "a", "cd"] {
+        interner.get_or_intern(word);
+        checkpoint = interner.save_delta(checkpoint, &mut file).unwrap();
+    }
+    assert_eq!(checkpoint, interner.checkpoint());
+
+    let snapshot = Snapshot::load(&file[..base_len]).unwrap();
+    let mut restored = StringInterner::from_snapshot(&snapshot).unwrap();
+    let mut deltas = &file[base_len..];
+
+    /* Deltas must be applied in order */
+    let mut other = StringInterner::from_snapshot(&snapshot).unwrap();
+    let mut rest = deltas;
+    other.apply_delta(&mut rest).unwrap();
+    other.apply_delta(rest).unwrap();
+    assert!(other.apply_delta(rest).is_err());
+
+    while !deltas.is_empty() {
+        restored.apply_delta(&mut deltas).unwrap();
+    }
+    assert_eq!(restored.checkpoint(), interner.checkpoint());
+    assert_eq!(restored.fingerprint(), interner.fingerprint());
+    assert_eq!(restored.get("cd"), interner.get("cd"));
+
+    /* A delta that fails half-way leaves the interner untouched */
+    let mut source = StringInterner::from_lines(["a"]);
+    let checkpoint = source.checkpoint();
+    source.get_or_intern("c");
+    source.get_or_intern("b");
+    let mut delta = Vec::new();
+    source.save_delta(checkpoint, &mut delta).unwrap();
+
+    let mut target = StringInterner::from_lines(["b"]);
+    let fingerprint = target.fingerprint();
+    assert!(target.apply_delta(&delta[..]).is_err());
+    assert_eq!(target.get("c"), None);
+    assert_eq!(target.checkpoint(), checkpoint);
+    assert_eq!(target.fingerprint(), fingerprint);
+    target.check_invariants().unwrap();
+
+    /* Validating interners find the new values by position */
+    let mut interner = StringInterner::with_backend(StringBackend::validating());
+    for value in ["x", "", "abc"] {
+        interner.get_or_intern(value);
+    }
+    let mut restored = StringInterner::from_snapshot(&interner.snapshot()).unwrap();
+    let checkpoint = interner.checkpoint();
+    interner.get_or_intern("y");
+    interner.get_or_intern("de");
+    let mut delta = Vec::new();
+    interner.save_delta(checkpoint, &mut delta).unwrap();
+    restored.apply_delta(&delta[..]).unwrap();
+    assert_eq!(restored.fingerprint(), interner.fingerprint());
+
+    /* Removals can't be saved on a delta */
+    let mut interner = StringInterner::from_lines(["a", "b", "c"]);
+    let checkpoint = interner.checkpoint();
+    interner.remove("a");
+    interner.get_or_intern("d");
+    let err = interner.save_delta(checkpoint, &mut Vec::new()).unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+
+    /* A new snapshot is needed, after which deltas work again */
+    let checkpoint = interner.checkpoint();
+    let mut restored = StringInterner::from_snapshot(&interner.snapshot()).unwrap();
+    interner.get_or_intern("e");
+    let mut delta = Vec::new();
+    interner.save_delta(checkpoint, &mut delta).unwrap();
+    restored.apply_delta(&delta[..]).unwrap();
+    assert_eq!(restored.get("d"), interner.get("d"));
+    assert_eq!(restored.get("e"), interner.get("e"));
+    assert_eq!(restored.fingerprint(), interner.fingerprint());
+}
+
+#[test]
+fn delta_after_compaction() {
+    let mut interner = StringInterner::from_lines(["a", "tmp", "b"]);
+
+    /* A retain that keeps everything doesn't invalidate the checkpoint */
+    let checkpoint = interner.checkpoint();
+    interner.retain(|_, _| true);
+    interner.get_or_intern("c");
+    assert!(interner.save_delta(checkpoint, &mut Vec::new()).is_ok());
+
+    let checkpoint = interner.checkpoint();
+    interner.retain(|_, s| s != "tmp");
+    let err = interner.save_delta(checkpoint, &mut Vec::new()).unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+
+    /* Compacting moves the values, even if there's nothing to reclaim */
+    let checkpoint = interner.checkpoint();
+    interner.compact();
+    interner.get_or_intern("d");
+    let err = interner.save_delta(checkpoint, &mut Vec::new()).unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+
+    let checkpoint = interner.checkpoint();
+    let mut restored = StringInterner::from_snapshot(&interner.snapshot()).unwrap();
+    interner.get_or_intern("e");
+    let mut delta = Vec::new();
+    interner.save_delta(checkpoint, &mut delta).unwrap();
+    restored.apply_delta(&delta[..]).unwrap();
+    assert_eq!(restored.fingerprint(), interner.fingerprint());
+}
+
+#[test]
+fn validating_backend() {
+    use crate::backend::string::Symbol;
+
+    let mut interner = StringInterner::with_backend(StringBackend::validating());
+    let abc = interner.get_or_intern("abc");
+    let empty = interner.get_or_intern("");
+    let de = interner.get_or_intern("de");
+    assert_eq!(interner.resolve(abc), Some("abc"));
+    assert_eq!(interner.resolve(empty), Some(""));
+    assert_eq!(interner.resolve(de), Some("de"));
+
+    for fabricated in [(0, 2), (1, 2), (3, 1), (0, 5), (5, 0)] {
+        let sym = Symbol::new_inlined(fabricated.0, fabricated.1);
+        assert_eq!(interner.resolve(sym), None);
+    }
+
+    /* Without validation, any slice of the buffer resolves */
+    let mut unchecked = StringInterner::new();
+    unchecked.get_or_intern("abc");
+    assert_eq!(unchecked.resolve(Symbol::new_inlined(1, 2)), Some("bc"));
+
+    /* Restored interners keep validating */
+    let mut restored = StringInterner::with_backend(StringBackend::validating());
+    restored.absorb_snapshot(interner.snapshot());
+    assert_eq!(restored.resolve(de), Some("de"));
+    assert_eq!(restored.resolve(Symbol::new_inlined(0, 2)), None);
+}
+
+#[test]
+fn fallible_api() {
+    use crate::backend::string::Symbol;
+
+    let mut interner = StringInterner::new();
+    let a = interner.try_get_or_intern("a").unwrap();
+    assert_eq!(interner.try_get_or_intern("a").unwrap(), a);
+    assert_eq!(interner.get_or_intern("a"), a);
+    assert_eq!(interner.try_resolve(a), Ok("a"));
+    assert_eq!(interner.try_resolve(Symbol::new_inlined(0, 4)), Err(ResolveError::InvalidSpan { offset: 0, len: 4 }));
+    assert_eq!(interner.try_resolve(Symbol::new_indexed(3)), Err(ResolveError::OutOfRange { index: 3, len: 0 }));
+
+    #[cfg(feature = "shm")]
+    {
+        use crate::backend::shm::ShmBackend;
+
+        let path = std::env::temp_dir().join(format!("interns-fallible-{}", std::process::id()));
+        let backend = ShmBackend::create(&path, 2, 4).unwrap();
+        let mut interner = Interner::<str, ShmBackend>::with_backend(backend);
+        interner.try_get_or_intern("abc").unwrap();
+        assert!(matches!(interner.try_get_or_intern("de"), Err(InternError::Full)));
+        interner.try_get_or_intern("d").unwrap();
+        assert!(matches!(interner.try_get_or_intern(""), Err(InternError::Full)));
+        assert_eq!(interner.len(), 2);
+        std::fs::remove_file(&path).unwrap();
+    }
+}
+
+#[test]
+#[cfg(feature = "huge-tests")]
+#[ignore = "needs more than 4 GiB of memory"]
+fn huge_string_buffer() {
+    const CHUNK: usize = 1 << 20;
+
+    let mut interner = StringInterner::new();
+    let chunk = |i: usize| {
+        let mut s = format!("{i:08}");
+        s.extend(core::iter::repeat_n('x', CHUNK - s.len()));
+        s
+    };
+    let n = (u32::MAX as usize).div_ceil(CHUNK) + 2;
+    let syms = (0..n).map(|i| interner.get_or_intern(&chunk(i))).collect::<Vec<_>>();
+
+    for (i, sym) in syms.iter().enumerate() {
+        let inlined = i * CHUNK <= u32::MAX as usize;
+        assert_eq!(sym.is_inlined(), inlined);
+        assert_eq!(interner.resolve(*sym), Some(chunk(i).as_str()));
+        assert_eq!(interner.get(&chunk(i)), Some(*sym));
+    }
+
+    let small = interner.get_or_intern("small");
+    assert!(!small.is_inlined());
+    assert_eq!(interner.resolve(small), Some("small"));
+}
+
+#[test]
+fn const_interners() {
+    use crate::backend::ArcBackend;
+    use crate::hash::FixedState;
+    use std::sync::Mutex;
+
+    static STRINGS: Mutex<DeterministicInterner<str>> =
+        Mutex::new(Interner::with_backend_and_hasher(StringBackend::new(), FixedState::new()));
+    static NUMBERS: Mutex<DeterministicInterner<u32>> =
+        Mutex::new(Interner::with_backend_and_hasher(VecBackend::new(), FixedState::new()));
+    static SHARED: Mutex<DeterministicInterner<str, ArcBackend<str>>> =
+        Mutex::new(Interner::with_backend_and_hasher(ArcBackend::new(), FixedState::new()));
+
+    let a = STRINGS.lock().unwrap().get_or_intern("a");
+    assert_eq!(STRINGS.lock().unwrap().resolve(a), Some("a"));
+    let n = NUMBERS.lock().unwrap().get_or_intern(&12);
+    assert_eq!(NUMBERS.lock().unwrap().resolve(n), Some(&12));
+    let s = SHARED.lock().unwrap().get_or_intern("s");
+    assert_eq!(SHARED.lock().unwrap().resolve(s), Some("s"));
+}
+
+#[test]
+fn invariants() {
+    let mut interner = StringInterner::with_backend(StringBackend::validating());
+    for word in ["a", "", "bc", "a", "def"] {
+        interner.get_or_intern(word);
+        assert_eq!(interner.check_invariants(), Ok(()));
+    }
+
+    /* A broken backend */
+    struct Broken(Vec<String>);
+    impl Backend<str> for Broken {
+        type Symbol = usize;
+        fn get(&self, sym: usize) -> Option<&str> {
+            self.0.get(sym).map(String::as_str)
+        }
+    }
+    impl Internable<str, Broken> for str {
+        fn intern_into(&self, b: &mut Broken) -> usize {
+            b.0.push(self.to_owned());
+            /* Returns the wrong symbol */
+            0
+        }
+    }
+
+    let mut interner = Interner::<str, Broken>::with_backend(Broken(Vec::new()));
+    interner.get_or_intern("a");
+    let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        interner.get_or_intern("b");
+    }));
+    if cfg!(feature = "debug-invariants") {
+        assert!(result.is_err());
+    } else {
+        assert!(interner.check_invariants().is_err());
+    }
+}
+
+#[test]
+#[cfg(feature = "zeroize")]
+fn zeroize() {
+    let mut interner = StringInterner::new();
+    let secret = interner.get_or_intern("secret token");
+    for i in 0..100 {
+        interner.get_or_intern(&format!("token {i}"));
+    }
+    assert_eq!(interner.resolve(secret), Some("secret token"));
+
+    interner.zeroize();
+    assert!(interner.is_empty());
+    assert_eq!(interner.resolve(secret), None);
+    assert_eq!(interner.check_invariants(), Ok(()));
+
+    let other = interner.get_or_intern("other");
+    assert_eq!(interner.resolve(other), Some("other"));
+
+    let mut numbers = Interner::<u64>::new();
+    numbers.get_or_intern(&1234);
+    numbers.zeroize();
+    assert_eq!(numbers.get(&1234), None);
+}
+
+#[test]
+fn default_hasher() {
+    use crate::hash::DefaultHashBuilder;
+
+    let mut interner: Interner<str, StringBackend, DefaultHashBuilder> = StringInterner::new();
+    let a = interner.get_or_intern("a");
+    assert_eq!(interner.get("a"), Some(a));
+
+    #[cfg(feature = "foldhash")]
+    let _: Interner<str, StringBackend, foldhash::fast::RandomState> = interner;
+    #[cfg(all(feature = "ahash", not(feature = "foldhash")))]
+    let _: Interner<str, StringBackend, ahash::RandomState> = interner;
+    #[cfg(all(feature = "fxhash", not(any(feature = "foldhash", feature = "ahash"))))]
+    let _: Interner<str, StringBackend, rustc_hash::FxBuildHasher> = interner;
+    #[cfg(not(any(feature = "foldhash", feature = "ahash", feature = "fxhash")))]
+    let _: Interner<str, StringBackend, std::hash::RandomState> = interner;
+}
+
+#[test]
+fn symbol_width() {
+    use crate::backend::SymbolRepr;
+    use core::mem::size_of;
+
+    type Sym<T> = <VecBackend<T> as Backend<T>>::Symbol;
+    type Span<T> = <VecBackend<T> as Backend<[T]>>::Symbol;
+    type ArcSym = <ArcBackend<str> as Backend<str>>::Symbol;
+    assert_eq!(size_of::<Sym<u8>>(), size_of::<SymbolRepr>());
+    assert_eq!(size_of::<Span<u8>>(), 2 * size_of::<SymbolRepr>());
+    assert_eq!(size_of::<ArcSym>(), size_of::<SymbolRepr>());
+
+    #[cfg(all(feature = "symbol-u16", not(any(feature = "symbol-u32", feature = "symbol-u64"))))]
+    {
+        let mut interner = Interner::<u32>::new();
+        for i in 0..=u16::MAX as u32 {
+            interner.try_get_or_intern(&i).unwrap();
+        }
+        assert!(matches!(interner.try_get_or_intern(&u32::MAX), Err(InternError::Full)));
+        assert_eq!(interner.len(), u16::MAX as usize + 1);
+    }
+}
+
+#[test]
+fn span_backend() {
+    use crate::backend::{SpanBackend, SpanRepr};
+
+    /* A string of UTF-16 code units, stored in native endian */
+    #[derive(Debug, Hash, PartialEq, Eq)]
+    #[repr(transparent)]
+    struct Utf16([u8]);
+
+    impl Utf16 {
+        fn new(bytes: &[u8]) -> &Utf16 {
+            unsafe { &*(bytes as *const [u8] as *const Utf16) }
+        }
+        fn decode(&self) -> String {
+            let units = self.0.chunks(2).map(|c| u16::from_ne_bytes([c[0], c[1]]));
+            char::decode_utf16(units).map(Result::unwrap).collect()
+        }
+    }
+
+    struct Utf16Repr;
+    impl SpanRepr for Utf16Repr {
+        type Target = Utf16;
+        fn encode(value: &Utf16, buf: &mut Vec<u8>) {
+            buf.extend_from_slice(&value.0);
+        }
+        fn decode(bytes: &[u8]) -> Option<&Utf16> {
+            bytes.len().is_multiple_of(2).then(|| Utf16::new(bytes))
+        }
+    }
+
+    let encode = |s: &str| s.encode_utf16().flat_map(u16::to_ne_bytes).collect::<Vec<u8>>();
+    let hello = encode("hello");
+    let world = encode("wörld");
+
+    let mut interner = Interner::<Utf16, SpanBackend<Utf16Repr>>::new();
+    let a = interner.get_or_intern(Utf16::new(&hello));
+    let b = interner.get_or_intern(Utf16::new(&world));
+    assert_eq!(interner.get_or_intern(Utf16::new(&hello)), a);
+    assert_ne!(a, b);
+    assert_eq!(interner.resolve(a).unwrap().decode(), "hello");
+    assert_eq!(interner.resolve(b).unwrap().decode(), "wörld");
+    assert_eq!(interner.get(Utf16::new(&world)), Some(b));
+
+    /* The symbols are dense, and can be listed */
+    let empty = interner.try_get_or_intern(Utf16::new(&[])).unwrap();
+    assert_eq!(interner.index_of(b), Some(1));
+    assert_eq!(interner.index_of(empty), Some(2));
+    assert_eq!(interner.symbol_at(0), Some(a));
+    assert_eq!(interner.symbol_at(3), None);
+    assert_eq!(interner.rebuild_index(), 0);
+    assert_eq!(interner.get(Utf16::new(&hello)), Some(a));
+}
+
+#[test]
+fn mixed_interner() {
+    use crate::mixed::{Kind, MixedInterner, MixedRef};
+
+    let mut interner = MixedInterner::with_hasher(crate::hash::FixedState::new());
+    let s = interner.get_or_intern_str("abc");
+    let b = interner.get_or_intern_bytes(b"abc");
+    let blob = interner.get_or_intern_bytes(&[0xff, 0xfe]);
+
+    /* Same storage, different symbols */
+    assert_ne!(s, b);
+    assert_eq!(interner.len(), 2);
+    assert_eq!(s.kind(), Kind::Str);
+    assert_eq!(b.kind(), Kind::Bytes);
+
+    assert_eq!(interner.resolve(s), Some(MixedRef::Str("abc")));
+    assert_eq!(interner.resolve(b), Some(MixedRef::Bytes(b"abc")));
+    assert_eq!(interner.resolve_bytes(s), Some(&b"abc"[..]));
+    assert_eq!(interner.resolve_str(blob), None);
+    assert_eq!(interner.resolve(blob).unwrap().as_bytes(), [0xff, 0xfe]);
+
+    assert_eq!(interner.get_str("abc"), Some(s));
+    assert_eq!(interner.get_bytes(b"abc"), Some(b));
+    assert_eq!(interner.get_str("xyz"), None);
+    assert_eq!(interner.get_or_intern_str("abc"), s);
+
+    /* A string symbol of another interner may point to invalid UTF-8 */
+    let mut other = MixedInterner::new();
+    let foreign = other.get_or_intern_str("xyz");
+    let mut blobs = MixedInterner::new();
+    blobs.get_or_intern_bytes(&[0xff, 0xfe, 0xfd]);
+    assert_eq!(blobs.resolve(foreign), None);
+    assert_eq!(blobs.resolve_str(foreign), None);
+    assert_eq!(blobs.resolve_bytes(foreign), Some(&[0xff, 0xfe, 0xfd][..]));
+}
+
+#[test]
+fn display_symbols() {
+    use crate::backend::string::Symbol;
+
+    let mut interner = StringInterner::new();
+    let foo = interner.get_or_intern("foo");
+    let fake = Symbol::new_inlined(10, 2);
+
+    assert_eq!(foo.to_string(), "<symbol 0:3>");
+    crate::scope::with_resolver(&interner, || {
+        assert_eq!(format!("{foo}"), "foo");
+        assert_eq!(format!("[{foo:>5}]"), "[  foo]");
+        assert_eq!(fake.to_string(), "<symbol 10:2>");
+    });
+    let bar = crate::scope::with_interner(&mut interner, || {
+        let bar = crate::scope::intern("bar").unwrap();
+        assert_eq!(format!("{foo}{bar}"), "foobar");
+        bar
+    });
+    assert_eq!(bar.to_string(), "<symbol 3:3>");
+}
+
+#[test]
+fn resolve_errors() {
+    use crate::backend::string::Symbol;
+
+    let mut validating = StringInterner::with_backend(StringBackend::validating());
+    validating.get_or_intern("abc");
+    let fake = Symbol::new_inlined(1, 1);
+    assert_eq!(validating.try_resolve(fake), Err(ResolveError::InvalidSpan { offset: 1, len: 1 }));
+
+    let mut numbers = Interner::<u32>::new();
+    let mut other = Interner::<u32>::new();
+    for i in 0..3 {
+        other.get_or_intern(&i);
+    }
+    let sym = other.get_or_intern(&7);
+    numbers.get_or_intern(&7);
+    assert_eq!(numbers.try_resolve(sym), Err(ResolveError::OutOfRange { index: 3, len: 1 }));
+
+    let mut slices = Interner::<[u8]>::new();
+    let span = other_span(&mut Interner::<[u8]>::new());
+    slices.get_or_intern(&[1][..]);
+    assert_eq!(slices.try_resolve(span), Err(ResolveError::InvalidSpan { offset: 0, len: 3 }));
+
+    fn other_span(interner: &mut Interner<[u8]>) -> <VecBackend<u8> as Backend<[u8]>>::Symbol {
+        interner.get_or_intern(&[1, 2, 3][..])
+    }
+
+    let err = ResolveError::StaleGeneration { generation: 1, current: 2 };
+    assert_eq!(err.to_string(), "stale symbol of generation 1 (current is 2)");
+}
+
+#[test]
+fn equivalent_lookup() {
+    use core::hash::Hasher;
+
+    let mut owned = Interner::<String>::new();
+    let hello = owned.get_or_intern("hello");
+    assert_eq!(owned.get("hello"), Some(hello));
+    assert!(owned.contains("hello"));
+    assert!(!owned.contains("world"));
+
+    struct Caseless<'a>(&'a str);
+
+    impl Hash for Caseless<'_> {
+        fn hash<H: Hasher>(&self, state: &mut H) {
+            self.0.to_ascii_lowercase().hash(state);
+        }
+    }
+
+    impl Equivalent<str> for Caseless<'_> {
+        fn equivalent(&self, key: &str) -> bool {
+            self.0.eq_ignore_ascii_case(key)
+        }
+    }
+
+    let mut interner = StringInterner::new();
+    let select = interner.get_or_intern("select");
+    assert_eq!(interner.get_equivalent(&Caseless("SeLeCt")), Some(select));
+    assert!(interner.contains_equivalent(&Caseless("SELECT")));
+    assert!(!interner.contains_equivalent(&Caseless("from")));
+}
+
+#[test]
+fn symbol_map() {
+    use crate::collections::SymbolMap;
+
+    let mut interner = Interner::<u32>::new();
+    let syms: Vec<_> = (0..5).map(|i| interner.get_or_intern(&(i * 10))).collect();
+
+    let mut counts = SymbolMap::new();
+    assert!(counts.is_empty());
+    assert_eq!(counts.insert(syms[3], 1), None);
+    assert_eq!(counts.insert(syms[1], 2), None);
+    assert_eq!(counts.insert(syms[3], 3), Some(1));
+    assert_eq!(counts.len(), 2);
+
+    counts[syms[1]] += 10;
+    assert_eq!(counts.get(syms[1]), Some(&12));
+    assert_eq!(counts.get(syms[0]), None);
+    assert_eq!(counts.get(syms[4]), None);
+    assert!(counts.contains_key(syms[3]));
+
+    let pairs: Vec<_> = counts.iter().map(|(sym, v)| (sym, *v)).collect();
+    assert_eq!(pairs, [(syms[1], 12), (syms[3], 3)]);
+
+    assert_eq!(counts.remove(syms[1]), Some(12));
+    assert_eq!(counts.remove(syms[1]), None);
+    assert_eq!(counts.len(), 1);
+
+    let other: SymbolMap<_, _> = [(syms[3], 3)].into_iter().collect();
+    assert_eq!(counts, other);
+}
+
+#[test]
+fn symbol_set() {
+    use crate::collections::SymbolSet;
+
+    let mut interner = Interner::<u32>::new();
+    let syms: Vec<_> = (0..200).map(|i| interner.get_or_intern(&i)).collect();
+
+    let mut exported = SymbolSet::new();
+    assert!(exported.insert(syms[3]));
+    assert!(!exported.insert(syms[3]));
+    assert!(exported.insert(syms[130]));
+    assert!(exported.insert(syms[64]));
+    assert_eq!(exported.len(), 3);
+    assert!(exported.contains(syms[64]));
+    assert!(!exported.contains(syms[65]));
+    assert!(!exported.contains(syms[199]));
+    assert_eq!(exported.iter().collect::<Vec<_>>(), [syms[3], syms[64], syms[130]]);
+
+    let in_scope: SymbolSet<_> = [syms[3], syms[4]].into_iter().collect();
+    assert_eq!((&exported & &in_scope).iter().collect::<Vec<_>>(), [syms[3]]);
+    assert_eq!((&exported | &in_scope).len(), 4);
+    assert_eq!((&exported - &in_scope).iter().collect::<Vec<_>>(), [syms[64], syms[130]]);
+    assert!((&exported & &in_scope).is_subset(&in_scope));
+    assert!(!exported.is_disjoint(&in_scope));
+
+    assert!(exported.remove(syms[130]));
+    assert!(!exported.remove(syms[130]));
+    assert!(!exported.remove(syms[199]));
+    let small: SymbolSet<_> = [syms[3], syms[64]].into_iter().collect();
+    assert_eq!(exported, small);
+
+    exported.clear();
+    assert!(exported.is_empty());
+}
+
+#[test]
+fn secondary_vec() {
+    use crate::collections::{Entry, SecondaryVec};
+
+    let mut interner = Interner::<u32>::new();
+    let mut types: SecondaryVec<_, Vec<&str>> = SecondaryVec::new();
+
+    let x = interner.get_or_intern(&1);
+    let y = interner.get_or_intern(&2);
+    types.entry(y).or_default().push("int");
+    types.entry(y).or_default().push("float");
+    types.entry(x).or_insert_with(|| vec!["bool"]);
+    assert_eq!(types[y], ["int", "float"]);
+    assert_eq!(types.len(), 2);
+
+    types.entry(x).and_modify(|t| t.clear()).or_default();
+    assert!(types[x].is_empty());
+
+    let z = interner.get_or_intern(&3);
+    match types.entry(z) {
+        Entry::Vacant(e) => assert_eq!(e.key(), z),
+        Entry::Occupied(_) => panic!("z has no value"),
+    }
+    match types.entry(x) {
+        Entry::Occupied(e) => assert!(e.remove().is_empty()),
+        Entry::Vacant(_) => panic!("x has a value"),
+    }
+    assert_eq!(types.len(), 1);
+    assert_eq!(types.entry(z).or_insert_with_key(|sym| vec![if sym == z { "z" } else { "?" }]), &["z"]);
+    assert_eq!(types.len(), 2);
+}
+
+#[test]
+fn longest_match() {
+    let mut interner = StringInterner::new();
+    assert_eq!(interner.longest_match("abc"), None);
+
+    let lt = interner.get_or_intern("<");
+    let shl = interner.get_or_intern("<<");
+    let shl_assign = interner.get_or_intern("<<=");
+    let arrow = interner.get_or_intern("->");
+    let ene = interner.get_or_intern("ñ");
+
+    assert_eq!(interner.longest_match("<<= 1"), Some((shl_assign, 3)));
+    assert_eq!(interner.longest_match("<<"), Some((shl, 2)));
+    assert_eq!(interner.longest_match("<a"), Some((lt, 1)));
+    assert_eq!(interner.longest_match("-> x"), Some((arrow, 2)));
+    assert_eq!(interner.longest_match("-"), None);
+    assert_eq!(interner.longest_match("ñu"), Some((ene, 2)));
+    assert_eq!(interner.longest_match(""), None);
+
+    let empty = interner.get_or_intern("");
+    assert_eq!(interner.longest_match("xyz"), Some((empty, 0)));
+}
+
+#[test]
+fn intern_split() {
+    let mut interner = StringInterner::new();
+
+    let words = interner.intern_split_whitespace("  the cat\tand\nthe hat ");
+    let resolved: Vec<_> = words.iter().map(|&sym| interner.resolve(sym).unwrap()).collect();
+    assert_eq!(resolved, ["the", "cat", "and", "the", "hat"]);
+    assert_eq!(interner.len(), 4);
+    assert!(interner.intern_split_whitespace(" \t ").is_empty());
+
+    let parts = interner.intern_split("a/b//cat", |c| c == '/');
+    let resolved: Vec<_> = parts.iter().map(|&sym| interner.resolve(sym).unwrap()).collect();
+    assert_eq!(resolved, ["a", "b", "", "cat"]);
+    assert_eq!(parts[3], words[1]);
+    assert_eq!(interner.intern_split("", |c| c == '/').len(), 1);
+}
+
+#[test]
+fn intern_lines() {
+    let mut interner = StringInterner::new();
+    let input = "error: disk full\n\nwarning: low memory\r\nerror: disk full\nlast";
+    let lines = interner.intern_lines(input.as_bytes()).unwrap();
+    let resolved: Vec<_> = lines.iter().map(|&sym| interner.resolve(sym).unwrap()).collect();
+    assert_eq!(resolved, ["error: disk full", "", "warning: low memory", "error: disk full", "last"]);
+    assert_eq!(interner.len(), 4);
+
+    assert!(interner.intern_lines(&b""[..]).unwrap().is_empty());
+    assert!(interner.intern_lines(&b"\xff\n"[..]).is_err());
+}
+
+#[test]
+fn hashcons() {
+    use crate::hashcons::{Expr, ExprInterner};
+
+    let mut interner = ExprInterner::<char>::new();
+    let x = interner.get_or_intern(&Expr::leaf('x'));
+    let one = interner.get_or_intern(&Expr::leaf('1'));
+    let add = interner.get_or_intern(&Expr::new('+', [x, one]));
+    let add2 = interner.get_or_intern(&Expr::new('+', vec![x, one]));
+    let mul = interner.get_or_intern(&Expr::new('*', [add, add2]));
+    assert_eq!(add, add2);
+    assert_eq!(interner.len(), 4);
+
+    assert_eq!(interner.display_tree(mul).to_string(), "(* (+ x 1) (+ x 1))");
+    assert_eq!(interner.display_tree(x).to_string(), "x");
+    assert_eq!(interner.depth(mul), Some(3));
+    assert_eq!(interner.dag_size(mul), Some(4));
+    assert_eq!(interner.dag_size(add), Some(3));
+
+    let mut folds = 0;
+    let leaves = interner.fold(mul, |node, children: &[usize]| {
+        folds += 1;
+        if node.is_leaf() { 1 } else { children.iter().sum() }
+    });
+    assert_eq!(leaves, Some(4));
+    assert_eq!(folds, 4);
+
+    /* Symbols of another interner don't resolve, or point to a different node */
+    let mut other = ExprInterner::<char>::new();
+    let n = other.get_or_intern(&Expr::new('n', [one]));
+    assert_eq!(other.depth(n), None);
+    assert_eq!(other.display_tree(n).to_string(), "(n <unknown>)");
+
+    let looping = other.get_or_intern(&Expr::new('l', [one]));
+    assert_eq!(looping, one);
+    assert_eq!(other.fold(n, |_, _: &[()]| ()), None);
+    assert_eq!(other.display_tree(n).to_string(), "(n (l <cycle>))");
+}
+
+#[test]
+fn hashcons_deep() {
+    use crate::hashcons::{Expr, ExprInterner};
+
+    /* Deep enough to overflow the stack if folding recursed */
+    let mut interner = ExprInterner::<u8>::new();
+    let mut node = interner.get_or_intern(&Expr::leaf(0));
+    for _ in 0..200_000 {
+        node = interner.get_or_intern(&Expr::new(1, [node, node]));
+    }
+    assert_eq!(interner.depth(node), Some(200_001));
+    assert_eq!(interner.dag_size(node), Some(200_001));
+
+    let mut chain = interner.get_or_intern(&Expr::leaf(0));
+    for _ in 0..200_000 {
+        chain = interner.get_or_intern(&Expr::new(2, [chain]));
+    }
+    let printed = interner.pretty(chain).share(true).to_string();
+    assert_eq!(printed.len(), 200_000 * 4 + 1);
+    assert!(printed.starts_with("(2 (2 ") && printed.trim_end_matches(')').ends_with("(2 0"));
+}
+
+#[test]
+fn hashcons_pretty() {
+    use crate::hashcons::{Expr, ExprInterner};
+
+    let mut interner = ExprInterner::<char>::new();
+    let x = interner.get_or_intern(&Expr::leaf('x'));
+    let y = interner.get_or_intern(&Expr::leaf('y'));
+    let add = interner.get_or_intern(&Expr::new('+', [x, y]));
+    let neg = interner.get_or_intern(&Expr::new('-', [add]));
+    let mul = interner.get_or_intern(&Expr::new('*', [add, neg, x]));
+
+    assert_eq!(interner.pretty(mul).to_string(), interner.display_tree(mul).to_string());
+    assert_eq!(interner.pretty(mul).share(true).to_string(), "(* #1=(+ x y) (- #1#) x)");
+    assert_eq!(interner.pretty(neg).share(true).to_string(), "(- (+ x y))");
+    assert_eq!(interner.pretty(add).indent(4).to_string(), "(+\n    x\n    y)");
+    assert_eq!(
+        interner.pretty(mul).indent(1).share(true).to_string(),
+        "(*\n #1=(+\n  x\n  y)\n (-\n  #1#)\n x)"
+    );
+
+    let upper = interner.pretty(add).labels(|node, f| write!(f, "{}", node.op.to_ascii_uppercase()));
+    assert_eq!(upper.to_string(), "(+ X Y)");
+
+    /* Unknown symbols and cycles */
+    let mut other = ExprInterner::<char>::new();
+    let n = other.get_or_intern(&Expr::new('n', [x]));
+    let m = other.get_or_intern(&Expr::new('m', [n, n]));
+    assert_eq!(other.pretty(m).share(true).to_string(), "(m #1=(n <cycle>) #1#)");
+    assert_eq!(other.pretty(mul).to_string(), "<unknown>");
+}
+
+#[test]
+fn typed_interners() {
+    use crate::typed::TypedInterners;
+
+    let mut interners = TypedInterners::new();
+    assert_eq!(interners.get::<str, _>("x"), None);
+
+    let a = interners.get_or_intern::<str, _>("a");
+    let b = interners.get_or_intern::<str, _>("b");
+    let a2 = interners.get_or_intern::<str, _>("a");
+    let n = interners.get_or_intern::<u64, _>(&7);
+    let blob = interners.get_or_intern::<[u8], _>(&[1, 2, 3][..]);
+    assert_eq!(a, a2);
+    assert_ne!(a, b);
+    assert_eq!(interners.types(), 3);
+
+    assert_eq!(interners.resolve(a), Some("a"));
+    assert_eq!(interners.resolve(n), Some(&7));
+    assert_eq!(interners.resolve(blob), Some(&[1, 2, 3][..]));
+    assert_eq!(interners.get::<u64, _>(&7), Some(n));
+    assert_eq!(interners.get::<u64, _>(&8), None);
+    assert_eq!(interners.interner::<str>().map(Interner::len), Some(2));
+    assert!(interners.interner::<u8>().is_none());
+}
+
+#[test]
+#[cfg(feature = "unicode")]
+fn unicode_nfc() {
+    use std::borrow::Cow;
+    use crate::unicode::nfc;
+
+    assert!(matches!(nfc("plain"), Cow::Borrowed("plain")));
+    assert!(matches!(nfc("\u{e9}"), Cow::Borrowed(_)));
+    assert_eq!(nfc("e\u{301}"), "\u{e9}");
+
+    let mut interner = StringInterner::new();
+    /* Hangul syllable, as a single code point and as its jamo */
+    let composed = interner.get_or_intern_nfc("\u{d55c}");
+    let decomposed = interner.get_or_intern_nfc("\u{1112}\u{1161}\u{11ab}");
+    assert_eq!(composed, decomposed);
+    assert_eq!(interner.len(), 1);
+    assert_eq!(interner.get_nfc("\u{1112}\u{1161}\u{11ab}"), Some(composed));
+    assert_eq!(interner.get("\u{1112}\u{1161}\u{11ab}"), None);
+}
+
+#[test]
+fn normalized_interner() {
+    use std::borrow::Cow;
+    use crate::normalize::{AsciiLowercase, Lowercase, NormalizedInterner, Normalizer, Trim};
+
+    let mut interner = NormalizedInterner::new((Trim, Lowercase));
+    let a = interner.get_or_intern("  Ñandú\n");
+    assert_eq!(interner.get_or_intern("ñandú"), a);
+    assert_eq!(interner.get("ÑANDÚ "), Some(a));
+    assert!(!interner.contains("nandu"));
+    assert_eq!(interner.resolve(a), Some("ñandú"));
+    assert_eq!(interner.len(), 1);
+
+    assert!(matches!(AsciiLowercase.normalize("abc"), Cow::Borrowed(_)));
+    assert_eq!(AsciiLowercase.normalize("ÑAbc"), "Ñabc");
+    assert!(matches!((Trim, AsciiLowercase).normalize(" x "), Cow::Borrowed("x")));
+
+    let mut existing = StringInterner::new();
+    let upper = existing.get_or_intern("KEY");
+    let mut interner = NormalizedInterner::with_interner(existing, AsciiLowercase);
+    assert_ne!(interner.get_or_intern("KEY"), upper);
+    assert_eq!(interner.interner().get("KEY"), Some(upper));
+    assert_eq!(interner.into_interner().len(), 2);
+}
+
+#[test]
+#[cfg(feature = "unicode")]
+fn nfc_normalizer() {
+    use crate::normalize::{Lowercase, NormalizedInterner, Nfc};
+
+    let mut interner = NormalizedInterner::new((Nfc, Lowercase));
+    let a = interner.get_or_intern("E\u{301}TAT");
+    assert_eq!(interner.get("\u{e9}tat"), Some(a));
+}
+
+#[test]
+fn path_interner() {
+    use std::path::Path;
+    use crate::path::{PathInterner, PathOptions};
+
+    let mut interner = PathInterner::new();
+    let main = interner.get_or_intern("src/main.rs");
+    for alias in ["./src/main.rs", "src//main.rs", "src/./bin/../main.rs", "src/main.rs/"] {
+        assert_eq!(interner.get(alias), Some(main), "{alias}");
+    }
+    assert_eq!(interner.resolve(main), Some(Path::new("src/main.rs")));
+
+    let up = interner.get_or_intern("../a/../../b");
+    assert_eq!(interner.resolve(up), Some(Path::new("../../b")));
+    let root = interner.get_or_intern("/../etc/./");
+    assert_eq!(interner.resolve(root), Some(Path::new("/etc")));
+    let cur = interner.get_or_intern("a/..");
+    assert_eq!(interner.resolve(cur), Some(Path::new(".")));
+    assert_eq!(interner.len(), 4);
+
+    let mut raw = PathInterner::with_options(PathOptions::new().simplify(false));
+    assert_ne!(raw.get_or_intern("a/../b"), raw.get_or_intern("b"));
+
+    let mut windows = PathInterner::with_options(PathOptions::new().unify_separators(true));
+    assert_eq!(windows.get_or_intern("src\\main.rs"), windows.get_or_intern("src/main.rs"));
+
+    let dir = std::env::temp_dir().join(format!("interns-path-{}", std::process::id()));
+    std::fs::create_dir_all(dir.join("sub")).unwrap();
+    let mut canonical = PathInterner::with_options(PathOptions::new().canonicalize(true));
+    let a = canonical.get_or_intern(dir.join("sub/.."));
+    let b = canonical.get_or_intern(&dir);
+    assert_eq!(a, b);
+    assert_eq!(canonical.resolve(a), Some(&*std::fs::canonicalize(&dir).unwrap()));
+    let missing = canonical.get_or_intern(dir.join("missing/../x"));
+    assert_eq!(canonical.resolve(missing), Some(&*dir.join("x")));
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+#[cfg(feature = "url")]
+fn url_interner() {
+    use crate::url::UrlInterner;
+
+    let mut interner = UrlInterner::new();
+    let home = interner.get_or_intern("https://example.com/").unwrap();
+    for alias in [
+        "HTTPS://EXAMPLE.com",
+        "https://example.com:443/",
+        "https://example.com/a/../",
+        "https://ex%61mple.com/",
+    ] {
+        assert_eq!(interner.get(alias), Some(home), "{alias}");
+    }
+
+    let search = interner.get_or_intern("http://a.org/s?q=%7e%2f%e2%82%ac#%41").unwrap();
+    assert_eq!(interner.resolve(search), Some("http://a.org/s?q=~%2F%E2%82%AC#A"));
+    assert_eq!(interner.get("http://a.org:8080/s?q=~%2F%E2%82%AC#A"), None);
+    assert_eq!(interner.resolve_url(search).unwrap().host_str(), Some("a.org"));
+
+    assert!(interner.get_or_intern("not a url").is_err());
+    assert_eq!(interner.get("not a url"), None);
+    assert_eq!(interner.len(), 2);
+
+    /* Malformed escapes are kept as they are */
+    let signed = interner.get_or_intern("http://a/%+1").unwrap();
+    let escaped = interner.get_or_intern("http://a/%01").unwrap();
+    assert_ne!(signed, escaped);
+    assert_eq!(interner.resolve(signed), Some("http://a/%+1"));
+    let truncated = interner.get_or_intern("http://a/%zz%4").unwrap();
+    assert_eq!(interner.resolve(truncated), Some("http://a/%zz%4"));
+}
+
+#[test]
+#[cfg(feature = "presets")]
+fn http_preset() {
+    use crate::presets::http;
+
+    let mut interner = http::BUILDER.build();
+    assert_eq!(interner.get_or_intern("content-type"), http::CONTENT_TYPE);
+    assert_eq!(interner.get("etag"), Some(http::ETAG));
+    assert_eq!(http::lookup("www-authenticate"), Some(http::WWW_AUTHENTICATE));
+    assert_eq!(http::lookup("Host"), None);
+    assert_eq!(http::lookup_ignore_case("HOST"), Some(http::HOST));
+    assert_eq!(http::canonical(http::ETAG), Some("ETag"));
+    assert_eq!(http::canonical(http::SEC_WEBSOCKET_KEY), Some("Sec-WebSocket-Key"));
+
+    let custom = interner.get_or_intern("x-custom");
+    assert_eq!(http::canonical(custom), None);
+    assert_eq!(http::lookup("x-custom"), None);
+
+    for i in 0..interner.len() - 1 {
+        let sym = http::BUILDER.symbol_at(i);
+        let name = interner.resolve(sym).unwrap();
+        assert!(http::canonical(sym).unwrap().eq_ignore_ascii_case(name));
+        assert_eq!(http::lookup(name), Some(sym));
+    }
+}
+
+#[test]
+#[cfg(feature = "presets")]
+fn mime_preset() {
+    use crate::presets::mime;
+
+    let mut interner = mime::BUILDER.build();
+    assert_eq!(interner.get_or_intern("image/png"), mime::IMAGE_PNG);
+    assert_eq!(mime::lookup("text/plain"), Some(mime::TEXT_PLAIN));
+    assert_eq!(mime::from_extension("JPG"), Some(mime::IMAGE_JPEG));
+    assert_eq!(mime::from_extension("exe"), None);
+    assert_eq!(interner.resolve(mime::from_extension("woff2").unwrap()), Some("font/woff2"));
+    assert_ne!(interner.get_or_intern("application/x-custom"), mime::APPLICATION_ZIP);
+}
+
+#[test]
+#[cfg(feature = "presets")]
+fn keyword_presets() {
+    use crate::presets::{c, json, rust, sql};
+
+    let mut interner = rust::BUILDER.build();
+    assert_eq!(interner.get_or_intern("self"), rust::KW_SELF);
+    assert_eq!(interner.get_or_intern("Self"), rust::KW_SELF_TYPE);
+    assert_eq!(rust::lookup("async"), Some(rust::KW_ASYNC));
+    assert!(rust::is_reserved(rust::KW_ABSTRACT));
+    assert!(!rust::is_reserved(rust::KW_WHILE));
+    assert!(!rust::is_reserved(interner.get_or_intern("foo")));
+
+    let mut interner = c::BUILDER.build();
+    assert_eq!(interner.get_or_intern("_Thread_local"), c::KW__THREAD_LOCAL);
+    assert_eq!(c::lookup("fn"), None);
+
+    let mut interner = json::BUILDER.build();
+    assert_eq!(interner.get_or_intern("true"), json::TRUE);
+    assert_eq!(json::lookup("false"), Some(json::FALSE));
+
+    let mut interner = sql::BUILDER.build();
+    assert_eq!(interner.get_or_intern("WHERE"), sql::KW_WHERE);
+    assert_eq!(sql::lookup("where"), None);
+    assert_eq!(sql::lookup_ignore_case("Current_Date"), Some(sql::KW_CURRENT_DATE));
+}
+
+#[test]
+fn counting() {
+    use crate::counting::CountingInterner;
+
+    let mut interner = CountingInterner::<u32>::new();
+    for n in [5, 1, 5, 3, 1, 5, 7] {
+        interner.get_or_intern(&n);
+    }
+    let five = interner.get(&5).unwrap();
+    let one = interner.get(&1).unwrap();
+    let three = interner.get(&3).unwrap();
+    let seven = interner.get(&7).unwrap();
+    assert_eq!(interner.count(five), 3);
+    assert_eq!(interner.count(seven), 1);
+    assert_eq!(interner.total(), 7);
+    assert_eq!(interner.len(), 4);
+    assert_eq!(interner.most_common(3), [(five, 3), (one, 2), (three, 1)]);
+    assert_eq!(interner.most_common(10).len(), 4);
+    assert_eq!(interner.counts().collect::<Vec<_>>(), [(five, 3), (one, 2), (three, 1), (seven, 1)]);
+
+    interner.reset_counts();
+    assert_eq!(interner.count(five), 0);
+    assert!(interner.most_common(1).is_empty());
+    interner.get_or_intern(&7);
+    assert_eq!(interner.most_common(1), [(seven, 1)]);
+
+    let mut existing = StringInterner::new();
+    let a = existing.get_or_intern("a");
+    let mut interner = CountingInterner::with_interner(existing);
+    assert_eq!(interner.count(a), 0);
+    assert_eq!(interner.get_or_intern("a"), a);
+    assert_eq!(interner.count(a), 1);
+    assert_eq!(interner.into_interner().len(), 1);
+}
+
+#[test]
+fn savings() {
+    let mut interner = Interner::<u64>::new();
+    assert_eq!(interner.savings().requests, 0);
+    for i in 0..100 {
+        interner.get_or_intern(&(i % 4));
+    }
+    interner.get(&1);
+    let report = interner.savings();
+    assert_eq!(report.requests, 100);
+    assert_eq!(report.requested_bytes, 800);
+    assert_eq!(report.unique, 4);
+    assert_eq!(report.stored_bytes, 32);
+    assert!(report.table_bytes >= 4 * (size_of::<Symbol<u64>>() + 1));
+    assert_eq!(report.saved_bytes(), 800 - (32 + report.table_bytes as i128));
+    assert!(report.ratio() > 1.0);
+    assert!(report.to_string().starts_with("100 requests (800 bytes) for 4 unique values"));
+
+    let mut strings = Interner::<String>::new();
+    strings.get_or_intern("abc");
+    strings.get_or_intern("x");
+    assert_eq!(strings.savings().requested_bytes, 4);
+
+    let mut once = StringInterner::new();
+    once.get_or_intern("unique");
+    assert!(once.savings().saved_bytes() < 0);
+}
+
+#[test]
+fn lru_interner() {
+    use crate::lru::LruInterner;
+
+    let mut interner = LruInterner::<str>::new(3);
+    let a = interner.get_or_intern("a");
+    let b = interner.get_or_intern("b");
+    let c = interner.get_or_intern("c");
+    assert_eq!(interner.get_or_intern("a"), a);
+    assert!(interner.touch(b));
+    assert_eq!(interner.iter().collect::<Vec<_>>(), [b, a, c]);
+
+    let d = interner.get_or_intern("d");
+    assert_eq!(interner.len(), 3);
+    assert_eq!(interner.resolve(c), None);
+    assert_eq!(interner.get("c"), None);
+    /* "d" reuses the slot of "c" */
+    assert_eq!(d.index(), c.index());
+    assert_eq!(
+        interner.try_resolve(c),
+        Err(ResolveError::StaleGeneration { generation: 0, current: 1 })
+    );
+    assert_eq!(interner.resolve(d), Some("d"));
+
+    assert!(interner.remove(a));
+    assert!(!interner.remove(a));
+    assert!(!interner.touch(a));
+    assert_eq!(interner.len(), 2);
+    assert_eq!(interner.iter().collect::<Vec<_>>(), [d, b]);
+
+    let mut bytes = LruInterner::<str>::new(100).max_weight(10);
+    let long = bytes.get_or_intern("12345678");
+    let x = bytes.get_or_intern("xy");
+    assert_eq!(bytes.weight(), 10);
+    bytes.get_or_intern("z");
+    assert_eq!(bytes.resolve(long), None);
+    assert_eq!(bytes.resolve(x), Some("xy"));
+    assert_eq!(bytes.weight(), 3);
+    let huge = bytes.get_or_intern("this is longer than 10 bytes");
+    assert_eq!(bytes.len(), 1);
+    assert_eq!(bytes.resolve(huge).map(str::len), Some(28));
+
+    let mut numbers = LruInterner::<u64>::new(1);
+    let one = numbers.get_or_intern(&1);
+    let two = numbers.get_or_intern(&2);
+    assert_eq!(numbers.resolve(one), None);
+    assert_eq!(numbers.resolve(two), Some(&2));
+    assert_eq!(numbers.max_entries(), 1);
+}
+
+#[test]
+fn lru_generations() {
+    use crate::lru::LruInterner;
+
+    let mut interner = LruInterner::<u32>::new(1);
+    let syms: Vec<_> = (0..100).map(|n| interner.get_or_intern(&n)).collect();
+    for (n, sym) in syms.iter().enumerate() {
+        assert_eq!(sym.index(), 0);
+        assert_eq!(sym.generation(), n as u32);
+    }
+    for &sym in &syms[..99] {
+        assert_eq!(interner.resolve(sym), None);
+    }
+    assert_eq!(interner.resolve(syms[99]), Some(&99));
+
+    /* Reinterning an evicted value doesn't revive its old symbol */
+    let zero = interner.get_or_intern(&0);
+    assert_ne!(zero, syms[0]);
+    assert_eq!(interner.resolve(syms[0]), None);
+    assert_eq!(interner.resolve(zero), Some(&0));
+}
+
+#[test]
+fn lru_ttl() {
+    use std::thread::sleep;
+    use std::time::Duration;
+    use crate::lru::LruInterner;
+
+    let ttl = Duration::from_millis(200);
+    let mut interner = LruInterner::<str>::new(10).ttl(ttl);
+    let old = interner.get_or_intern("old");
+    let kept = interner.get_or_intern("kept");
+    assert_eq!(interner.purge_expired(), 0);
+
+    sleep(ttl / 2);
+    assert!(interner.touch(kept));
+    sleep(ttl / 2 + Duration::from_millis(10));
+    /* Expired, but not purged yet */
+    assert_eq!(interner.resolve(old), Some("old"));
+    assert_eq!(interner.purge_expired(), 1);
+    assert_eq!(interner.resolve(old), None);
+    assert_eq!(interner.resolve(kept), Some("kept"));
+
+    sleep(ttl);
+    let new = interner.get_or_intern("new");
+    assert_eq!(interner.resolve(kept), None);
+    assert_eq!(interner.len(), 1);
+    assert_eq!(interner.resolve(new), Some("new"));
+
+    let mut forever = LruInterner::<str>::new(10);
+    forever.get_or_intern("a");
+    assert_eq!(forever.purge_expired(), 0);
+    assert_eq!(forever.len(), 1);
+}
+
+#[test]
+fn lru_weigher() {
+    use crate::lru::LruInterner;
+
+    let mut interner = LruInterner::<str>::with_max_weight(10);
+    let small: Vec<_> = ["a", "b", "c", "d"].into_iter().map(|s| interner.get_or_intern(s)).collect();
+    assert_eq!(interner.weight(), 4);
+    let big = interner.get_or_intern("12345678");
+    assert_eq!(interner.len(), 3);
+    assert_eq!(interner.weight(), 10);
+    assert_eq!(interner.resolve(small[0]), None);
+    assert_eq!(interner.resolve(small[1]), None);
+    assert_eq!(interner.resolve(small[2]), Some("c"));
+    assert_eq!(interner.resolve(big), Some("12345678"));
+
+    /* Every vowel weighs 5, the rest weigh nothing */
+    let vowels = |s: &str| 5 * s.chars().filter(|c| "aeiou".contains(*c)).count();
+    let mut interner = LruInterner::<str>::new(100).weigher(vowels).max_weight(10);
+    let a = interner.get_or_intern("hi");
+    let b = interner.get_or_intern("xyz");
+    interner.get_or_intern("yo");
+    assert_eq!(interner.weight(), 10);
+    let c = interner.get_or_intern("zzza");
+    assert_eq!(interner.resolve(a), None);
+    assert_eq!(interner.resolve(b), Some("xyz"));
+    assert_eq!(interner.resolve(c), Some("zzza"));
+    interner.remove(c);
+    assert_eq!(interner.weight(), 5);
+
+    /* The stored clone is weighed, not the value passed in */
+    let mut interner = LruInterner::<String>::new(100).weigher(|s: &String| s.capacity());
+    let mut src = String::with_capacity(64);
+    src.push_str("abc");
+    let sym = interner.get_or_intern(&src);
+    assert_eq!(interner.weight(), interner.resolve(sym).unwrap().capacity());
+    assert!(interner.weight() < 64);
+    interner.remove(sym);
+    assert_eq!(interner.weight(), 0);
+}
+
+#[test]
+fn observed_interner() {
+    use crate::observer::{ObservedInterner, Observer};
+    use std::collections::HashMap;
+
+    /* Keeps an index of the words by their first letter,
+     * and rejects the ones that aren't lowercase */
+    #[derive(Default)]
+    struct ByInitial {
+        index: HashMap<char, Vec<Symbol<str>>>,
+        requests: usize,
+    }
+
+    impl Observer<str, Symbol<str>> for ByInitial {
+        fn admit(&mut self, value: &str) -> bool {
+            value.chars().all(|c| c.is_ascii_lowercase())
+        }
+
+        fn on_intern(&mut self, sym: Symbol<str>, value: &str, was_new: bool) {
+            self.requests += 1;
+            if was_new {
+                self.index.entry(value.chars().next().unwrap()).or_default().push(sym);
+            }
+        }
+    }
+
+    let mut interner = ObservedInterner::<str, _>::new(ByInitial::default());
+    let apple = interner.get_or_intern("apple").unwrap();
+    let avocado = interner.get_or_intern("avocado").unwrap();
+    let banana = interner.get_or_intern("banana").unwrap();
+    assert_eq!(interner.get_or_intern("apple"), Some(apple));
+    assert_eq!(interner.get_or_intern("Cherry"), None);
+    assert_eq!(interner.get("Cherry"), None);
+
+    let observer = interner.observer();
+    assert_eq!(observer.requests, 4);
+    assert_eq!(observer.index[&'a'], [apple, avocado]);
+    assert_eq!(observer.index[&'b'], [banana]);
+    assert!(!observer.index.contains_key(&'C'));
+
+    /* Values already on the interner are always admitted */
+    let mut base = Interner::<str>::new();
+    let upper = base.get_or_intern("UPPER");
+    let mut interner = ObservedInterner::with_interner(base, ByInitial::default());
+    assert_eq!(interner.get_or_intern("UPPER"), Some(upper));
+    let (base, observer) = interner.into_parts();
+    assert_eq!(base.len(), 1);
+    assert_eq!(observer.requests, 1);
+    assert!(observer.index.is_empty());
+}
+
+#[test]
+#[cfg(feature = "tracing")]
+fn tracing_events() {
+    use std::sync::{Arc, Mutex};
+    use tracing::field::{Field, Visit};
+    use tracing::span::{Attributes, Id, Record};
+    use tracing::{Event, Metadata, Subscriber};
+
+    /* Collects the `new` and `entries` fields of the events */
+    #[derive(Clone, Default)]
+    struct Collect(Arc<Mutex<Vec<(bool, u64)>>>);
+
+    #[derive(Default)]
+    struct Fields(bool, u64);
+
+    impl Visit for Fields {
+        fn record_bool(&mut self, field: &Field, value: bool) {
+            if field.name() == "new" {
+                self.0 = value;
+            }
+        }
+
+        fn record_u64(&mut self, field: &Field, value: u64) {
+            if field.name() == "entries" {
+                self.1 = value;
+            }
+        }
+
+        fn record_debug(&mut self, _field: &Field, _value: &dyn core::fmt::Debug) {}
+    }
+
+    impl Subscriber for Collect {
+        fn enabled(&self, _: &Metadata<'_>) -> bool {
+            true
+        }
+
+        fn new_span(&self, _: &Attributes<'_>) -> Id {
+            Id::from_u64(1)
+        }
+
+        fn record(&self, _: &Id, _: &Record<'_>) {}
+
+        fn record_follows_from(&self, _: &Id, _: &Id) {}
+
+        fn event(&self, event: &Event<'_>) {
+            let mut fields = Fields::default();
+            event.record(&mut fields);
+            self.0.lock().unwrap().push((fields.0, fields.1));
+        }
+
+        fn enter(&self, _: &Id) {}
+
+        fn exit(&self, _: &Id) {}
+    }
+
+    let collect = Collect::default();
+    tracing::subscriber::with_default(collect.clone(), || {
+        let mut interner = Interner::<str>::new();
+        interner.get_or_intern("a");
+        interner.get_or_intern("b");
+        interner.get_or_intern("a");
+    });
+    assert_eq!(*collect.0.lock().unwrap(), [(true, 1), (true, 2), (false, 2)]);
+}
+
+#[test]
+#[cfg(feature = "metrics")]
+fn metrics_recorder() {
+    use metrics::{Counter, CounterFn, Gauge, GaugeFn, Histogram, Key, KeyName, Metadata, Recorder, SharedString, Unit};
+    use std::collections::HashMap;
+    use std::sync::{Arc, Mutex};
+
+    type Values = Arc<Mutex<HashMap<String, f64>>>;
+
+    struct Handle(String, Values);
+
+    impl CounterFn for Handle {
+        fn increment(&self, value: u64) {
+            *self.1.lock().unwrap().entry(self.0.clone()).or_default() += value as f64;
+        }
+
+        fn absolute(&self, value: u64) {
+            self.1.lock().unwrap().insert(self.0.clone(), value as f64);
+        }
+    }
+
+    impl GaugeFn for Handle {
+        fn increment(&self, value: f64) {
+            *self.1.lock().unwrap().entry(self.0.clone()).or_default() += value;
+        }
+
+        fn decrement(&self, value: f64) {
+            *self.1.lock().unwrap().entry(self.0.clone()).or_default() -= value;
+        }
+
+        fn set(&self, value: f64) {
+            self.1.lock().unwrap().insert(self.0.clone(), value);
+        }
+    }
+
+    #[derive(Default)]
+    struct Collect(Values);
+
+    impl Collect {
+        fn handle(&self, key: &Key) -> Arc<Handle> {
+            let mut name = key.name().to_owned();
+            for label in key.labels() {
+                name += &format!("{{{}={}}}", label.key(), label.value());
+            }
+            Arc::new(Handle(name, self.0.clone()))
+        }
+    }
+
+    impl Recorder for Collect {
+        fn describe_counter(&self, _: KeyName, _: Option<Unit>, _: SharedString) {}
+
+        fn describe_gauge(&self, _: KeyName, _: Option<Unit>, _: SharedString) {}
+
+        fn describe_histogram(&self, _: KeyName, _: Option<Unit>, _: SharedString) {}
+
+        fn register_counter(&self, key: &Key, _: &Metadata<'_>) -> Counter {
+            Counter::from_arc(self.handle(key))
+        }
+
+        fn register_gauge(&self, key: &Key, _: &Metadata<'_>) -> Gauge {
+            Gauge::from_arc(self.handle(key))
+        }
+
+        fn register_histogram(&self, _: &Key, _: &Metadata<'_>) -> Histogram {
+            Histogram::noop()
+        }
+    }
+
+    let recorder = Collect::default();
+    metrics::with_local_recorder(&recorder, || {
+        crate::stats::describe_metrics();
+        let mut interner = Interner::<str>::new();
+        interner.get_or_intern("abc");
+        interner.get_or_intern("de");
+        interner.get_or_intern("abc");
+        let gauges = || {
+            let values = recorder.0.lock().unwrap();
+            (values["interns_entries"], values["interns_arena_bytes"])
+        };
+        assert_eq!(gauges(), (2.0, 5.0));
+
+        interner.remove("de");
+        assert_eq!(gauges(), (1.0, 3.0));
+        interner.bulk_load(vec!["f", "gh"]);
+        interner.retain(|_, s| s != "abc");
+        assert_eq!(gauges(), (2.0, 3.0));
+        interner.compact();
+        assert_eq!(gauges(), (2.0, 3.0));
+
+        let other = Interner::<str>::from_lines(["x"]);
+        assert_eq!(gauges(), (3.0, 4.0));
+        drop(interner);
+        assert_eq!(gauges(), (1.0, 1.0));
+        drop(other);
+    });
+
+    let values = recorder.0.lock().unwrap();
+    assert_eq!(values["interns_requests_total{result=miss}"], 5.0);
+    assert_eq!(values["interns_requests_total{result=hit}"], 1.0);
+    assert_eq!(values["interns_entries"], 0.0);
+    assert_eq!(values["interns_arena_bytes"], 0.0);
+    /* The ratio is global, and other tests are interning concurrently */
+    assert!((0.0..=1.0).contains(&values["interns_hit_ratio"]));
+}
+
+#[test]
+fn write_to() {
+    use core::fmt::Write;
+
+    let mut interner = StringInterner::new();
+    let key = interner.get_or_intern("key");
+    let value = interner.get_or_intern("value");
+
+    let mut out = String::new();
+    interner.write_to(key, &mut out).unwrap();
+    out.push('=');
+    interner.write_to(value, &mut out).unwrap();
+    assert_eq!(out, "key=value");
+
+    let dyn_out: &mut dyn Write = &mut out;
+    interner.write_to(key, dyn_out).unwrap();
+    assert_eq!(out, "key=valuekey");
+
+    let mut bytes = Vec::new();
+    interner.write_bytes_to(value, &mut bytes).unwrap();
+    assert_eq!(bytes, b"value");
+
+    let unknown = StringInterner::new();
+    assert!(unknown.write_to(key, &mut out).is_err());
+    assert_eq!(out, "key=valuekey");
+    let err = unknown.write_bytes_to(key, &mut bytes).unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+    assert_eq!(bytes, b"value");
+
+    let mut nums = Interner::<u32>::new();
+    let n = nums.get_or_intern(&42);
+    let mut out = String::new();
+    nums.write_to(n, &mut out).unwrap();
+    assert_eq!(out, "42");
+}
+
+#[test]
+fn get_or_intern_display() {
+    use core::fmt;
+
+    struct Name<'a>(&'a str, u32);
+
+    impl fmt::Display for Name<'_> {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            write!(f, "{}#{}", self.0, self.1)
+        }
+    }
+
+    let mut interner = StringInterner::new();
+    let syms: Vec<_> = (0..100).map(|i| interner.get_or_intern_display(&(i % 10))).collect();
+    assert_eq!(interner.len(), 10);
+    assert_eq!(syms[3], syms[13]);
+    assert_eq!(interner.resolve(syms[7]), Some("7"));
+
+    let a = interner.get_or_intern_display(&Name("x", 1));
+    let b = interner.get_or_intern_display(&Name("x", 1));
+    assert_eq!(a, b);
+    assert_eq!(interner.resolve(a), Some("x#1"));
+    assert_eq!(interner.get_or_intern_display("plain"), interner.get_or_intern("plain"));
+
+    /* A Display implementation that interns on another interner */
+    struct Nested<'a>(core::cell::RefCell<&'a mut StringInterner>);
+
+    impl fmt::Display for Nested<'_> {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            let sym = self.0.borrow_mut().get_or_intern_display(&"inner");
+            write!(f, "outer:{}", self.0.borrow().resolve(sym).unwrap())
+        }
+    }
+
+    let mut other = StringInterner::new();
+    let outer = interner.get_or_intern_display(&Nested(core::cell::RefCell::new(&mut other)));
+    assert_eq!(interner.resolve(outer), Some("outer:inner"));
+    assert!(other.contains("inner"));
+}
+
+#[test]
+#[cfg(feature = "bytes")]
+fn bytes_intern_reader() {
+    use crate::backend::BytesBackend;
+    use std::io::{self, Read};
+
+    let mut interner = Interner::<[u8], BytesBackend>::new();
+    let small = interner.get_or_intern(&b"small"[..]);
+    let blob: Vec<u8> = (0..10_000u32).map(|i| (i % 251) as u8).collect();
+
+    let a = interner.get_or_intern_reader(&blob[..]).unwrap();
+    let b = interner.get_or_intern_reader(io::Cursor::new(blob.clone())).unwrap();
+    assert_eq!(a, b);
+    assert_eq!(interner.len(), 2);
+    assert_eq!(interner.resolve(a), Some(&blob[..]));
+    assert_eq!(interner.get_or_intern_reader(&b"small"[..]).unwrap(), small);
+    assert_eq!(interner.resolve(small), Some(&b"small"[..]));
+
+    /* The values interned after a roll back are still right */
+    let c = interner.get_or_intern(&b"after"[..]);
+    let d = interner.get_or_intern_reader(&b"reader"[..]).unwrap();
+    assert_eq!(interner.resolve(c), Some(&b"after"[..]));
+    assert_eq!(interner.resolve(d), Some(&b"reader"[..]));
+    assert_eq!(interner.resolve_bytes(a).unwrap(), &blob[..]);
+
+    struct Failing(usize);
+
+    impl Read for Failing {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            if self.0 == 0 {
+                return Err(io::Error::other("broken pipe"));
+            }
+            self.0 -= 1;
+            buf[0] = b'x';
+            Ok(1)
+        }
+    }
+
+    assert!(interner.get_or_intern_reader(Failing(3)).is_err());
+    assert_eq!(interner.len(), 4);
+    assert!(!interner.contains(&b"xxx"[..]));
+    let e = interner.get_or_intern(&b"e"[..]);
+    assert_eq!(interner.resolve(e), Some(&b"e"[..]));
+    interner.check_invariants().unwrap();
+
+    /* Interrupted reads are retried */
+    struct Interrupted<'a>(bool, &'a [u8]);
+
+    impl Read for Interrupted<'_> {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            self.0 = !self.0;
+            if self.0 {
+                return Err(io::ErrorKind::Interrupted.into());
+            }
+            self.1.read(buf)
+        }
+    }
+
+    let mut interner = Interner::<[u8], BytesBackend>::with_backend(BytesBackend::with_chunk_size(4));
+    let a = interner.get_or_intern(&blob[..]);
+    assert_eq!(interner.get_or_intern_reader(Interrupted(false, &blob)).unwrap(), a);
+    let f = interner.get_or_intern_reader(Interrupted(false, b"from a reader")).unwrap();
+    assert_eq!(interner.resolve(f), Some(&b"from a reader"[..]));
+    interner.check_invariants().unwrap();
+}
+
+#[test]
+fn get_or_intern_chars() {
+    let mut interner = StringInterner::new();
+    let a = interner.get_or_intern("alpha");
+    let b = interner.get_or_intern_chars("beta".chars());
+    assert_eq!(interner.get_or_intern_chars("alpha".chars()), a);
+    assert_eq!(interner.get_or_intern_chars("beta".chars()), b);
+    assert_eq!(interner.get_or_intern("beta"), b);
+    assert_eq!(interner.len(), 2);
+
+    /* The hits don't leave anything behind on the buffer */
+    let c = interner.get_or_intern_chars("ñandú".chars());
+    assert_eq!(interner.resolve(c), Some("ñandú"));
+    assert_eq!(interner.resolve(a), Some("alpha"));
+    assert_eq!(interner.get_or_intern("gamma"), interner.get_or_intern_chars("gamma".chars()));
+
+    let empty = interner.get_or_intern_chars(core::iter::empty());
+    assert_eq!(interner.resolve(empty), Some(""));
+    assert_eq!(interner.get(""), Some(empty));
+
+    let long: String = core::iter::repeat_n('x', 100_000).collect();
+    let l = interner.get_or_intern_chars(long.chars());
+    assert_eq!(interner.resolve(l), Some(long.as_str()));
+    assert_eq!(interner.get_or_intern_chars(long.chars()), l);
+    assert_eq!(interner.savings().requests, 11);
+    interner.check_invariants().unwrap();
+
+    let mut validating = StringInterner::with_backend(StringBackend::validating());
+    let v = validating.get_or_intern_chars("v".chars());
+    assert_eq!(validating.resolve(v), Some("v"));
+    validating.check_invariants().unwrap();
+}
+
+#[test]
+fn get_ignore_case() {
+    let mut interner = StringInterner::new();
+    let http = interner.get_or_intern("HttpClient");
+    let straße = interner.get_or_intern("Straße");
+    let mixed = interner.get_or_intern("camelCase");
+    let lower = interner.get_or_intern("camelcase");
+
+    assert_eq!(interner.get_ignore_case("httpclient"), Some((http, "HttpClient")));
+    assert_eq!(interner.get_ignore_case("HTTPCLIENT"), Some((http, "HttpClient")));
+    assert_eq!(interner.get_ignore_case("STRASSE"), None);
+    assert_eq!(interner.get_ignore_case("STRAßE"), Some((straße, "Straße")));
+    assert_eq!(interner.get_ignore_case("missing"), None);
+
+    /* Exact matches are preferred */
+    assert_eq!(interner.get_ignore_case("camelCase"), Some((mixed, "camelCase")));
+    assert_eq!(interner.get_ignore_case("camelcase"), Some((lower, "camelcase")));
+    let (sym, _) = interner.get_ignore_case("CAMELCASE").unwrap();
+    assert!(sym == mixed || sym == lower);
+    assert_eq!(interner.len(), 4);
+}
+
+#[test]
+fn cross_interner_eq() {
+    use crate::remap::{cross_eq, cross_eq_all};
+
+    let mut strings = StringInterner::new();
+    let mut boxed = Interner::<str, ArcBackend<str>>::new();
+    let a = strings.get_or_intern("foo");
+    let b = strings.get_or_intern("bar");
+    boxed.get_or_intern("bar");
+    let c = boxed.get_or_intern("foo");
+
+    assert!(cross_eq(a, &strings, c, &boxed));
+    assert!(!cross_eq(b, &strings, c, &boxed));
+    assert!(cross_eq(b, &strings, b, &strings));
+
+    let empty = StringInterner::new();
+    assert!(!cross_eq(a, &strings, a, &empty));
+
+    assert!(cross_eq_all(&[a, b], &strings, &[c, boxed.get("bar").unwrap()], &boxed));
+    assert!(!cross_eq_all(&[a, b], &strings, &[c, c], &boxed));
+    assert!(!cross_eq_all(&[a], &strings, &[c, c], &boxed));
+    assert!(cross_eq_all(&[], &strings, &[], &boxed));
+}
+
+#[test]
+fn translate_symbols() {
+    use crate::remap::{Translator, translate};
+
+    let mut from = StringInterner::new();
+    let mut to = Interner::<str, ArcBackend<str>>::new();
+    let x = from.get_or_intern("x");
+    let y = from.get_or_intern("y");
+    to.get_or_intern("y");
+
+    let tx = translate(x, &from, &mut to).unwrap();
+    assert_eq!(to.resolve(tx), Some("x"));
+    assert_eq!(translate(x, &from, &mut to), Some(tx));
+    assert_eq!(translate(x, &StringInterner::new(), &mut to), None);
+
+    let stream = [x, y, x, y, y];
+    let mut translator = Translator::new(&from, &mut to);
+    let out = translator.translate_all(stream).unwrap();
+    assert_eq!(translator.translate_all([x, crate::backend::string::Symbol::new_indexed(7)]), None);
+    let remap = translator.into_remap();
+    assert_eq!(remap.len(), 2);
+    assert_eq!(remap.get(x), Some(tx));
+    assert_eq!(out.len(), 5);
+    assert_eq!(out[1], to.get("y").unwrap());
+    assert_eq!(to.len(), 2);
+}
+
+#[test]
+fn layered_interner() {
+    use crate::layered::{LayeredInterner, LayeredSymbol};
+    use std::sync::Arc;
+
+    let mut session = StringInterner::new();
+    let int = session.get_or_intern("int");
+    let main = session.get_or_intern("main");
+    let session = Arc::new(session);
+
+    let mut a = LayeredInterner::new(Arc::clone(&session));
+    let mut b = LayeredInterner::new(Arc::clone(&session));
+    assert_eq!(a.get_or_intern("int"), LayeredSymbol::Parent(int));
+    let foo = a.get_or_intern("foo");
+    let bar = b.get_or_intern("bar");
+    assert!(foo.is_local() && bar.is_local());
+    assert_eq!(a.get("bar"), None);
+    assert_eq!(b.get("foo"), None);
+    assert_eq!(a.resolve(foo), Some("foo"));
+    assert_eq!(b.resolve(bar), Some("bar"));
+    assert_eq!(a.resolve(LayeredSymbol::Parent(main)), Some("main"));
+    assert_eq!(a.len(), 3);
+    assert_eq!(a.local().len(), 1);
+    assert_eq!(session.len(), 2);
+    assert!(a.contains("main") && !a.contains("bar"));
+
+    /* The parent shadows the values of the local layer */
+    let mut local = StringInterner::new();
+    local.get_or_intern("int");
+    let c = LayeredInterner::with_local(Arc::clone(&session), local);
+    assert_eq!(c.get("int"), Some(LayeredSymbol::Parent(int)));
+    assert_eq!(c.into_local().len(), 1);
+
+    let mut empty = LayeredInterner::<str>::default();
+    assert!(empty.is_empty());
+    let x = empty.get_or_intern("x");
+    assert_eq!(x.into_inner(), empty.local().get("x").unwrap());
+}
+
+#[test]
+fn fork_interner() {
+    use std::sync::Arc;
+
+    let mut base = Interner::<u32>::new();
+    for i in 0..100 {
+        base.get_or_intern(&i);
+    }
+    let base = Arc::new(base);
+
+    let forks: Vec<_> = (0..10)
+        .map(|i| {
+            let mut fork = base.fork();
+            assert!(fork.get_or_intern(&i).is_parent());
+            let new = fork.get_or_intern(&(1000 + i));
+            assert_eq!(fork.resolve(new), Some(&(1000 + i)));
+            fork
+        })
+        .collect();
+
+    assert_eq!(Arc::strong_count(&base), 11);
+    assert_eq!(base.len(), 100);
+    for (i, fork) in forks.iter().enumerate() {
+        assert_eq!(fork.local().len(), 1);
+        assert!(Arc::ptr_eq(fork.parent(), &base));
+        let other = 1000 + (i as u32 + 1) % 10;
+        assert_eq!(fork.get(&other), None);
+    }
+    drop(forks);
+    assert_eq!(Arc::strong_count(&base), 1);
+}
+
+#[test]
+fn persistent_interner() {
+    use crate::persistent::PersistentInterner;
+
+    let mut versions = vec![PersistentInterner::<str>::new()];
+    let words: Vec<String> = (0..1000).map(|i| format!("word{}", i % 700)).collect();
+    let mut syms = Vec::new();
+    for word in &words {
+        let (next, sym) = versions.last().unwrap().intern(word);
+        syms.push(sym);
+        versions.push(next);
+    }
+
+    let last = versions.last().unwrap();
+    assert_eq!(last.len(), 700);
+    for (word, &sym) in words.iter().zip(&syms) {
+        assert_eq!(last.resolve(sym), Some(word.as_str()));
+        assert_eq!(last.get(word.as_str()), Some(sym));
+    }
+    assert_eq!(syms[5], syms[705]);
+
+    /* Old versions don't see the values interned after them */
+    let v10 = &versions[10];
+    assert_eq!(v10.len(), 10);
+    assert_eq!(v10.resolve(syms[9]), Some("word9"));
+    assert_eq!(v10.resolve(syms[10]), None);
+    assert!(!v10.contains("word10"));
+    assert_eq!(v10.iter().map(|(_, s)| s).collect::<Vec<_>>()[..2], ["word0", "word1"]);
+
+    /* Branching from an old version */
+    let mut branch = v10.clone();
+    let other = branch.get_or_intern("other");
+    assert_eq!(other.index(), 10);
+    assert_eq!(branch.resolve(other), Some("other"));
+    assert_eq!(last.resolve(other), Some("word10"));
+    assert!(!last.contains("other"));
+
+    /* Collisions on the hash index are handled */
+    #[derive(Clone)]
+    struct Colliding;
+
+    impl core::hash::BuildHasher for Colliding {
+        type Hasher = std::hash::DefaultHasher;
+
+        fn build_hasher(&self) -> Self::Hasher {
+            std::hash::DefaultHasher::new()
+        }
+
+        fn hash_one<T: core::hash::Hash>(&self, _: T) -> u64 {
+            42
+        }
+    }
+
+    let mut colliding = PersistentInterner::<u32, _>::with_hasher(Colliding);
+    let a = colliding.get_or_intern(&1);
+    let b = colliding.get_or_intern(&2);
+    assert_ne!(a, b);
+    assert_eq!(colliding.get(&1), Some(a));
+    assert_eq!(colliding.get(&2), Some(b));
+    assert_eq!(colliding.get(&3), None);
+}
+
+#[test]
+fn diff_interners() {
+    let mut a = StringInterner::new();
+    let mut b = Interner::<str, ArcBackend<str>>::new();
+    for w in ["x", "y", "z"] {
+        a.get_or_intern(w);
+    }
+    for w in ["z", "y", "w", "v"] {
+        b.get_or_intern(w);
+    }
+
+    let diff = a.diff(&b);
+    assert!(!diff.is_empty());
+    assert_eq!(diff.only_left, [(a.get("x").unwrap(), "x")]);
+    let mut right: Vec<_> = diff.only_right.iter().map(|&(sym, s)| (b.resolve(sym).unwrap(), s)).collect();
+    right.sort();
+    assert_eq!(right, [("v", "v"), ("w", "w")]);
+
+    /* Same vocabulary, different symbols */
+    let mut c = StringInterner::new();
+    for w in ["z", "y", "x"] {
+        c.get_or_intern(w);
+    }
+    let diff = a.diff(&c);
+    assert!(diff.is_empty());
+    assert_eq!(format!("{diff:?}"), "Diff { only_left: [], only_right: [] }");
+}
+
+#[test]
+fn sub_interner() {
+    use crate::collections::{SymbolIndex, SymbolSet};
+
+    let mut interner = Interner::<u32>::new();
+    let syms: Vec<_> = (0..100).map(|i| interner.get_or_intern(&(i * 10))).collect();
+    let all = interner.symbol_set();
+    assert_eq!(all.len(), 100);
+
+    let even: SymbolSet<_> = syms.iter().step_by(2).copied().collect();
+    let small: SymbolSet<_> = syms[..10].iter().copied().collect();
+
+    let (sub, remap) = interner.sub_interner(&(&even & &small));
+    assert_eq!(sub.len(), 5);
+    assert_eq!(remap.len(), 5);
+    let values: Vec<_> = sub.iter().map(|(_, v)| *v).collect();
+    assert_eq!(values.len(), 5);
+    for (i, &sym) in syms[..10].iter().enumerate() {
+        match remap.get(sym) {
+            Some(new) => {
+                assert_eq!(i % 2, 0);
+                assert_eq!(sub.resolve(new), interner.resolve(sym));
+                /* The order of the symbols is kept */
+                assert_eq!(new.to_index(), i / 2);
+            }
+            None => assert_eq!(i % 2, 1),
+        }
+    }
+
+    let (odd, _) = interner.sub_interner(&(&all - &even));
+    assert_eq!(odd.len(), 50);
+    assert!(odd.contains(&10) && !odd.contains(&20));
+
+    let (everything, remap) = interner.sub_interner(&(&all | &small));
+    assert_eq!(everything.len(), 100);
+    assert!(remap.is_identity());
+}
+
+#[test]
+fn sorted_symbols() {
+    let mut interner = Interner::<i64>::new();
+    let values = [5, -3, 12, 0, 7, -3, 5];
+    for v in &values {
+        interner.get_or_intern(v);
+    }
+
+    let sorted: Vec<_> = interner.sorted_symbols().iter().map(|&s| *interner.resolve(s).unwrap()).collect();
+    assert_eq!(sorted, [-3, 0, 5, 7, 12]);
+
+    let mut index = interner.sort_index();
+    assert!(!index.is_stale(&interner));
+    assert_eq!(index.symbols(), interner.sorted_symbols());
+
+    let min = interner.get_or_intern(&-100);
+    assert!(index.is_stale(&interner));
+    assert_eq!(index.symbols().len(), 5);
+    assert!(index.refresh(&interner));
+    assert_eq!(index.symbols()[0], min);
+    assert_eq!(index.symbols().len(), 6);
+    assert!(!index.refresh(&interner));
+
+    /* Changes that keep the same length are noticed */
+    interner.remove(&-100);
+    interner.get_or_intern(&100);
+    assert!(index.is_stale(&interner));
+    assert!(index.refresh(&interner));
+    assert_eq!(interner.resolve(index.symbols()[5]), Some(&100));
+
+    interner.retain(|_, _| true);
+    assert!(!index.is_stale(&interner));
+    interner.compact();
+    assert!(index.is_stale(&interner));
+
+    assert!(StringInterner::new().sorted_symbols().is_empty());
+}
+
+#[test]
+fn frozen_interner() {
+    let mut interner = StringInterner::new();
+    let words = ["delta", "alpha", "charlie", "bravo", "echo"];
+    let syms: Vec<_> = words.iter().map(|w| interner.get_or_intern(w)).collect();
+
+    let frozen = interner.freeze();
+    assert_eq!(frozen.len(), 5);
+    for (w, &sym) in words.iter().zip(&syms) {
+        assert_eq!(frozen.get(*w), Some(sym));
+        assert_eq!(frozen.resolve(sym), Some(*w));
+    }
+    assert!(!frozen.contains("foxtrot"));
+    assert!(!frozen.contains(""));
+    let sorted: Vec<_> = frozen.iter().map(|(_, w)| w).collect();
+    assert_eq!(sorted, ["alpha", "bravo", "charlie", "delta", "echo"]);
+
+    let mut thawed: StringInterner = frozen.thaw();
+    assert_eq!(thawed.get("charlie"), Some(syms[2]));
+    let f = thawed.get_or_intern("foxtrot");
+    assert_eq!(thawed.resolve(f), Some("foxtrot"));
+    thawed.check_invariants().unwrap();
+
+    let mut nums = Interner::<u16>::new();
+    for i in (0..1000).rev() {
+        nums.get_or_intern(&(i * 3));
+    }
+    let frozen = nums.freeze();
+    assert!(frozen.contains(&300) && !frozen.contains(&301));
+    assert!(Interner::<u16>::new().freeze().is_empty());
+}
+
+#[test]
+fn mph_interner() {
+    use crate::collections::SymbolIndex;
+
+    let mut interner = Interner::<u64>::new();
+    let syms: Vec<_> = (0..10_000u64).map(|i| interner.get_or_intern(&(i * 7919))).collect();
+
+    let mph = interner.freeze_mph();
+    assert_eq!(mph.len(), 10_000);
+    for (i, &sym) in syms.iter().enumerate() {
+        let value = i as u64 * 7919;
+        assert_eq!(mph.get(&value), Some(sym));
+        assert_eq!(mph.resolve(sym), Some(&value));
+    }
+    assert!(!mph.contains(&1));
+    assert_eq!(mph.iter().count(), 10_000);
+    assert!(mph.hash_bits() <= 3 * 10_000, "{} bits", mph.hash_bits());
+
+    let mut thawed = mph.thaw();
+    assert_eq!(thawed.get(&7919), Some(syms[1]));
+    assert_eq!(thawed.get_or_intern(&1).to_index(), 10_000);
+    thawed.check_invariants().unwrap();
+
+    let empty = StringInterner::new().freeze_mph();
+    assert!(empty.is_empty());
+    assert_eq!(empty.get("x"), None);
+
+    let mut one = StringInterner::new();
+    let x = one.get_or_intern("x");
+    let one = one.freeze_mph();
+    assert_eq!(one.get("x"), Some(x));
+    assert_eq!(one.get("y"), None);
+
+    /* Every small size, so some keys are remapped */
+    for n in 2..200 {
+        let mut interner = Interner::<u64>::new();
+        let syms: Vec<_> = (0..n).map(|i| interner.get_or_intern(&i)).collect();
+        let mph = interner.freeze_mph();
+        assert!((0..n).all(|i| mph.get(&i) == Some(syms[i as usize])));
+        assert_eq!(mph.get(&n), None);
+    }
+}
+
+#[test]
+fn length_stats() {
+    use crate::backend::string::StringInternerBuilder;
+
+    let mut interner = StringInterner::new();
+    for w in ["a", "bb", "cc", "dddd", "a", "", "eeee"] {
+        interner.get_or_intern(w);
+    }
+    let histogram = interner.length_histogram();
+    assert_eq!(histogram.into_iter().collect::<Vec<_>>(), [(0, 1), (1, 1), (2, 2), (4, 2)]);
+
+    let stats = interner.length_stats();
+    assert_eq!(stats.count, 6);
+    assert_eq!(stats.total_bytes, 13);
+    assert_eq!(stats.min_len, 0);
+    assert_eq!(stats.max_len, 4);
+    assert_eq!(stats.count_up_to(2), 4);
+    assert!((stats.mean_len() - 13.0 / 6.0).abs() < 1e-9);
+    assert_eq!(interner.indexed_count(), 0);
+
+    let empty = Interner::<u64>::new().length_stats();
+    assert_eq!(empty, crate::stats::LengthStats::default());
+    assert_eq!(empty.mean_len(), 0.0);
+
+    let mut nums = Interner::<u64>::new();
+    for i in 0..3 {
+        nums.get_or_intern(&i);
+    }
+    assert_eq!(nums.length_stats().histogram.get(&8), Some(&3));
+
+    const BUILDER: StringInternerBuilder<2> = StringInternerBuilder::with_const_symbols(["if", "else"]);
+    let mut prefilled = BUILDER.build();
+    prefilled.get_or_intern("while");
+    assert_eq!(prefilled.indexed_count(), 2);
+}
+
+#[test]
+fn dump_table() {
+    use crate::dump::DumpOptions;
+
+    let mut interner = StringInterner::new();
+    for w in ["one", "two\nlines", "three", "two\nlines"] {
+        interner.get_or_intern(w);
+    }
+    let mut out = String::new();
+    interner.dump(&mut out, DumpOptions::new()).unwrap();
+    assert_eq!(
+        out,
+        "\
+symbol                         offset  value
+Symbol { offset: 0, len: 3 }        0  \"one\"
+Symbol { offset: 3, len: 9 }        3  \"two\\nlines\"
+Symbol { offset: 12, len: 5 }      12  \"three\"
+"
+    );
+
+    /* The dump is the same for interners built the same way */
+    let mut other = StringInterner::new();
+    for w in ["one", "two\nlines", "three"] {
+        other.get_or_intern(w);
+    }
+    let mut other_out = String::new();
+    other.dump(&mut other_out, DumpOptions::default()).unwrap();
+    assert_eq!(out, other_out);
+
+    out.clear();
+    interner.dump(&mut out, DumpOptions::new().header(false).max_rows(1).max_value_width(3)).unwrap();
+    assert_eq!(out, "Symbol { offset: 0, len: 3 }  0  \"o…\n... 2 more\n");
+
+    /* Backends without offsets */
+    let mut arc = Interner::<str, ArcBackend<str>>::new();
+    arc.get_or_intern("x");
+    out.clear();
+    arc.dump(&mut out, DumpOptions::new().header(false)).unwrap();
+    assert!(out.ends_with("  -  \"x\"\n"));
+}
+
+#[test]
+fn dense_symbols() {
+    use crate::DenseSymbols;
+    use crate::backend::string::Symbol;
+
+    let mut interner = StringInterner::with_backend(StringBackend::validating());
+    let syms: Vec<_> = ["a", "", "bc", "d"].iter().map(|s| interner.get_or_intern(*s)).collect();
+    for (i, sym) in syms.iter().enumerate() {
+        assert_eq!(interner.index_of(*sym), Some(i));
+        assert_eq!(interner.symbol_at(i), Some(*sym));
+    }
+    assert_eq!(interner.symbol_at(4), None);
+    assert_eq!(interner.index_of(Symbol::new_inlined(0, 3)), None);
+
+    /* Indexed and inlined symbols are numbered by their position */
+    let mut backend = StringBackend::validating();
+    assert!(backend.push_at(0, Symbol::new_indexed(0), "kw"));
+    assert!(backend.push_at(2, Symbol::new_inlined(2, 0), ""));
+    assert!(backend.push_at(2, Symbol::new_indexed(1), "x"));
+    let order = [Symbol::new_indexed(0), Symbol::new_indexed(1), Symbol::new_inlined(2, 0)];
+    for (i, sym) in order.iter().enumerate() {
+        assert_eq!(backend.index_of(*sym), Some(i));
+        assert_eq!(backend.symbol_at(i), Some(*sym));
+    }
+
+    let mut plain = StringInterner::new();
+    let a = plain.get_or_intern("a");
+    assert_eq!(plain.index_of(a), None);
+
+    let mut ints = Interner::<u32>::new();
+    let ten = ints.get_or_intern(&10);
+    assert_eq!(ints.index_of(ten), Some(0));
+    assert_eq!(ints.symbol_at(0), Some(ten));
+    assert_eq!(ints.symbol_at(1), None);
+}
+
+#[test]
+fn bulk_load() {
+    let mut interner = StringInterner::new();
+    let b = interner.get_or_intern("b");
+
+    let words: Vec<String> = ["delta", "b", "alpha", "delta", "charlie", ""].iter().map(|w| w.to_string()).collect();
+    assert_eq!(interner.bulk_load(words), 4);
+    assert_eq!(interner.len(), 5);
+    assert_eq!(interner.get("b"), Some(b));
+    for w in ["alpha", "b", "charlie", "delta", ""] {
+        let sym = interner.get(w).unwrap();
+        assert_eq!(interner.resolve(sym), Some(w));
+    }
+    assert!(interner.check_invariants().is_ok());
+
+    /* Interning again doesn't create new symbols */
+    let alpha = interner.get("alpha").unwrap();
+    assert_eq!(interner.get_or_intern("alpha"), alpha);
+    assert_eq!(interner.bulk_load(vec!["alpha", "echo"]), 1);
+
+    let mut ints = Interner::<u32>::new();
+    assert_eq!(ints.bulk_load((0..1000).rev().collect()), 1000);
+    assert_eq!(ints.resolve(ints.get(&500).unwrap()), Some(&500));
+    assert_eq!(ints.savings().requests, 1000);
+}
+
+#[test]
+fn namespace() {
+    let mut interner = StringInterner::new();
+    let shared = interner.get_or_intern("9:tenant1::user");
+
+    let mut t1 = interner.namespace("tenant1::");
+    assert_eq!(t1.get("user"), Some(shared));
+    let order = t1.get_or_intern("order");
+    assert_eq!(t1.get_or_intern("order"), order);
+    let mut keys: Vec<_> = t1.iter().map(|(_, k)| k).collect();
+    keys.sort_unstable();
+    assert_eq!(keys, ["order", "user"]);
+
+    let mut t2 = interner.namespace("tenant2::");
+    assert!(!t2.contains("user"));
+    assert_eq!(t2.resolve(order), None);
+    let user = t2.get_or_intern("user");
+    assert_ne!(user, shared);
+    assert_eq!(t2.resolve(user), Some("user"));
+    assert_eq!(t2.prefix(), "tenant2::");
+
+    assert_eq!(interner.len(), 3);
+    assert_eq!(interner.resolve(user), Some("9:tenant2::user"));
+
+    /* A prefix of another prefix doesn't see its keys */
+    let x = interner.namespace("a::").get_or_intern("x");
+    let mut a = interner.namespace("a:");
+    assert_eq!(a.get(":x"), None);
+    assert_ne!(a.get_or_intern(":x"), x);
+    assert_eq!(a.resolve(x), None);
+    assert_eq!(a.iter().count(), 1);
+}
+
+#[test]
+fn const_symbols_duplicates() {
+    let ok = StringInternerBuilder::with_const_symbols(["if", "i", "iff"]);
+    assert_eq!(ok.build().len(), 3);
+
+    let dup = std::panic::catch_unwind(|| StringInternerBuilder::with_const_symbols(["if", "else", "if"]));
+    assert!(dup.is_err());
+}
+
+#[test]
+fn builder_hasher_and_backend() {
+    use crate::hash::FixedState;
+
+    const BUILDER: StringInternerBuilder<3> = StringInternerBuilder::with_const_symbols(["fn", "let", "mut"]);
+
+    let mut fixed = BUILDER.build_with_hasher(FixedState::with_seed(7));
+    assert_eq!(fixed.get_or_intern("mut"), BUILDER.symbol_at(2));
+    assert_eq!(fixed.len(), 3);
+
+    let mut deterministic: Interner<str, StringBackend, FixedState> = BUILDER.build_into();
+    assert_eq!(deterministic.get("fn"), Some(BUILDER.symbol_at(0)));
+    let x = deterministic.get_or_intern("x");
+    assert_eq!(deterministic.resolve(x), Some("x"));
+    assert!(deterministic.check_invariants().is_ok());
+}
+
+#[test]
+fn builder_extend() {
+    const BASE: StringInternerBuilder<2> = StringInternerBuilder::with_const_symbols(["if", "else"]);
+    const LOOPS: StringInternerBuilder<2> = StringInternerBuilder::with_const_symbols(["for", "while"]);
+    const EXTENDED: StringInternerBuilder<3> = BASE.extend(["match"]);
+    const ALL: StringInternerBuilder<5> = EXTENDED.concat(&LOOPS);
+
+    assert_eq!(ALL.symbol_at(1), BASE.symbol_at(1));
+    assert_eq!(ALL.symbol_at(2), EXTENDED.symbol_at(2));
+    assert_eq!(ALL.string_at(4), "while");
+
+    let mut interner = ALL.build();
+    assert_eq!(interner.len(), 5);
+    assert_eq!(interner.get_or_intern("else"), BASE.symbol_at(1));
+    assert_eq!(interner.get_or_intern("for"), ALL.symbol_at(3));
+    assert_ne!(ALL.symbol_at(3), LOOPS.symbol_at(0));
+
+    let dup = std::panic::catch_unwind(|| BASE.extend::<1, 3>(["if"]));
+    assert!(dup.is_err());
+    let wrong_len = std::panic::catch_unwind(|| BASE.extend::<1, 4>(["loop"]));
+    assert!(wrong_len.is_err());
+}
+
+#[test]
+fn runtime_prefill() {
+    use crate::PrefillError;
+
+    let mut interner = StringInterner::new();
+    let get = interner.get_or_intern("GET");
+
+    let syms = interner.prefill(&["POST", "GET", ""]).unwrap();
+    assert_eq!(syms.len(), 3);
+    assert_eq!(syms[1], get);
+    assert_eq!(interner.resolve(syms[2]), Some(""));
+    assert_eq!(interner.len(), 3);
+
+    let Err(err) = interner.prefill(&["PATCH", "PUT", "PATCH"]) else { panic!() };
+    assert!(matches!(&err, PrefillError::Duplicate { string, first: 0, index: 2 } if string == "PATCH"));
+    assert_eq!(err.to_string(), "duplicate string \"PATCH\" at positions 0 and 2");
+    assert!(!interner.contains("PUT"));
+
+    assert_eq!(interner.prefill(&[]).unwrap(), []);
+}
+
+#[test]
+fn intern_parts() {
+    let mut interner = StringInterner::new();
+    let io = interner.get_or_intern("std::io");
+    let before = interner.backend.buf_len();
+
+    assert_eq!(interner.intern_parts(&["std", "::", "io"]), io);
+    assert_eq!(interner.backend.buf_len(), before);
+
+    let rs = interner.intern_parts(&["lib", ".rs"]);
+    assert_eq!(interner.resolve(rs), Some("lib.rs"));
+    assert_eq!(interner.get("lib.rs"), Some(rs));
+
+    let empty = interner.intern_parts(&[]);
+    assert_eq!(interner.intern_parts(&["", ""]), empty);
+    assert_eq!(interner.len(), 3);
+    assert!(interner.check_invariants().is_ok());
+}
+
+#[test]
+fn intern_unescaped() {
+    use crate::EscapeError;
+    use crate::escape::EscapeRules;
+
+    let mut interner = StringInterner::new();
+    let rules = EscapeRules::new();
+    let nl = interner.get_or_intern("a\nb");
+    let before = interner.backend.buf_len();
+
+    assert_eq!(interner.get_or_intern_unescaped(r"a\nb", rules), Ok(nl));
+    assert_eq!(interner.get_or_intern_unescaped(r"a\x0Ab", rules), Ok(nl));
+    assert_eq!(interner.get_or_intern_unescaped(r"a\u{a}b", rules), Ok(nl));
+    assert_eq!(interner.backend.buf_len(), before);
+
+    let s = interner.get_or_intern_unescaped(r"\\ \' \0 \xe9 \u{1F980}", rules).unwrap();
+    assert_eq!(interner.resolve(s), Some("\\ ' \0 é 🦀"));
+
+    let errors = [
+        (r"end\", EscapeError::Trailing { offset: 3 }),
+        (r"\q", EscapeError::Unknown { offset: 0, escape: 'q' }),
+        (r"x\x4", EscapeError::InvalidHex { offset: 1 }),
+        (r"\u{110000}", EscapeError::InvalidUnicode { offset: 0 }),
+        (r"\u{+41}", EscapeError::InvalidUnicode { offset: 0 }),
+        (r"\u41", EscapeError::InvalidUnicode { offset: 0 }),
+    ];
+    for (raw, err) in errors {
+        assert_eq!(interner.get_or_intern_unescaped(raw, rules), Err(err));
+    }
+    assert_eq!(interner.len(), 2);
+    assert!(interner.check_invariants().is_ok());
+
+    let lenient = EscapeRules::new().strict(false).hex(false).escape_char('%');
+    let s = interner.get_or_intern_unescaped("100%% %q %x41", lenient).unwrap();
+    assert_eq!(interner.resolve(s), Some("100% q x41"));
+}
+
+#[test]
+fn remove_and_compact() {
+    let mut interner = StringInterner::new();
+    let syms: Vec<_> = ["alpha", "beta", "gamma", "delta"].iter().map(|s| interner.get_or_intern(*s)).collect();
+
+    assert_eq!(interner.remove("beta"), Some(syms[1]));
+    assert_eq!(interner.remove("beta"), None);
+    interner.retain(|_, s| s != "delta");
+    assert_eq!(interner.len(), 2);
+    assert!(interner.check_invariants().is_ok());
+
+    /* The removed values are still on the buffer */
+    assert_eq!(interner.resolve(syms[1]), Some("beta"));
+    let beta = interner.get_or_intern("beta");
+    assert_ne!(beta, syms[1]);
+    assert_eq!(interner.backend.buf_len(), 23);
+
+    let remap = interner.compact();
+    assert_eq!(remap.len(), 3);
+    assert_eq!(remap.get(syms[3]), None);
+    assert_eq!(interner.backend.buf_len(), 14);
+    for (old, s) in [(syms[0], "alpha"), (syms[2], "gamma"), (beta, "beta")] {
+        let new = remap.get(old).unwrap();
+        assert_eq!(interner.resolve(new), Some(s));
+        assert_eq!(interner.get(s), Some(new));
+    }
+    assert!(interner.check_invariants().is_ok());
+
+    let mut ints = Interner::<u32>::new();
+    for n in 0..10 {
+        ints.get_or_intern(&n);
+    }
+    ints.retain(|_, n| n % 3 == 0);
+    let remap = ints.compact();
+    assert_eq!(ints.to_vec(), [0, 3, 6, 9]);
+    assert_eq!(remap.len(), 4);
+}
+
+#[test]
+fn rebuild_index() {
+    let mut interner = Interner::<u32>::new();
+    let syms: Vec<_> = (0..100).map(|n| interner.get_or_intern(&n)).collect();
+
+    /* Corrupt the set, and add a value behind the interner's back */
+    interner.set.clear();
+    let dup = interner.backend.intern(&7);
+    assert!(interner.check_invariants().is_ok());
+    assert_eq!(interner.get(&7), None);
+
+    assert_eq!(interner.rebuild_index(), 1);
+    assert_eq!(interner.len(), 100);
+    assert_eq!(interner.get(&7), Some(syms[7]));
+    assert_ne!(interner.get(&7), Some(dup));
+    assert!(interner.check_invariants().is_ok());
+
+    let mut arcs = Interner::<str, ArcBackend<str>>::new();
+    let a = arcs.get_or_intern("a");
+    arcs.set.clear();
+    assert_eq!(arcs.rebuild_index(), 0);
+    assert_eq!(arcs.get("a"), Some(a));
+}
+
+#[test]
+fn append_unique() {
+    let mut interner = Interner::<str, ArcBackend<str>>::new();
+    let a = interner.get_or_intern("a");
+    let b = interner.append_unique("b");
+    let dup = interner.append_unique("a");
+
+    assert_eq!(interner.resolve(b), Some("b"));
+    assert_eq!(interner.len(), 1);
+    assert!(!interner.contains("b"));
+
+    assert_eq!(interner.rebuild_index(), 1);
+    assert_eq!(interner.len(), 2);
+    assert_eq!(interner.get("a"), Some(a));
+    assert_ne!(dup, a);
+    assert_eq!(interner.get("b"), Some(b));
+    assert!(interner.check_invariants().is_ok());
+}
+
+#[test]
+fn resolve_trait() {
+    use std::sync::{Arc, Mutex};
+
+    use crate::Resolve;
+    use crate::layered::LayeredInterner;
+
+    fn lookup<R: Resolve<str>>(r: R, s: &str) -> Option<&'static str> {
+        let sym = r.get(s)?;
+        r.resolve(sym).map(|v| if v == s { "ok" } else { "mismatch" })
+    }
+
+    let mut interner = StringInterner::new();
+    interner.get_or_intern("a");
+    assert_eq!(lookup(&interner, "a"), Some("ok"));
+    assert_eq!(lookup(&interner, "b"), None);
+
+    let shared = Arc::new(Mutex::new(interner));
+    assert_eq!(lookup(shared.lock().unwrap(), "a"), Some("ok"));
+    let interner = Arc::into_inner(shared).unwrap().into_inner().unwrap();
+
+    let boxed: Box<dyn Resolve<str, Symbol = _>> = Box::new(interner.freeze());
+    assert!(boxed.contains("a"));
+    assert_eq!(lookup(&boxed, "a"), Some("ok"));
+
+    let mut layered = LayeredInterner::<str>::default();
+    layered.get_or_intern("x");
+    assert_eq!(lookup(&layered, "x"), Some("ok"));
+}
+
+#[test]
+fn interner_view() {
+    use crate::Resolve;
+    use crate::view::InternerView;
+
+    fn count_known(view: InternerView<'_, str>, words: &[&str]) -> usize {
+        words.iter().filter(|w| view.contains(**w)).count()
+    }
+
+    let mut interner = StringInterner::new();
+    let a = interner.get_or_intern("a");
+    interner.get_or_intern("b");
+
+    let view = interner.view();
+    let copy = view;
+    assert_eq!(count_known(view, &["a", "b", "c"]), 2);
+    assert_eq!(copy.get("a"), Some(a));
+    assert_eq!(Resolve::resolve(&copy, a), Some("a"));
+    assert_eq!(view.iter().count(), view.len());
+
+    /* Values outlive the view */
+    let value = interner.view().resolve(a).unwrap();
+    assert_eq!(value, "a");
+    assert!(!InternerView::from(&interner).is_empty());
+}
+
+#[test]
+fn mapped_resolver() {
+    use std::borrow::Cow;
+
+    use crate::resolve::MappedResolver;
+
+    let mut interner = StringInterner::new();
+    let mangled = interner.get_or_intern("_ZN4main4init");
+    let plain = interner.get_or_intern("printf");
+
+    fn demangle(s: &str) -> Cow<'_, str> {
+        match s.strip_prefix("_ZN") {
+            Some(rest) => Cow::Owned(rest.replace('4', "::").trim_start_matches("::").to_string()),
+            None => Cow::Borrowed(s),
+        }
+    }
+    let mapped = MappedResolver::new(&interner, demangle);
+    assert_eq!(mapped.resolve(mangled).as_deref(), Some("main::init"));
+    assert!(matches!(mapped.resolve(plain), Some(Cow::Borrowed("printf"))));
+    assert_eq!(mapped.get("_ZN4main4init"), Some(mangled));
+    assert_eq!(mapped.get("main::init"), None);
+
+    let frozen = MappedResolver::new(interner.freeze(), |s: &str| Cow::Borrowed(s.trim_start_matches('_')));
+    assert_eq!(frozen.resolve(mangled).as_deref(), Some("ZN4main4init"));
+    assert_eq!(frozen.into_inner().len(), 2);
+}
+
+#[cfg(feature = "bumpalo")]
+#[test]
+fn bump_backend() {
+    use bumpalo::Bump;
+
+    use crate::backend::BumpBackend;
+
+    let bump = Bump::new();
+    let (hello, nums) = {
+        let mut strs = Interner::<str, BumpBackend<str>>::with_backend(BumpBackend::new(&bump));
+        let a = strs.get_or_intern("hello");
+        assert_eq!(strs.get_or_intern("hello"), a);
+        let b = strs.get_or_intern("world");
+        assert_ne!(a, b);
+        assert_eq!(strs.index_of(b), Some(1));
+
+        let mut slices = Interner::<[u8], BumpBackend<[u8]>>::with_backend(BumpBackend::new(&bump));
+        let s = slices.get_or_intern(&[1, 2, 3]);
+        (strs.resolve_bump(a).unwrap(), slices.resolve_bump(s).unwrap())
+    };
+    assert_eq!(hello, "hello");
+    assert_eq!(nums, &[1, 2, 3]);
+
+    let mut ints = Interner::<u64, BumpBackend<u64>>::with_backend(BumpBackend::new(&bump));
+    let n = ints.get_or_intern(&42);
+    assert_eq!(ints.resolve(n), Some(&42));
+    assert_eq!(ints.rebuild_index(), 0);
+}
+
+#[cfg(feature = "typed-arena")]
+#[test]
+fn arena_backend() {
+    use typed_arena::Arena;
+
+    use crate::backend::ArenaBackend;
+
+    let arena = Arena::new();
+    let mut interner = Interner::<String, ArenaBackend<String>>::with_backend(ArenaBackend::new(&arena));
+    let a = interner.get_or_intern(&"a".to_string());
+    let first = interner.resolve_arena(a).unwrap();
+
+    let syms: Vec<_> = (0..1000).map(|n| interner.get_or_intern(&n.to_string())).collect();
+    assert_eq!(first, "a");
+    assert_eq!(interner.get_or_intern(&"a".to_string()), a);
+    assert_eq!(interner.resolve(syms[999]).map(String::as_str), Some("999"));
+    assert_eq!(interner.index_of(syms[0]), Some(1));
+    assert_eq!(interner.rebuild_index(), 0);
+
+    drop(interner);
+    assert_eq!(first, "a");
+    assert_eq!(arena.len(), 1001);
+}
+
+#[test]
+fn table_stats() {
+    use core::hash::{BuildHasherDefault, Hasher};
+
+    #[derive(Default)]
+    struct Constant;
+
+    impl Hasher for Constant {
+        fn finish(&self) -> u64 {
+            42
+        }
+
+        fn write(&mut self, _: &[u8]) {}
+    }
+
+    let empty = StringInterner::new().table_stats();
+    assert_eq!(empty.len, 0);
+    assert_eq!(empty.load_factor(), 0.0);
+
+    let interner = StringInterner::from_lines((0..100).map(|n| n.to_string()));
+    let stats = interner.table_stats();
+    assert_eq!(stats.len, 100);
+    assert!(stats.capacity >= 100);
+    assert_eq!(stats.table_bytes, interner.savings().table_bytes);
+    if let Some(probes) = stats.probes {
+        assert_eq!(probes.distinct_hashes, 100);
+        assert_eq!(probes.max_same_hash, 1);
+        assert!(probes.total_comparisons >= 100);
+    }
+
+    let mut bad = Interner::<str, StringBackend, _>::with_hasher(BuildHasherDefault::<Constant>::default());
+    for n in 0..50 {
+        bad.get_or_intern(&n.to_string());
+    }
+    let probes = bad.table_stats().probes;
+    assert_eq!(probes.is_some(), cfg!(debug_assertions));
+    if let Some(probes) = probes {
+        assert_eq!(probes.distinct_hashes, 1);
+        assert_eq!(probes.max_same_hash, 50);
+        assert_eq!(probes.max_comparisons, 50);
+        assert_eq!(probes.total_comparisons, 50 * 51 / 2);
+        assert_eq!(probes.mean_comparisons(50), 25.5);
+    }
+}
+
+#[test]
+fn pair_interner() {
+    use crate::backend::string::Symbol;
+    use crate::composite::{PairInterner, PairSymbol};
+
+    let mut strings = StringInterner::new();
+    let mut pairs = PairInterner::<Symbol>::new();
+    let a = strings.get_or_intern("a");
+    let b = strings.get_or_intern("b");
+
+    let ab = pairs.get_or_intern_pair(a, b);
+    let ba = pairs.get_or_intern_pair(b, a);
+    assert_ne!(ab, ba);
+    assert_eq!(pairs.get_or_intern_pair(a, b), ab);
+    assert_eq!(pairs.get_pair(a, a), None);
+    assert_eq!(pairs.resolve_pair(ba), Some((b, a)));
+    assert_eq!(pairs.resolve_parts(ba, &strings), Some(("b", "a")));
+    assert_eq!(pairs.resolve_parts(ab, &StringInterner::new()), None);
+    assert_eq!(pairs.len(), 2);
+
+    /* Pairs of different symbol types, and pairs of pairs */
+    let mut mixed = PairInterner::<PairSymbol, u32>::new();
+    let nested = mixed.get_or_intern_pair(ab, 7u32);
+    assert_eq!(mixed.resolve_pair(nested), Some((ab, 7)));
+}
+
+#[test]
+fn path_symbol_interner() {
+    use crate::qualified::PathSymbolInterner;
+
+    let mut interner = PathSymbolInterner::new();
+    assert_eq!(interner.intern_path(Vec::<&str>::new()), None);
+
+    let vec = interner.intern_path(["std", "vec", "Vec"]).unwrap();
+    let string = interner.intern_path(["std", "string", "String"]).unwrap();
+    assert_eq!(interner.len(), 5);
+    assert_eq!(interner.segment_interner().len(), 5);
+
+    let std = interner.get_path(["std"]).unwrap();
+    assert_eq!(interner.parent(std), None);
+    assert_eq!(interner.ancestors(vec).last(), Some(std));
+    assert_eq!(interner.ancestors(string).count(), 3);
+    assert!(interner.starts_with(vec, std));
+    assert!(!interner.starts_with(vec, string));
+    assert_eq!(interner.segments(vec), Some(vec!["std", "vec", "Vec"]));
+
+    /* The same segment under different parents */
+    let inner = interner.intern_path(["vec"]).unwrap();
+    assert_ne!(Some(inner), interner.parent(vec));
+    assert_eq!(interner.segment_interner().len(), 5);
+    assert_eq!(interner.get_path(["std", "vec"]), interner.parent(vec));
+    assert_eq!(interner.get_path(["std", "Vec"]), None);
+    assert_eq!(interner.get_child(Some(vec), "new"), None);
+
+    assert_eq!(interner.display(string).to_string(), "std::string::String");
+    assert_eq!(interner.display_with(vec, "/").to_string(), "std/vec/Vec");
+
+    let other = PathSymbolInterner::new();
+    assert_eq!(other.display(vec).to_string(), "<unknown>");
+    assert_eq!(other.segments(vec), None);
+    assert_eq!(other.ancestors(vec).count(), 0);
+}
+
+#[test]
+fn dict_encoder() {
+    use crate::dict::DictEncoder;
+
+    let words = "the quick fox and the lazy dog and the end";
+    let (codes, dict) = DictEncoder::encode(words.split(' '));
+    assert_eq!(codes, [0, 1, 2, 3, 0, 4, 5, 3, 0, 6]);
+    assert_eq!(dict.len(), 7);
+    assert_eq!(DictEncoder::decode(&codes, &dict).unwrap().join(" "), words);
+    assert_eq!(DictEncoder::dictionary(&dict).unwrap(), ["the", "quick", "fox", "and", "lazy", "dog", "end"]);
+    assert_eq!(DictEncoder::decode(&[7], &dict), None);
+
+    let mut encoder = DictEncoder::new();
+    encoder.extend(["", "a", "", "b", "a"]);
+    assert_eq!(encoder.push("c"), 3);
+    assert_eq!(encoder.codes(), [0, 1, 0, 2, 1, 3]);
+    assert_eq!(encoder.dict().len(), 4);
+    let (codes, dict) = encoder.finish();
+    assert_eq!(DictEncoder::decode(&codes, &dict).unwrap(), ["", "a", "", "b", "a", "c"]);
+
+    /* Only validating interners know the codes of their values */
+    let plain = StringInterner::from_lines(["a"]);
+    assert_eq!(DictEncoder::dictionary(&plain), None);
+
+    let (codes, dict) = DictEncoder::encode(Vec::<String>::new());
+    assert!(codes.is_empty() && dict.is_empty());
+
+    let encoder = DictEncoder::with_capacity(0, 100);
+    assert!(encoder.dict().set.capacity() >= 100);
+}
+
+#[cfg(feature = "aho-corasick")]
+#[test]
+fn aho_corasick_matcher() {
+    use crate::matcher::{AhoCorasickBuilder, MatchKind, SymbolMatch};
+
+    let mut interner = StringInterner::new();
+    let he = interner.get_or_intern("he");
+    let hello = interner.get_or_intern("hello");
+    let lo = interner.get_or_intern("lo");
+    interner.get_or_intern("");
+
+    let matcher = interner.build_matcher().unwrap();
+    assert_eq!(matcher.len(), 3);
+    assert_eq!(matcher.find("say hello"), Some(SymbolMatch { symbol: hello, start: 4, end: 9 }));
+    assert_eq!(matcher.find("say hello").unwrap().range(), 4..9);
+    let hits: Vec<_> = matcher.find_iter("hello, he said. lo").map(|m| m.symbol).collect();
+    assert_eq!(hits, [hello, he, lo]);
+    assert!(!matcher.is_match("nothing"));
+
+    let mut builder = AhoCorasickBuilder::new();
+    builder.match_kind(MatchKind::Standard);
+    let overlapping = interner.build_matcher_with(builder).unwrap();
+    let mut hits: Vec<_> = overlapping.find_overlapping_iter("hello").unwrap().map(|m| m.symbol).collect();
+    hits.sort();
+    let mut expected = [he, hello, lo];
+    expected.sort();
+    assert_eq!(hits, expected);
+    assert!(matcher.find_overlapping_iter("hello").is_err());
+
+    let mut matcher = matcher;
+    assert!(!matcher.refresh(&interner).unwrap());
+    let world = interner.get_or_intern("world");
+    assert!(matcher.is_stale(&interner));
+    assert_eq!(matcher.find("world"), None);
+    assert!(matcher.refresh(&interner).unwrap());
+    assert_eq!(matcher.find("world").map(|m| m.symbol), Some(world));
+    assert!(!matcher.is_stale(&interner));
+
+    /* Removing and interning keeps the length, but the matcher is stale */
+    interner.remove("world");
+    let word = interner.get_or_intern("word");
+    assert!(matcher.is_stale(&interner));
+    assert!(matcher.refresh(&interner).unwrap());
+    assert_eq!(matcher.find("world").map(|m| m.symbol), None);
+    assert_eq!(matcher.find("word").map(|m| m.symbol), Some(word));
+
+    let empty = StringInterner::new().build_matcher().unwrap();
+    assert!(empty.is_empty());
+    assert_eq!(empty.find("anything"), None);
+}
@@ -0,0 +1,151 @@
+//! Container with one interner per type
+//!
+//! A [TypedInterners] holds an [Interner] for every type that has been
+//! interned on it. Its [TypedSymbol]s remember the type they belong to,
+//! so a symbol of one type can't be resolved as another.
+//!
+//! # Example
+//! ```
+//! use std::path::Path;
+//! use interns::typed::TypedInterners;
+//!
+//! let mut interners = TypedInterners::new();
+//! let name = interners.get_or_intern::<str, _>("main");
+//! let port = interners.get_or_intern::<u16, _>(&8080);
+//! let file = interners.get_or_intern::<Box<Path>, _>(Path::new("src/main.rs"));
+//!
+//! assert_eq!(interners.resolve(name), Some("main"));
+//! assert_eq!(interners.resolve(port), Some(&8080));
+//! assert_eq!(interners.resolve(file).map(|p| &**p), Some(Path::new("src/main.rs")));
+//! ```
+
+use core::any::{Any, TypeId};
+use core::borrow::Borrow;
+use core::fmt;
+use core::hash::{Hash, Hasher};
+use core::marker::PhantomData;
+use std::collections::HashMap;
+
+use crate::backend::{DefaultBackend, DefaultBackendBuilder, Internable};
+use crate::{Interner, Symbol};
+
+/// Symbol of a value of type `T`, interned on a [TypedInterners]
+pub struct TypedSymbol<T: ?Sized + DefaultBackendBuilder> {
+    sym: Symbol<T>,
+    _marker: PhantomData<fn() -> Box<T>>,
+}
+
+impl<T: ?Sized + DefaultBackendBuilder> TypedSymbol<T> {
+    /// Returns the symbol of the underlying [Interner]
+    pub fn symbol(self) -> Symbol<T> {
+        self.sym
+    }
+}
+
+impl<T: ?Sized + DefaultBackendBuilder> Clone for TypedSymbol<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T: ?Sized + DefaultBackendBuilder> Copy for TypedSymbol<T> {}
+
+impl<T: ?Sized + DefaultBackendBuilder> PartialEq for TypedSymbol<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.sym == other.sym
+    }
+}
+
+impl<T: ?Sized + DefaultBackendBuilder> Eq for TypedSymbol<T> {}
+
+impl<T: ?Sized + DefaultBackendBuilder> Hash for TypedSymbol<T> {
+    fn hash<S: Hasher>(&self, state: &mut S) {
+        self.sym.hash(state);
+    }
+}
+
+impl<T: ?Sized + DefaultBackendBuilder> fmt::Debug for TypedSymbol<T>
+where
+    Symbol<T>: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("TypedSymbol").field(&self.sym).finish()
+    }
+}
+
+/// One [Interner] per type
+///
+/// The interners use the [default backend](DefaultBackendBuilder)
+/// of their type, and are created the first time a value of that
+/// type is interned.
+#[derive(Default)]
+pub struct TypedInterners {
+    interners: HashMap<TypeId, Box<dyn Any>>,
+}
+
+impl TypedInterners {
+    /// Creates an empty container
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Interns `src` on the interner of `T`
+    pub fn get_or_intern<T, Ref>(&mut self, src: &Ref) -> TypedSymbol<T>
+    where
+        T: ?Sized + Hash + Eq + DefaultBackendBuilder + 'static,
+        DefaultBackend<T>: Default + 'static,
+        T: Borrow<Ref>,
+        Ref: ?Sized + Hash + Eq + Internable<T, DefaultBackend<T>>,
+    {
+        let sym = self.interner_mut::<T>().get_or_intern(src);
+        TypedSymbol { sym, _marker: PhantomData }
+    }
+
+    /// Gets the symbol of `src` on the interner of `T`, without interning it
+    pub fn get<T, Ref>(&self, src: &Ref) -> Option<TypedSymbol<T>>
+    where
+        T: ?Sized + Hash + Eq + DefaultBackendBuilder + 'static,
+        DefaultBackend<T>: 'static,
+        T: Borrow<Ref>,
+        Ref: ?Sized + Hash + Eq,
+    {
+        let sym = self.interner::<T>()?.get(src)?;
+        Some(TypedSymbol { sym, _marker: PhantomData })
+    }
+
+    /// Resolves `sym`
+    pub fn resolve<T>(&self, sym: TypedSymbol<T>) -> Option<&T>
+    where
+        T: ?Sized + Hash + Eq + DefaultBackendBuilder + 'static,
+        DefaultBackend<T>: 'static,
+    {
+        self.interner::<T>()?.resolve(sym.sym)
+    }
+
+    /// Returns the interner of `T`, if a value of that type has been interned
+    pub fn interner<T>(&self) -> Option<&Interner<T>>
+    where
+        T: ?Sized + Hash + Eq + DefaultBackendBuilder + 'static,
+        DefaultBackend<T>: 'static,
+    {
+        self.interners.get(&TypeId::of::<T>())?.downcast_ref()
+    }
+
+    /// Returns the interner of `T`, creating it if needed
+    pub fn interner_mut<T>(&mut self) -> &mut Interner<T>
+    where
+        T: ?Sized + Hash + Eq + DefaultBackendBuilder + 'static,
+        DefaultBackend<T>: Default + 'static,
+    {
+        self.interners
+            .entry(TypeId::of::<T>())
+            .or_insert_with(|| Box::new(Interner::<T>::new()))
+            .downcast_mut()
+            .expect("The interner of a TypeId is always of that type")
+    }
+
+    /// Returns the number of types with an interner
+    pub fn types(&self) -> usize {
+        self.interners.len()
+    }
+}
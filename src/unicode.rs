@@ -0,0 +1,65 @@
+//! Unicode normalization
+//!
+//! The same text can be encoded with different sequences of code points.
+//! For example, `é` can be a single code point (U+00E9), or an `e` followed
+//! by a combining acute accent (U+0301). Both look identical, but they
+//! are different strings, so they get different symbols.
+//!
+//! The methods of this module normalize strings to [NFC] before interning
+//! them, so that visually identical strings get the same symbol.
+//!
+//! [NFC]: https://unicode.org/reports/tr15/
+//!
+//! # Example
+//! ```
+//! use interns::StringInterner;
+//!
+//! let mut interner = StringInterner::new();
+//! let composed = interner.get_or_intern_nfc("caf\u{e9}");
+//! let decomposed = interner.get_or_intern_nfc("cafe\u{301}");
+//! assert_eq!(composed, decomposed);
+//! assert_eq!(interner.resolve(decomposed), Some("caf\u{e9}"));
+//! ```
+
+use core::hash::BuildHasher;
+use std::borrow::Cow;
+
+use unicode_normalization::{is_nfc_quick, IsNormalized, UnicodeNormalization};
+
+use crate::backend::Internable;
+use crate::{Backend, Interner};
+
+/// Normalizes `s` to NFC
+///
+/// Strings that are already normalized are returned
+/// as they are, without allocating.
+pub fn nfc(s: &str) -> Cow<'_, str> {
+    match is_nfc_quick(s.chars()) {
+        IsNormalized::Yes => Cow::Borrowed(s),
+        IsNormalized::No | IsNormalized::Maybe => {
+            let normalized: String = s.nfc().collect();
+            if normalized == s {
+                Cow::Borrowed(s)
+            } else {
+                Cow::Owned(normalized)
+            }
+        }
+    }
+}
+
+impl<B, H> Interner<str, B, H>
+where
+    B: Backend<str>,
+    H: BuildHasher,
+    str: Internable<str, B>,
+{
+    /// Interns the [NFC](nfc) normalization of `src`
+    pub fn get_or_intern_nfc(&mut self, src: &str) -> B::Symbol {
+        self.get_or_intern(&*nfc(src))
+    }
+
+    /// Gets the symbol of the [NFC](nfc) normalization of `src`, without interning it
+    pub fn get_nfc(&self, src: &str) -> Option<B::Symbol> {
+        self.get(&*nfc(src))
+    }
+}
@@ -0,0 +1,139 @@
+//! Interning of normalized URLs
+//!
+//! URLs that differ only in their syntax are semantically identical, and
+//! an [UrlInterner] gives them the same symbol. Before interning, the URL is
+//! parsed and normalized following [RFC 3986, section 6.2.2]:
+//!
+//! - The scheme and host are converted to lowercase
+//! - Default ports (e.g. `:80` for `http`) are removed
+//! - An empty path is replaced by `/`, and `.` and `..` segments are resolved
+//! - Percent-encoded unreserved characters (`A-Z a-z 0-9 - . _ ~`) are decoded,
+//!   and the hex digits of the rest of percent-encodings are uppercased
+//!
+//! [RFC 3986, section 6.2.2]: https://www.rfc-editor.org/rfc/rfc3986#section-6.2.2
+//!
+//! # Example
+//! ```
+//! use interns::url::UrlInterner;
+//!
+//! let mut interner = UrlInterner::new();
+//! let a = interner.get_or_intern("HTTP://Example.COM:80/%7euser/a%2fb").unwrap();
+//! let b = interner.get_or_intern("http://example.com/~user/a%2Fb").unwrap();
+//! assert_eq!(a, b);
+//! assert_eq!(interner.resolve(a), Some("http://example.com/~user/a%2Fb"));
+//! ```
+
+use core::hash::BuildHasher;
+
+pub use url::{ParseError, Url};
+
+use crate::backend::string::Symbol;
+use crate::hash::DefaultHashBuilder;
+use crate::{Interner, StringBackend};
+
+/// Returns the normalized form of `url`
+///
+/// See the [module-level documentation](self) for the rules
+/// applied. Most of them are already applied by [Url::parse].
+pub fn normalize(url: &Url) -> String {
+    let src = url.as_str().as_bytes();
+    let mut out = String::with_capacity(src.len());
+    let mut i = 0;
+    while i < src.len() {
+        if src[i] == b'%'
+            && let Some(byte) = src.get(i + 1..i + 3).and_then(decode_hex)
+        {
+            if is_unreserved(byte) {
+                out.push(char::from(byte));
+            } else {
+                out.push('%');
+                out.push_str(&format!("{byte:02X}"));
+            }
+            i += 3;
+        } else {
+            /* Url serializes to ASCII, so every byte is a char */
+            out.push(char::from(src[i]));
+            i += 1;
+        }
+    }
+    out
+}
+
+/// Decodes two hex digits
+///
+/// `from_str_radix` alone would accept a sign, like in `%+1`
+fn decode_hex(digits: &[u8]) -> Option<u8> {
+    if !digits.iter().all(u8::is_ascii_hexdigit) {
+        return None
+    }
+    let digits = core::str::from_utf8(digits).ok()?;
+    u8::from_str_radix(digits, 16).ok()
+}
+
+fn is_unreserved(byte: u8) -> bool {
+    byte.is_ascii_alphanumeric() || matches!(byte, b'-' | b'.' | b'_' | b'~')
+}
+
+/// Interner for [normalized](normalize) URLs
+///
+/// See the [module-level documentation](self) for more details.
+pub struct UrlInterner<H: BuildHasher = DefaultHashBuilder> {
+    inner: Interner<str, StringBackend, H>,
+}
+
+impl UrlInterner {
+    /// Creates an empty interner
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl<H: BuildHasher + Default> Default for UrlInterner<H> {
+    fn default() -> Self {
+        Self { inner: Interner::new() }
+    }
+}
+
+impl<H: BuildHasher> UrlInterner<H> {
+    /// Parses and normalizes `url`, and gets its symbol, interning it if it doesn't exist
+    pub fn get_or_intern(&mut self, url: &str) -> Result<Symbol, ParseError> {
+        Ok(self.get_or_intern_url(&Url::parse(url)?))
+    }
+
+    /// Normalizes `url`, and gets its symbol, interning it if it doesn't exist
+    pub fn get_or_intern_url(&mut self, url: &Url) -> Symbol {
+        self.inner.get_or_intern(&normalize(url))
+    }
+
+    /// Gets the symbol of `url`, without interning it
+    ///
+    /// Returns None if `url` is not a valid URL, or it hasn't been interned.
+    pub fn get(&self, url: &str) -> Option<Symbol> {
+        self.get_url(&Url::parse(url).ok()?)
+    }
+
+    /// Gets the symbol of `url`, without interning it
+    pub fn get_url(&self, url: &Url) -> Option<Symbol> {
+        self.inner.get(&*normalize(url))
+    }
+
+    /// Resolves the symbol into the normalized URL
+    pub fn resolve(&self, sym: Symbol) -> Option<&str> {
+        self.inner.resolve(sym)
+    }
+
+    /// Resolves the symbol, and parses the normalized URL
+    pub fn resolve_url(&self, sym: Symbol) -> Option<Url> {
+        Url::parse(self.resolve(sym)?).ok()
+    }
+
+    /// Returns the number of unique URLs
+    pub fn len(&self) -> usize {
+        self.inner.len()
+    }
+
+    /// Returns true if nothing has been interned yet
+    pub fn is_empty(&self) -> bool {
+        self.inner.is_empty()
+    }
+}
@@ -0,0 +1,154 @@
+//! Read-only views of an interner
+//!
+//! An [InternerView] borrows an [Interner] immutably, and only exposes
+//! its lookup methods. Passing a view to a phase that must not intern
+//! new values (e.g. an analysis pass, after parsing) makes that rule
+//! part of its signature.
+//!
+//! # Example
+//! ```
+//! use interns::StringInterner;
+//! use interns::view::InternerView;
+//!
+//! fn is_keyword(idents: InternerView<'_, str>, name: &str) -> bool {
+//!     idents.contains(name)
+//! }
+//!
+//! let mut interner = StringInterner::new();
+//! interner.get_or_intern("fn");
+//! assert!(is_keyword(interner.view(), "fn"));
+//! ```
+//!
+//! Views are [Copy], so they can be handed out freely.
+
+use core::borrow::Borrow;
+use core::hash::{BuildHasher, Hash};
+
+use crate::backend::DefaultBackend;
+use crate::hash::DefaultHashBuilder;
+use crate::{Backend, Interner, Resolve};
+
+/// Read-only view of an [Interner]
+///
+/// See the [module-level documentation](self) for more details.
+pub struct InternerView<'a, T, B = DefaultBackend<T>, H = DefaultHashBuilder>
+where
+    T: Hash + Eq + ?Sized,
+    B: Backend<T>,
+    H: BuildHasher,
+{
+    interner: &'a Interner<T, B, H>,
+}
+
+impl<'a, T, B, H> InternerView<'a, T, B, H>
+where
+    T: Hash + Eq + ?Sized,
+    B: Backend<T>,
+    H: BuildHasher,
+{
+    /// Creates a view of `interner`
+    pub fn new(interner: &'a Interner<T, B, H>) -> Self {
+        Self { interner }
+    }
+
+    /// Gets the symbol for `src`
+    pub fn get<Ref>(&self, src: &Ref) -> Option<B::Symbol>
+    where
+        Ref: ?Sized + Hash + Eq,
+        T: Borrow<Ref>,
+    {
+        self.interner.get(src)
+    }
+
+    /// Returns true if `src` has been interned
+    pub fn contains<Ref>(&self, src: &Ref) -> bool
+    where
+        Ref: ?Sized + Hash + Eq,
+        T: Borrow<Ref>,
+    {
+        self.interner.contains(src)
+    }
+
+    /// Resolves the symbol
+    ///
+    /// The value borrows from the interner, not from the view.
+    pub fn resolve(&self, sym: B::Symbol) -> Option<&'a T> {
+        self.interner.resolve(sym)
+    }
+
+    /// Returns an iterator over the symbols and their values
+    ///
+    /// The iteration order is unspecified.
+    pub fn iter(&self) -> impl Iterator<Item = (B::Symbol, &'a T)> + 'a {
+        self.interner.iter()
+    }
+
+    /// Returns the number of unique values
+    pub fn len(&self) -> usize {
+        self.interner.len()
+    }
+
+    /// Returns true if the interner is empty
+    pub fn is_empty(&self) -> bool {
+        self.interner.is_empty()
+    }
+}
+
+impl<T, B, H> Clone for InternerView<'_, T, B, H>
+where
+    T: Hash + Eq + ?Sized,
+    B: Backend<T>,
+    H: BuildHasher,
+{
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T, B, H> Copy for InternerView<'_, T, B, H>
+where
+    T: Hash + Eq + ?Sized,
+    B: Backend<T>,
+    H: BuildHasher,
+{
+}
+
+impl<T, B, H> Resolve<T> for InternerView<'_, T, B, H>
+where
+    T: Hash + Eq + ?Sized,
+    B: Backend<T>,
+    H: BuildHasher,
+{
+    type Symbol = B::Symbol;
+
+    fn resolve(&self, sym: B::Symbol) -> Option<&T> {
+        InternerView::resolve(self, sym)
+    }
+
+    fn get(&self, value: &T) -> Option<B::Symbol> {
+        InternerView::get(self, value)
+    }
+}
+
+impl<'a, T, B, H> From<&'a Interner<T, B, H>> for InternerView<'a, T, B, H>
+where
+    T: Hash + Eq + ?Sized,
+    B: Backend<T>,
+    H: BuildHasher,
+{
+    fn from(interner: &'a Interner<T, B, H>) -> Self {
+        Self::new(interner)
+    }
+}
+
+impl<T, B, H> Interner<T, B, H>
+where
+    T: Hash + Eq + ?Sized,
+    B: Backend<T>,
+    H: BuildHasher,
+{
+    /// Returns a read-only [view](InternerView) of this interner
+    pub fn view(&self) -> InternerView<'_, T, B, H> {
+        InternerView::new(self)
+    }
+}
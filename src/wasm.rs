@@ -0,0 +1,59 @@
+//! [wasm_bindgen](mod@wasm_bindgen) bindings
+//!
+//! Exposes a [StringInterner] to JavaScript as the `JsInterner` class.
+//! Symbols are passed to JavaScript as [u64]s (BigInt), since the
+//! packed encoding of a [Symbol] needs 64 bits, and a [usize] is only
+//! 32 bits wide on wasm32.
+//!
+//! # Example (JavaScript)
+//! ```js
+//! const interner = new JsInterner();
+//! const a = interner.intern("hello");
+//! console.log(interner.resolve(a)); // "hello"
+//! ```
+
+use wasm_bindgen::prelude::*;
+
+use crate::backend::string::Symbol;
+use crate::StringInterner;
+
+/// A [StringInterner] usable from JavaScript
+#[wasm_bindgen]
+#[derive(Default)]
+pub struct JsInterner {
+    inner: StringInterner,
+}
+
+#[wasm_bindgen]
+impl JsInterner {
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Interns `s`, returning its symbol
+    pub fn intern(&mut self, s: &str) -> u64 {
+        self.inner.get_or_intern(s).as_u64()
+    }
+
+    /// Returns the symbol for `s`, if it's been interned
+    pub fn get(&self, s: &str) -> Option<u64> {
+        self.inner.get(s).map(|sym| sym.as_u64())
+    }
+
+    /// Resolves `sym` into a string
+    pub fn resolve(&self, sym: u64) -> Option<String> {
+        self.inner.resolve(Symbol::from_u64(sym)).map(String::from)
+    }
+
+    /// Number of unique strings interned
+    #[wasm_bindgen(getter)]
+    pub fn len(&self) -> usize {
+        self.inner.len()
+    }
+
+    #[wasm_bindgen(js_name = isEmpty)]
+    pub fn is_empty(&self) -> bool {
+        self.inner.is_empty()
+    }
+}
@@ -0,0 +1,115 @@
+//! Streaming values into and out of the interner
+
+use core::cell::Cell;
+use core::fmt::{self, Display, Write};
+use core::hash::{BuildHasher, Hash};
+use std::io;
+
+use crate::backend::Internable;
+use crate::{Backend, Interner};
+
+thread_local! {
+    /// Buffer to render [Display] values before interning them
+    static SCRATCH: Cell<String> = const { Cell::new(String::new()) };
+}
+
+/// Scratch buffers bigger than this are freed after use
+const MAX_SCRATCH: usize = 4096;
+
+impl<T, B, H> Interner<T, B, H>
+where
+    T: Hash + Eq + ?Sized,
+    B: Backend<T>,
+    H: BuildHasher,
+{
+    /// Writes the value of `sym` into `out`
+    ///
+    /// This avoids building an intermediate [String] in code that only
+    /// has a symbol. If `sym` can't be resolved, nothing is written, and
+    /// a [fmt::Error] is returned.
+    ///
+    /// # Example
+    /// ```
+    /// use interns::StringInterner;
+    ///
+    /// let mut interner = StringInterner::new();
+    /// let sym = interner.get_or_intern("world");
+    ///
+    /// let mut out = String::from("hello ");
+    /// interner.write_to(sym, &mut out).unwrap();
+    /// assert_eq!(out, "hello world");
+    /// ```
+    pub fn write_to<W>(&self, sym: B::Symbol, out: &mut W) -> fmt::Result
+    where
+        T: Display,
+        W: fmt::Write + ?Sized,
+    {
+        let value = self.resolve(sym).ok_or(fmt::Error)?;
+        write!(out, "{value}")
+    }
+
+    /// Writes the bytes of the value of `sym` into `out`
+    ///
+    /// If `sym` can't be resolved, nothing is written, and an
+    /// error of kind [InvalidInput](io::ErrorKind::InvalidInput) is returned.
+    ///
+    /// # Example
+    /// ```
+    /// use interns::Interner;
+    ///
+    /// let mut interner = Interner::<[u8]>::new();
+    /// let sym = interner.get_or_intern(b"\x7fELF".as_slice());
+    ///
+    /// let mut out = Vec::new();
+    /// interner.write_bytes_to(sym, &mut out).unwrap();
+    /// assert_eq!(out, b"\x7fELF");
+    /// ```
+    pub fn write_bytes_to<W>(&self, sym: B::Symbol, out: &mut W) -> io::Result<()>
+    where
+        T: AsRef<[u8]>,
+        W: io::Write + ?Sized,
+    {
+        let value = self
+            .resolve(sym)
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "unknown symbol"))?;
+        out.write_all(value.as_ref())
+    }
+}
+
+impl<B, H> Interner<str, B, H>
+where
+    B: Backend<str>,
+    H: BuildHasher,
+    str: Internable<str, B>,
+{
+    /// Gets the symbol for the [Display] representation of `value`,
+    /// interning it if it doesn't exist
+    ///
+    /// The value is rendered into a scratch buffer that's reused between
+    /// calls, so no temporary [String] is allocated for each value.
+    ///
+    /// # Example
+    /// ```
+    /// use interns::StringInterner;
+    ///
+    /// let mut interner = StringInterner::new();
+    /// let n = interner.get_or_intern_display(&42);
+    /// assert_eq!(interner.get("42"), Some(n));
+    ///
+    /// let name = format_args!("tmp_{}", 7);
+    /// let tmp = interner.get_or_intern_display(&name);
+    /// assert_eq!(interner.resolve(tmp), Some("tmp_7"));
+    /// ```
+    pub fn get_or_intern_display<D: Display + ?Sized>(&mut self, value: &D) -> B::Symbol {
+        /* The buffer is taken out of the thread local, instead of borrowed,
+         * in case `value`'s Display implementation also interns something */
+        let mut buf = SCRATCH.take();
+        buf.clear();
+        write!(buf, "{value}").expect("a Display implementation returned an error unexpectedly");
+        let sym = self.get_or_intern(buf.as_str());
+        if buf.capacity() <= MAX_SCRATCH {
+            SCRATCH.set(buf);
+        }
+        sym
+    }
+}